@@ -51,6 +51,8 @@ mod bevy_bench {
 			insert_entities(ITER_COUNT);
 		}
 	
+		// Spawns all entities with their components in one spawn_batch call, instead of
+		// moving every entity to a new archetype for each inserted component
 		pub fn run_batched(&mut self) {
 			let mut world = World::new();
 			world.spawn_batch((0..ITER_COUNT).map(|_| {
@@ -117,6 +119,8 @@ mod hecs_bench {
 			insert_entities(ITER_COUNT);
 		}
 
+		// Spawns all entities with one spawn_batch call, which reserves the rows of the
+		// archetype up front instead of moving every entity for each inserted component
 		pub fn run_batched(&mut self) {
 			let mut world = World::new();
 			world.spawn_batch((0..ITER_COUNT).map(|_| {
@@ -185,8 +189,24 @@ mod flecs_bench {
 			insert_entities(ITER_COUNT);
 		}
 
-		// TODO
-		pub fn _run_batched(&mut self) {
+		// Copies the columns in with one ecs_bulk_init call instead of a set per component
+		// per entity
+		pub fn run_batched(&mut self) {
+			let mut world = World::new();
+			world.component::<Position>();
+			world.component::<Rotation>();
+			world.component::<Velocity>();
+
+			let positions = vec![Position::default(); ITER_COUNT];
+			let rotations = vec![Rotation::default(); ITER_COUNT];
+			let velocities = vec![Velocity { x: 1.0, y: 1.0, z: 1.0 }; ITER_COUNT];
+			world.bulk::<(Position, Rotation, Velocity)>()
+				.count(ITER_COUNT)
+				.column(&positions)
+				.column(&rotations)
+				.column(&velocities)
+				.build()
+				.unwrap();
 		}
 	}	
 
@@ -199,8 +219,8 @@ mod flecs_bench {
 		}
 	
 		pub fn run_each(&mut self) {
-			// while more friendly to the user, this is 20x slower compared to iter()
-			//		until we can get some performance improvements related to Tuples use.
+			// each used to fetch every field per entity, now the field pointers are fetched
+			// once per table
 			let filter = self.0.filter::<(Position, Velocity)>();
 			filter.each_mut(|_e, (position, velocity)| {
 				position.x += velocity.x;
@@ -210,12 +230,29 @@ mod flecs_bench {
 		}
 
 		pub fn run_iter(&mut self) {
+			let f = self.0.filter_builder().with_components::<(Position, Velocity)>().build();
+			f.iter_mut(|it| {
+				let velocities = it.field::<Velocity>(2);
+				let positions = it.field_slice_mut::<Position>(1);
+
+				for (i, p) in positions.iter_mut().enumerate() {
+					let v = velocities.get(i);
+					p.x += v.x;
+					p.y += v.y;
+					p.z += v.z;
+				}
+			});
+		}
+
+		// Same as run_iter, also looking up the entity handle for every element
+		pub fn run_iter_entity(&mut self) {
 			let f = self.0.filter_builder().with_components::<(Position, Velocity)>().build();
 			f.iter(|it| {
 				let positions = it.field::<Position>(1);
 				let velocities = it.field::<Velocity>(2);
 
 				for i in 0..it.count() {
+					black_box(it.entity(i as i32));
 					let p = positions.get_mut(i);
 					let v = velocities.get(i);
 					p.x += v.x;
@@ -225,6 +262,146 @@ mod flecs_bench {
 			});
 		}
 	}
+
+	pub struct SimpleEach(World);
+
+	impl SimpleEach {
+		pub fn new() -> Self {
+			Self(insert_entities(ITER_COUNT))
+		}
+
+		pub fn run_each1(&mut self) {
+			self.0.each1(|e, p: &Position| {
+				black_box((e, p.x));
+			});
+		}
+
+		pub fn run_each_term(&mut self) {
+			self.0.each_term(|e, p: &Position| {
+				black_box((e, p.x));
+			});
+		}
+	}
+
+	// each1 over a small world for many frames, where creating the filter dominates
+	pub struct FilterCache(World);
+
+	impl FilterCache {
+		const FRAMES: usize = 1000;
+
+		pub fn new() -> Self {
+			let mut world = World::new();
+			world.component::<Position>();
+			for _ in 0..100 {
+				world.entity().set(Position::default());
+			}
+			Self(world)
+		}
+
+		// What each1 did before filters were cached: create, iterate and free every frame
+		pub fn run_cold(&mut self) {
+			let world = self.0.raw();
+			let mut desc: ecs_filter_desc_t = unsafe { std::mem::MaybeUninit::zeroed().assume_init() };
+			desc.terms[0].id = self.0.id::<Position>().unwrap().id();
+			for _ in 0..Self::FRAMES {
+				unsafe {
+					let filter = ecs_filter_init(world, &desc);
+					let mut it = ecs_filter_iter(world, filter);
+					while ecs_filter_next(&mut it) {
+						let p = ecs_field::<Position>(&it, 1);
+						for i in 0..it.count as isize {
+							black_box((*p.offset(i)).x);
+						}
+					}
+					ecs_filter_fini(filter);
+				}
+			}
+		}
+
+		pub fn run_cached(&mut self) {
+			for _ in 0..Self::FRAMES {
+				self.0.each1(|_, p: &Position| {
+					black_box(p.x);
+				});
+			}
+		}
+	}
+
+	pub struct SimpleSystem(World);
+
+	impl SimpleSystem {
+		pub fn new_each() -> Self {
+			let world = insert_entities(ITER_COUNT);
+			world.system()
+				.with_components::<(Position, Velocity)>()
+				.for_each::<(&mut Position, &Velocity)>(|_e, (position, velocity)| {
+					position.x += velocity.x;
+					position.y += velocity.y;
+					position.z += velocity.z;
+				});
+			Self(world)
+		}
+
+		pub fn new_iter() -> Self {
+			let world = insert_entities(ITER_COUNT);
+			world.system()
+				.with_components::<(Position, Velocity)>()
+				.iter(|it| {
+					let positions = it.field::<Position>(1);
+					let velocities = it.field::<Velocity>(2);
+
+					for i in 0..it.count() {
+						let p = positions.get_mut(i);
+						let v = velocities.get(i);
+						p.x += v.x;
+						p.y += v.y;
+						p.z += v.z;
+					}
+				});
+			Self(world)
+		}
+
+		pub fn run(&mut self) {
+			self.0.progress(0.0);
+		}
+	}
+
+	pub struct SimpleGet {
+		_world: World,
+		entities: Vec<Entity>,
+		refs: Vec<Ref<Position>>,
+	}
+
+	impl SimpleGet {
+		pub fn new() -> Self {
+			let mut world = World::new();
+			world.component::<Position>();
+			world.component::<Velocity>();
+
+			let entities: Vec<Entity> = (0..ITER_COUNT).map(|_| {
+				world.entity()
+					.set(Position::default())
+					.set(Velocity { x: 1.0, y: 1.0, z: 1.0 })
+			}).collect();
+			let refs = entities.iter().map(|e| e.get_ref::<Position>()).collect();
+
+			Self { _world: world, entities, refs }
+		}
+
+		pub fn run_get(&mut self) {
+			for e in &self.entities {
+				let p = e.get::<Position>();
+				black_box(p.x);
+			}
+		}
+
+		pub fn run_ref(&mut self) {
+			for r in &mut self.refs {
+				let p = r.get().unwrap();
+				black_box(p.x);
+			}
+		}
+	}
 }
 
 fn bench_simple_insert(c: &mut Criterion) {
@@ -249,6 +426,10 @@ fn bench_simple_insert(c: &mut Criterion) {
         let mut bench = flecs_bench::SimpleInsert::new();
         b.iter(move || bench.run());
     });
+    group.bench_function("flecs_batched", |b| {
+        let mut bench = flecs_bench::SimpleInsert::new();
+        b.iter(move || bench.run_batched());
+    });
 }
 
 fn bench_simple_iter(c: &mut Criterion) {
@@ -269,11 +450,67 @@ fn bench_simple_iter(c: &mut Criterion) {
         let mut bench = flecs_bench::SimpleIter::new();
         b.iter(move || bench.run_iter());
     });
+    group.bench_function("flecs_iter_entity", |b| {
+        let mut bench = flecs_bench::SimpleIter::new();
+        b.iter(move || bench.run_iter_entity());
+    });
+}
+
+fn bench_simple_each(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simple_each");
+    group.bench_function("flecs_each1", |b| {
+        let mut bench = flecs_bench::SimpleEach::new();
+        b.iter(move || bench.run_each1());
+    });
+    group.bench_function("flecs_each_term", |b| {
+        let mut bench = flecs_bench::SimpleEach::new();
+        b.iter(move || bench.run_each_term());
+    });
+}
+
+fn bench_filter_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter_cache");
+    group.bench_function("flecs_each1_cold", |b| {
+        let mut bench = flecs_bench::FilterCache::new();
+        b.iter(move || bench.run_cold());
+    });
+    group.bench_function("flecs_each1_cached", |b| {
+        let mut bench = flecs_bench::FilterCache::new();
+        b.iter(move || bench.run_cached());
+    });
+}
+
+fn bench_simple_system(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simple_system");
+    group.bench_function("flecs_each", |b| {
+        let mut bench = flecs_bench::SimpleSystem::new_each();
+        b.iter(move || bench.run());
+    });
+    group.bench_function("flecs_iter", |b| {
+        let mut bench = flecs_bench::SimpleSystem::new_iter();
+        b.iter(move || bench.run());
+    });
+}
+
+fn bench_simple_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simple_get");
+    group.bench_function("flecs_get", |b| {
+        let mut bench = flecs_bench::SimpleGet::new();
+        b.iter(move || bench.run_get());
+    });
+    group.bench_function("flecs_ref", |b| {
+        let mut bench = flecs_bench::SimpleGet::new();
+        b.iter(move || bench.run_ref());
+    });
 }
 
 criterion_group!(
     benchmarks,
     bench_simple_insert,
     bench_simple_iter,
+    bench_simple_each,
+    bench_filter_cache,
+    bench_simple_system,
+    bench_simple_get,
 );
 criterion_main!(benchmarks);
\ No newline at end of file
@@ -0,0 +1,41 @@
+use flecs::*;
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+struct Position {
+	x: f32,
+	y: f32,
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+struct Velocity {
+	x: f32,
+	y: f32,
+}
+
+fn main() {
+	let mut world = World::new();
+	world.component::<Position>();
+	world.component::<Velocity>();
+
+	world.system()
+		.with_components::<(Position, Velocity)>()
+		.for_each::<(&mut Position, &Velocity)>(|e, (pos, vel)| {
+			pos.x += vel.x;
+			pos.y += vel.y;
+			println!("  {}: {:?}", e.name(), pos);
+		});
+
+	// The app takes over the world, runs the main loop and destroys the world when done.
+	// Use .enable_rest(true) and drop .frames to inspect the world with the explorer.
+	let result = world.app()
+		.target_fps(60.0)
+		.frames(3)
+		.init(|world| {
+			world.entity().named("Bob")
+				.set(Position { x: 0.0, y: 0.0 })
+				.set(Velocity { x: 1.0, y: 2.0 });
+		})
+		.run();
+
+	std::process::exit(result);
+}
@@ -40,7 +40,7 @@ fn main() {
 
 	// The each() function iterates each entity individually and accepts an
     // entity argument plus arguments for each query component as a tuple:
-    q.each_mut::<(Position, Velocity)>(|e, (p, v)| {
+    q.for_each::<(&mut Position, &Velocity)>(|e, (p, v)| {
         p.x += v.x;
         p.y += v.y;
 		println!("Each - {}: {:?}, {:?}", e.name(), p, v);
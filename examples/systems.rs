@@ -66,12 +66,12 @@ fn main() {
 	// Can wire a function into a system
 	world.system().named("system_one")
 		.expr("Position, Velocity, !Scale")
-		.each_mut::<(Position, Velocity)>(system_one);
+		.for_each::<(&mut Position, &mut Velocity)>(system_one);
 
 	// Or pass a closure directly
 	world.system().named("system_two")
 		.expr("Position, Scale")
-		.each_mut::<(Position, Scale)>(|e, (pos, s)| {
+		.for_each::<(&mut Position, &mut Scale)>(|e, (pos, s)| {
 			println!("Sys2 - {}: {:?}, {:?}", e.name(), pos, s);
 		});
 
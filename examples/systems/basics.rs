@@ -20,7 +20,7 @@ fn main() {
 	// Can wire a function into a system
 	let system = world.system()
 		.expr("Position, Velocity")
-		.each_mut::<(Position, Velocity)>(|e, (p, v)| {
+		.for_each::<(&mut Position, &Velocity)>(|e, (p, v)| {
             p.x += v.x;
             p.y += v.y;
 			println!("{}: {:?}, {:?}", e.name(), p, v);
@@ -0,0 +1,144 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Lit, Meta, NestedMeta};
+
+// Generates flecs::ComponentType and flecs::PairData impls for the type
+//
+// Supported attributes:
+//	#[flecs(name = "Position")]		name registered with flecs, defaults to the type name
+//	#[flecs(symbol = "game.Position")]	symbol registered with flecs, defaults to the name
+//	#[flecs(tag)]					asserts the type carries no data, pairs with it as
+//									relation take their data from the target
+//	#[flecs(meta)]					registers the fields as members for reflection (JSON,
+//									explorer), requires #[repr(C)] and named fields
+//
+// World::component uses the name and symbol once the type was registered with
+// World::component_type, or as a member of a #[flecs(meta)] type, in any world.
+//
+#[proc_macro_derive(Component, attributes(flecs))]
+pub fn derive_component(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match impl_component(&input) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
+fn impl_component(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+	let ident = &input.ident;
+	let mut name = ident.to_string();
+	let mut symbol = None;
+	let mut is_tag = false;
+	let mut meta = false;
+
+	for attr in input.attrs.iter().filter(|a| a.path.is_ident("flecs")) {
+		let list = match attr.parse_meta()? {
+			Meta::List(list) => list,
+			other => return Err(syn::Error::new_spanned(other, "expected #[flecs(...)]")),
+		};
+
+		for nested in list.nested.iter() {
+			match nested {
+				NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+					name = lit_str(&nv.lit)?;
+				}
+				NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("symbol") => {
+					symbol = Some(lit_str(&nv.lit)?);
+				}
+				NestedMeta::Meta(Meta::Path(path)) if path.is_ident("tag") => {
+					is_tag = true;
+				}
+				NestedMeta::Meta(Meta::Path(path)) if path.is_ident("meta") => {
+					meta = true;
+				}
+				other => return Err(syn::Error::new_spanned(other, "unknown flecs attribute")),
+			}
+		}
+	}
+
+	if is_tag && has_fields(&input.data) {
+		return Err(syn::Error::new_spanned(ident, "a flecs tag cannot have fields"));
+	}
+
+	let symbol = symbol.unwrap_or_else(|| name.clone());
+	let register_members = if meta { impl_register_members(input)? } else { quote!() };
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let mut pair_generics = input.generics.clone();
+	pair_generics.params.push(syn::parse_quote!(__FlecsTarget: 'static));
+	let (pair_impl_generics, _, _) = pair_generics.split_for_impl();
+	let pair_data = if is_tag { quote!(__FlecsTarget) } else { quote!(#ident #ty_generics) };
+
+	Ok(quote! {
+		impl #impl_generics ::flecs::ComponentType for #ident #ty_generics #where_clause {
+			const NAME: &'static str = #name;
+			const SYMBOL: &'static str = #symbol;
+			const IS_TAG: bool = #is_tag;
+			#register_members
+		}
+
+		impl #pair_impl_generics ::flecs::PairData<__FlecsTarget> for #ident #ty_generics #where_clause {
+			type Data = #pair_data;
+		}
+	})
+}
+
+fn impl_register_members(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+	let is_repr_c = input.attrs.iter()
+		.filter(|a| a.path.is_ident("repr"))
+		.any(|a| a.tokens.to_string().contains('C'));
+	if !is_repr_c {
+		return Err(syn::Error::new_spanned(&input.ident, "#[flecs(meta)] requires #[repr(C)], flecs computes the member offsets with C layout"));
+	}
+
+	let fields = match &input.data {
+		Data::Struct(s) => match &s.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => return Err(syn::Error::new_spanned(&input.ident, "#[flecs(meta)] requires named fields")),
+		},
+		_ => return Err(syn::Error::new_spanned(&input.ident, "#[flecs(meta)] requires a struct")),
+	};
+
+	// member types which derive Component get their names when registered by member()
+	let records = fields.iter().map(|field| {
+		let ty = match &field.ty {
+			syn::Type::Array(array) => &array.elem,
+			ty => ty,
+		};
+		quote!((&::flecs::__MemberType::<#ty>(::std::marker::PhantomData)).record();)
+	});
+
+	let members = fields.iter().map(|field| {
+		let name = field.ident.as_ref().unwrap().to_string();
+		match &field.ty {
+			syn::Type::Array(array) => {
+				let elem = &array.elem;
+				let len = &array.len;
+				quote!(.member_array::<#elem>(#name, (#len) as i32))
+			}
+			ty => quote!(.member::<#ty>(#name)),
+		}
+	});
+
+	Ok(quote! {
+		fn register_members(component: ::flecs::Entity) {
+			use ::flecs::{__RecordDerived as _, __RecordOther as _};
+			#(#records)*
+			component #(#members)*;
+		}
+	})
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+	match lit {
+		Lit::Str(s) => Ok(s.value()),
+		other => Err(syn::Error::new_spanned(other, "expected a string literal")),
+	}
+}
+
+fn has_fields(data: &Data) -> bool {
+	match data {
+		Data::Struct(s) => !matches!(s.fields, Fields::Unit) && !s.fields.is_empty(),
+		Data::Enum(_) | Data::Union(_) => true,
+	}
+}
@@ -0,0 +1,94 @@
+use crate::*;
+
+// App addon support
+//
+// Runs the main loop with the settings of ecs_app_desc_t. flecs 3.0 has no frame limit,
+// so the loop is driven from here, one ecs_app_run_frame call per frame.
+
+type InitFn = Box<dyn FnOnce(&World)>;
+
+pub struct AppBuilder {
+	world: World,
+	desc: ecs_app_desc_t,
+	frames: i32,
+	init: Option<InitFn>,
+}
+
+impl AppBuilder {
+	pub(crate) fn new(world: World) -> Self {
+		let desc: ecs_app_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		AppBuilder { world, desc, frames: 0, init: None }
+	}
+
+	/// 0 runs frames as fast as possible
+	pub fn target_fps(mut self, fps: f32) -> Self {
+		self.desc.target_fps = fps;
+		self
+	}
+
+	/// Fixed frame time, 0 (the default) measures the time between frames
+	pub fn delta_time(mut self, delta_time: f32) -> Self {
+		self.desc.delta_time = delta_time;
+		self
+	}
+
+	/// Stops after the number of frames, 0 runs until World::quit is called
+	pub fn frames(mut self, frames: i32) -> Self {
+		self.frames = frames;
+		self
+	}
+
+	pub fn threads(mut self, threads: i32) -> Self {
+		self.desc.threads = threads;
+		self
+	}
+
+	/// Starts the REST server on the default port, so the explorer can connect
+	pub fn enable_rest(mut self, enable: bool) -> Self {
+		self.desc.enable_rest = enable;
+		self
+	}
+
+	/// Called once before the first frame
+	pub fn init(mut self, init: impl FnOnce(&World) + 'static) -> Self {
+		self.init = Some(Box::new(init));
+		self
+	}
+
+	/// Runs the main loop and destroys the world when it ends. Returns 0 when the loop ended
+	/// normally, -1 if the REST server couldn't be started, or the error code of the frame action.
+	pub fn run(self) -> i32 {
+		let AppBuilder { world, desc, frames, init } = self;
+
+		if desc.enable_rest && world.enable_rest_with(RestOptions::default()).is_err() {
+			return -1;
+		}
+
+		unsafe {
+			ecs_set_target_fps(world.raw(), desc.target_fps);
+			ecs_set_threads(world.raw(), desc.threads);
+		}
+
+		if let Some(init) = init {
+			init(&world);
+		}
+
+		let mut frame = 0;
+		loop {
+			let result = unsafe { ecs_app_run_frame(world.raw(), &desc) };
+			frame += 1;
+			if result == 1 || (frames > 0 && frame >= frames) {
+				return 0;
+			} else if result != 0 {
+				return result;
+			}
+		}
+	}
+}
+
+impl World {
+	/// Creates an app, which takes over the world and runs its main loop
+	pub fn app(self) -> AppBuilder {
+		AppBuilder::new(self)
+	}
+}
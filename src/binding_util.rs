@@ -23,9 +23,43 @@ pub unsafe fn ecs_iter_column<T: Component>(it: *const ecs_iter_t, index: i32) -
     ecs_iter_column_w_size(it, size as size_t, index) as *mut T
 }
 
+// Iterates in deferred mode, like systems do. Structural changes made by the callbacks
+// (e.g. deleting the iterated entities) are applied once the iteration is done instead
+// of moving the table data that is being iterated.
+pub(crate) unsafe fn defer_iter(world: *mut ecs_world_t, iter: impl FnOnce()) {
+    // ends deferred mode when a callback panics too, flecs asserts at fini otherwise
+    struct DeferGuard(*mut ecs_world_t);
+    impl Drop for DeferGuard {
+        fn drop(&mut self) {
+            unsafe { ecs_defer_end(self.0) };
+        }
+    }
+
+    ecs_defer_begin(world);
+    let _guard = DeferGuard(world);
+    iter();
+}
+
+pub(crate) unsafe fn ecs_os_free(ptr: *mut ::std::os::raw::c_void) {
+    if let Some(free) = ecs_os_api.free_ {
+        free(ptr);
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////////
 // String helpers
 
+// Copies a string allocated by flecs into an owned String and frees the original
+pub(crate) unsafe fn flecs_to_owned_string(cstr: *mut ::std::os::raw::c_char) -> String {
+    if cstr.is_null() {
+        return String::new();
+    }
+
+    let r_str = std::ffi::CStr::from_ptr(cstr).to_string_lossy().into_owned();
+    ecs_os_free(cstr as *mut ::std::os::raw::c_void);
+    r_str
+}
+
 pub unsafe fn flecs_to_rust_str(cstr: *const ::std::os::raw::c_char) -> &'static str {
     if cstr.is_null() {
         return "";
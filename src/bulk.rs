@@ -0,0 +1,117 @@
+use crate::*;
+use crate::cache::WorldInfoCache;
+use std::marker::PhantomData;
+
+// Bulk creation of entities with component data
+//
+// All entities end up in the same table, the columns are copied in with a single
+// ecs_bulk_init call instead of setting every component of every entity.
+
+/// Returned by BulkBuilder::build when the columns don't match the group or count
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkError {
+	/// The component type isn't registered with the world
+	NotRegistered { component: &'static str },
+	/// A column was provided for a component that isn't in the group, or twice
+	NotInGroup { component: &'static str },
+	/// No column was provided for a component of the group
+	MissingColumn { index: usize },
+	LengthMismatch { component: &'static str, expected: usize, found: usize },
+}
+
+impl std::fmt::Display for BulkError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BulkError::NotRegistered { component } =>
+				write!(f, "Component {} is not registered", component),
+			BulkError::NotInGroup { component } =>
+				write!(f, "Component {} is not in the group or its column was provided twice", component),
+			BulkError::MissingColumn { index } =>
+				write!(f, "No column was provided for component {} of the group", index),
+			BulkError::LengthMismatch { component, expected, found } =>
+				write!(f, "Column of {} has {} values, expected {}", component, found, expected),
+		}
+	}
+}
+
+impl std::error::Error for BulkError {}
+
+struct BulkColumn {
+	component: &'static str,
+	id: Option<ecs_entity_t>,
+	data: *const ::std::os::raw::c_void,	// null for tags, which have no storage
+	len: usize,
+}
+
+/// Creates count entities with the components of G, see World::bulk
+pub struct BulkBuilder<'a, G> {
+	world: *mut ecs_world_t,
+	count: usize,
+	columns: Vec<BulkColumn>,
+	_phantom: PhantomData<(&'a [u8], G)>,
+}
+
+impl<'a, G: ComponentGroup<'a>> BulkBuilder<'a, G> {
+	pub(crate) fn new(world: *mut ecs_world_t) -> Self {
+		Self { world, count: 0, columns: Vec::new(), _phantom: PhantomData }
+	}
+
+	pub fn count(mut self, count: usize) -> Self {
+		self.count = count;
+		self
+	}
+
+	/// Provides the values of one of the components, one per entity. The values are
+	/// copied bitwise into the table, hence T must be Copy.
+	pub fn column<T: Component + Copy>(mut self, data: &'a [T]) -> Self {
+		self.columns.push(BulkColumn {
+			component: std::any::type_name::<T>(),
+			id: WorldInfoCache::get_component_id_for_type::<T>(self.world),
+			data: if std::mem::size_of::<T>() == 0 { std::ptr::null() } else { data.as_ptr() as *const ::std::os::raw::c_void },
+			len: data.len(),
+		});
+		self
+	}
+
+	/// Creates the entities, every component of G needs a column of count values
+	pub fn build(self) -> Result<Vec<EntityId>, BulkError> {
+		let ids = G::try_component_ids(self.world).map_err(|component| BulkError::NotRegistered { component })?;
+
+		let mut data: Vec<*mut ::std::os::raw::c_void> = vec![std::ptr::null_mut(); ids.len()];
+		let mut provided = vec![false; ids.len()];
+		for column in &self.columns {
+			let id = column.id.ok_or(BulkError::NotRegistered { component: column.component })?;
+			let index = ids.iter().position(|i| *i == id)
+				.filter(|i| !provided[*i])
+				.ok_or(BulkError::NotInGroup { component: column.component })?;
+			if column.len != self.count {
+				return Err(BulkError::LengthMismatch { component: column.component, expected: self.count, found: column.len });
+			}
+			data[index] = column.data as *mut ::std::os::raw::c_void;
+			provided[index] = true;
+		}
+		if let Some(index) = provided.iter().position(|p| !p) {
+			return Err(BulkError::MissingColumn { index });
+		}
+		if self.count == 0 {
+			return Ok(Vec::new());
+		}
+
+		let mut desc: ecs_bulk_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.count = self.count as i32;
+		desc.ids[..ids.len()].copy_from_slice(&ids);
+		desc.data = data.as_mut_ptr();
+
+		let entities = unsafe { ecs_bulk_init(self.world, &desc) };
+		assert!(!entities.is_null());
+		Ok(unsafe { std::slice::from_raw_parts(entities, self.count) }.to_vec())
+	}
+}
+
+impl World {
+	/// Creates entities with the components of G in bulk, from a column of values per
+	/// component: world.bulk::<(A, B)>().count(n).column(&a).column(&b).build()
+	pub fn bulk<'a, G: ComponentGroup<'a>>(&'a self) -> BulkBuilder<'a, G> {
+		BulkBuilder::new(self.raw())
+	}
+}
@@ -1,6 +1,6 @@
 use crate::bindings::*;
 use crate::Component;
-use std::{any::TypeId, collections::HashMap, sync::Mutex};
+use std::{any::TypeId, collections::{HashMap, HashSet}, sync::Mutex};
 
 // TODO: Revisit how we cache the runtime Component IDs per type
 //
@@ -38,7 +38,13 @@ pub(crate) struct ComponentInfo {
 pub(crate) struct WorldInfoCache
 {
 	component_typeid_map: HashMap<TypeId, u64>,
-	component_symbol_map: HashMap<&'static str, ComponentInfo>,
+	component_symbol_map: HashMap<String, ComponentInfo>,
+	// filters keyed by their term ids, freed with the world
+	filter_map: HashMap<Vec<ecs_id_t>, u64>,	//*mut ecs_filter_t
+	// callbacks passed to ecs_run_post_frame which haven't run yet
+	post_frame_actions: Vec<u64>,
+	// components with an override_on_instantiate observer
+	override_components: HashSet<ecs_entity_t>,
 }
 
 impl WorldInfoCache {
@@ -46,6 +52,9 @@ impl WorldInfoCache {
 		let cache = WorldInfoCache {
 			component_typeid_map: HashMap::new(),
 			component_symbol_map: HashMap::new(),
+			filter_map: HashMap::new(),
+			post_frame_actions: Vec::new(),
+			override_components: HashSet::new(),
 		};
 
 		let world_key = Self::key_for_world(world);
@@ -82,17 +91,80 @@ impl WorldInfoCache {
 		cache.component_typeid_map.insert(type_id, comp_id);
 	}
 
-	pub fn get_component_id_for_symbol(world: *mut ecs_world_t, symbol: &'static str) -> Option<ComponentInfo> {
+	pub fn get_component_id_for_symbol(world: *mut ecs_world_t, symbol: &str) -> Option<ComponentInfo> {
 		let world_key = Self::key_for_world(world);
 		let m = WORLD_INFOS.lock().unwrap();
 		let cache = m.get(&world_key).unwrap();
 		cache.component_symbol_map.get(symbol).map(|v| *v)
 	}
 
-	pub fn register_component_id_for_symbol(world: *mut ecs_world_t, comp_id: ecs_entity_t, symbol: &'static str, size: usize) {
+	pub fn register_component_id_for_symbol(world: *mut ecs_world_t, comp_id: ecs_entity_t, symbol: &str, size: usize) {
 		let world_key = Self::key_for_world(world);
 		let mut m = WORLD_INFOS.lock().unwrap();
 		let cache = m.get_mut(&world_key).unwrap();
-		cache.component_symbol_map.insert(symbol, ComponentInfo { id: comp_id, size });
+		cache.component_symbol_map.insert(symbol.to_owned(), ComponentInfo { id: comp_id, size });
 	}	
+
+	/// Returns the filter matching all of ids, which is created on first use and reused
+	/// until the world is destroyed
+	pub fn get_or_init_filter(world: *mut ecs_world_t, ids: &[ecs_id_t]) -> *mut ecs_filter_t {
+		let world_key = Self::key_for_world(world);
+		let mut m = WORLD_INFOS.lock().unwrap();
+		let cache = m.get_mut(&world_key).unwrap();
+		if let Some(filter) = cache.filter_map.get(ids) {
+			return *filter as *mut ecs_filter_t;
+		}
+
+		let mut desc: ecs_filter_desc_t = unsafe { std::mem::MaybeUninit::zeroed().assume_init() };
+		for (term, id) in desc.terms.iter_mut().zip(ids) {
+			term.id = *id;
+		}
+		let filter = unsafe { ecs_filter_init(world_key as *mut ecs_world_t, &desc) };
+		cache.filter_map.insert(ids.to_vec(), filter as u64);
+		filter
+	}
+
+	pub fn add_post_frame_action(world: *mut ecs_world_t, ctx: u64) {
+		let world_key = Self::key_for_world(world);
+		let mut m = WORLD_INFOS.lock().unwrap();
+		m.get_mut(&world_key).unwrap().post_frame_actions.push(ctx);
+	}
+
+	/// Returns false if the action was taken already
+	pub fn remove_post_frame_action(world: *mut ecs_world_t, ctx: u64) -> bool {
+		let world_key = Self::key_for_world(world);
+		let mut m = WORLD_INFOS.lock().unwrap();
+		let actions = &mut m.get_mut(&world_key).unwrap().post_frame_actions;
+		match actions.iter().position(|a| *a == ctx) {
+			Some(index) => {
+				actions.swap_remove(index);
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn take_post_frame_actions(world: *mut ecs_world_t) -> Vec<u64> {
+		let world_key = Self::key_for_world(world);
+		let mut m = WORLD_INFOS.lock().unwrap();
+		m.get_mut(&world_key).map(|cache| std::mem::take(&mut cache.post_frame_actions)).unwrap_or_default()
+	}
+
+	/// Returns false if the component was added already
+	pub fn add_override_component(world: *mut ecs_world_t, comp_id: ecs_entity_t) -> bool {
+		let world_key = Self::key_for_world(world);
+		let mut m = WORLD_INFOS.lock().unwrap();
+		m.get_mut(&world_key).unwrap().override_components.insert(comp_id)
+	}
+
+	/// Frees the cached filters of a world, called before it is destroyed
+	pub fn free_filters(world: *mut ecs_world_t) {
+		let world_key = Self::key_for_world(world);
+		let mut m = WORLD_INFOS.lock().unwrap();
+		if let Some(cache) = m.get_mut(&world_key) {
+			for (_, filter) in cache.filter_map.drain() {
+				unsafe { ecs_filter_fini(filter as *mut ecs_filter_t) };
+			}
+		}
+	}
 }	
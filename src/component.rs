@@ -1,4 +1,5 @@
 use std::alloc::Layout;
+use std::{collections::HashMap, sync::Mutex};
 
 use crate::*;
 use crate::cache::WorldInfoCache;
@@ -18,6 +19,23 @@ pub(crate) fn register_component_typed<T: 'static>(world: *mut ecs_world_t, name
 
 	let type_id = TypeId::of::<T>();
 	let layout = std::alloc::Layout::new::<T>();
+
+	// derived types are registered with the name and symbol of their ComponentType impl
+	let info = COMPONENT_TYPES.lock().unwrap().get(&type_id).copied();
+	if let (Some(info), None) = (info, name) {
+		assert!(!info.is_tag || layout.size() == 0, "Tag types cannot hold data!");
+		let comp_id = register_component(world, 
+			ComponentDescriptor { 
+				symbol: info.symbol.to_owned(),
+				name: info.name.to_owned(), 
+				custom_id: None,
+				layout 
+		});
+		WorldInfoCache::register_component_id_for_type_id(world, comp_id, type_id);
+		(info.register_members)(Entity::new(world, comp_id));
+		return comp_id;
+	}
+
 	let symbol = std::any::type_name::<T>().to_owned();
 
 	// Need to figure out best way to 'Auto-Name' components based on the rust type name.
@@ -48,10 +66,124 @@ pub(crate) fn register_component_typed<T: 'static>(world: *mut ecs_world_t, name
 	comp_id
 }
 
-pub(crate) fn register_component_dynamic(world: *mut ecs_world_t, symbol: &'static str, name: Option<&'static str>, layout: Layout) -> EntityId {
+// The ComponentType impls of derived types, so that component::<T>() can use them without
+// a ComponentType bound
+#[derive(Clone, Copy)]
+struct ComponentTypeInfo {
+	name: &'static str,
+	symbol: &'static str,
+	is_tag: bool,
+	register_members: fn(Entity),
+}
+
+lazy_static::lazy_static! {
+	static ref COMPONENT_TYPES: Mutex<HashMap<TypeId, ComponentTypeInfo>> = Mutex::new(HashMap::new());
+}
+
+// Registers T if it is a recorded derived type, e.g. for the member types of a derived type
+pub(crate) fn register_recorded_component_type<T: 'static>(world: *mut ecs_world_t) -> Option<EntityId> {
+	if !COMPONENT_TYPES.lock().unwrap().contains_key(&TypeId::of::<T>()) {
+		return None;
+	}
+	Some(register_component_typed::<T>(world, None))
+}
+
+// Records the ComponentType impl of T for World::component
+fn record_component_type<T: ComponentType>() {
+	let info = ComponentTypeInfo { name: T::NAME, symbol: T::SYMBOL, is_tag: T::IS_TAG, register_members: T::register_members };
+	COMPONENT_TYPES.lock().unwrap().insert(TypeId::of::<T>(), info);
+}
+
+// The member types of a #[flecs(meta)] type are recorded with the type, so that nested
+// derived types get their ComponentType names. The derive calls record() on a
+// &__MemberType<T>, which resolves to __RecordDerived if T implements ComponentType and
+// to the no-op __RecordOther otherwise.
+#[doc(hidden)]
+pub struct __MemberType<T>(pub std::marker::PhantomData<T>);
+
+#[doc(hidden)]
+pub trait __RecordDerived {
+	fn record(&self);
+}
+
+impl<T: ComponentType> __RecordDerived for __MemberType<T> {
+	fn record(&self) {
+		record_component_type::<T>();
+	}
+}
+
+#[doc(hidden)]
+pub trait __RecordOther {
+	fn record(&self);
+}
+
+impl<T> __RecordOther for &__MemberType<T> {
+	fn record(&self) {}
+}
+
+/// Registration metadata for a component type. Usually implemented with #[derive(Component)]:
+/// 
+/// ```
+/// use flecs::*;
+/// 
+/// #[derive(Component)]
+/// #[flecs(name = "Position")]
+/// struct Position { x: f32, y: f32 }
+/// 
+/// let mut world = World::new();
+/// let pos = world.component_type::<Position>();
+/// assert_eq!(pos.name(), "Position");
+/// ```
+pub trait ComponentType: 'static {
+	const NAME: &'static str;
+	const SYMBOL: &'static str;
+	const IS_TAG: bool;
+
+	/// Adds the reflection data of the members to the registered component, see
+	/// Entity::member. Generated for #[flecs(meta)].
+	fn register_members(_component: Entity) {}
+}
+
+/// Resolves which element of a (Self, T) pair holds the data: Self, unless it is a tag, in
+/// which case T. Implemented by #[derive(Component)], where #[flecs(tag)] marks tags.
+pub trait PairData<T: 'static>: 'static {
+	type Data: 'static;
+}
+
+pub(crate) fn register_component_type<T: ComponentType>(world: *mut ecs_world_t) -> EntityId {
+	record_component_type::<T>();
+	register_component_typed::<T>(world, None)
+}
+
+/// The largest alignment of dynamic components. Table columns are allocated with
+/// ecs_os_malloc, which only guarantees the alignment of the C allocator.
+pub const MAX_DYNAMIC_COMPONENT_ALIGN: usize = 16;
+
+/// Returned when a dynamic component can't be registered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicComponentError {
+	/// The layout needs a larger alignment than the table storage provides
+	OverAligned { align: usize, max: usize },
+}
+
+impl std::fmt::Display for DynamicComponentError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DynamicComponentError::OverAligned { align, max } =>
+				write!(f, "Alignment {} is above the maximum of {} for dynamic components", align, max),
+		}
+	}
+}
+
+impl std::error::Error for DynamicComponentError {}
+
+pub(crate) fn register_component_dynamic(world: *mut ecs_world_t, symbol: &str, name: Option<&str>, layout: Layout, hooks: Option<DynamicComponentHooks>) -> Result<EntityId, DynamicComponentError> {
 	// see if we already cached it
 	if let Some(comp_info) = WorldInfoCache::get_component_id_for_symbol(world, symbol) {
-		return comp_info.id;
+		return Ok(comp_info.id);
+	}
+	if layout.align() > MAX_DYNAMIC_COMPONENT_ALIGN {
+		return Err(DynamicComponentError::OverAligned { align: layout.align(), max: MAX_DYNAMIC_COMPONENT_ALIGN });
 	}
 	let comp_id = register_component(world, 
 		ComponentDescriptor { 
@@ -61,12 +193,121 @@ pub(crate) fn register_component_dynamic(world: *mut ecs_world_t, symbol: &'stat
 			layout 
 	});
 
+	if let Some(hooks) = hooks {
+		// hooks can only be attached to actual components, not tags
+		assert!(layout.size() > 0, "Cannot register hooks for a zero sized component!");
+		set_dynamic_hooks(world, comp_id, hooks);
+	}
+
 	WorldInfoCache::register_component_id_for_symbol(world, comp_id, symbol, layout.size());
-	comp_id
+	Ok(comp_id)
+}
+
+/// Lifecycle callbacks for dynamically registered components. Each callback
+/// is invoked per element with a pointer to the component storage.
+/// 
+/// Moving a component between tables is a plain byte copy (like a Rust move),
+/// so dtor is only called once for each value that was constructed.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct DynamicComponentHooks {
+	/// Initializes new storage. When not set flecs zero initializes the memory
+	pub ctor: Option<fn(*mut u8)>,
+	/// Releases any resources owned by the value
+	pub dtor: Option<fn(*mut u8)>,
+	/// Copies src into an already constructed dst. When not set the bytes are copied
+	pub copy: Option<fn(dst: *mut u8, src: *const u8)>,
+}
+
+fn set_dynamic_hooks(world: *mut ecs_world_t, comp_id: EntityId, hooks: DynamicComponentHooks) {
+	let mut type_hooks: ecs_type_hooks_t = unsafe { MaybeUninit::zeroed().assume_init() };
+	if hooks.ctor.is_some() {
+		type_hooks.ctor = Some(dynamic_ctor);
+	}
+	if hooks.dtor.is_some() {
+		type_hooks.dtor = Some(dynamic_dtor);
+		type_hooks.move_dtor = Some(dynamic_move_dtor);
+	}
+	if hooks.copy.is_some() {
+		type_hooks.copy = Some(dynamic_copy);
+	}
+	type_hooks.ctor_move_dtor = Some(dynamic_ctor_move_dtor);
+
+	// flecs owns the boxed callbacks from here on
+	type_hooks.binding_ctx = Box::into_raw(Box::new(hooks)) as *mut std::ffi::c_void;
+	type_hooks.binding_ctx_free = Some(free_dynamic_hooks);
+
+	unsafe { ecs_set_hooks_id(world, comp_id, &type_hooks) };
+}
+
+unsafe fn dynamic_hooks<'a>(type_info: *const ecs_type_info_t) -> (&'a DynamicComponentHooks, usize) {
+	let ti = type_info.as_ref().unwrap();
+	let hooks = (ti.hooks.binding_ctx as *const DynamicComponentHooks).as_ref().unwrap();
+	(hooks, ti.size as usize)
+}
+
+unsafe extern "C" fn free_dynamic_hooks(ctx: *mut std::ffi::c_void) {
+	drop(Box::from_raw(ctx as *mut DynamicComponentHooks));
+}
+
+unsafe extern "C" fn dynamic_ctor(ptr: *mut std::ffi::c_void, count: i32, type_info: *const ecs_type_info_t) {
+	let (hooks, size) = dynamic_hooks(type_info);
+	let ctor = hooks.ctor.unwrap();
+	for i in 0..count as usize {
+		ctor((ptr as *mut u8).add(i * size));
+	}
+}
+
+unsafe extern "C" fn dynamic_dtor(ptr: *mut std::ffi::c_void, count: i32, type_info: *const ecs_type_info_t) {
+	let (hooks, size) = dynamic_hooks(type_info);
+	let dtor = hooks.dtor.unwrap();
+	for i in 0..count as usize {
+		dtor((ptr as *mut u8).add(i * size));
+	}
+}
+
+unsafe extern "C" fn dynamic_copy(dst: *mut std::ffi::c_void, src: *const std::ffi::c_void, count: i32, type_info: *const ecs_type_info_t) {
+	let (hooks, size) = dynamic_hooks(type_info);
+	let copy = hooks.copy.unwrap();
+	for i in 0..count as usize {
+		copy((dst as *mut u8).add(i * size), (src as *const u8).add(i * size));
+	}
+}
+
+// dst is constructed, so it must be dropped before the bytes of src move in
+unsafe extern "C" fn dynamic_move_dtor(dst: *mut std::ffi::c_void, src: *mut std::ffi::c_void, count: i32, type_info: *const ecs_type_info_t) {
+	dynamic_dtor(dst, count, type_info);
+	dynamic_ctor_move_dtor(dst, src, count, type_info);
+}
+
+// dst is uninitialized, src is not touched again after the move
+unsafe extern "C" fn dynamic_ctor_move_dtor(dst: *mut std::ffi::c_void, src: *mut std::ffi::c_void, count: i32, type_info: *const ecs_type_info_t) {
+	let size = (*type_info).size as usize;
+	std::ptr::copy_nonoverlapping(src as *const u8, dst as *mut u8, size * count as usize);
 }
 
-// Looks up the EcsComponent data on a Component entity
+// Looks up the EcsComponent data on a Component entity. A pair gets the data type of its
+// first element if that is a component with data, otherwise of its second element.
 pub(crate) fn get_component_info(world: *mut ecs_world_t, comp_e: ecs_entity_t) -> Option<EcsComponent> {
+	if is_pair(comp_e) {
+		let typeid = unsafe { ecs_get_typeid(world, comp_e) };
+		if typeid != 0 {
+			return get_component_info(world, typeid);
+		}
+
+		// the pair has no id record yet. Relations with the Tag property never have data.
+		let first = unsafe { ecs_get_alive(world, pair_first(comp_e)) };
+		if first != 0 {
+			if unsafe { ecs_has_id(world, first, EcsTag) } {
+				return None;
+			}
+			if let Some(info) = get_component_info(world, first).filter(|info| info.size != 0) {
+				return Some(info);
+			}
+		}
+		let second = unsafe { ecs_get_alive(world, pair_second(comp_e)) };
+		return if second != 0 { get_component_info(world, second).filter(|info| info.size != 0) } else { None };
+	}
+
 	// flecs stores info about components (size, align) within the world
 	// these are built-in components which we can acess via special component ids
 	let id = unsafe { FLECS__EEcsComponent as u64 };
@@ -80,6 +321,64 @@ pub(crate) fn get_component_info(world: *mut ecs_world_t, comp_e: ecs_entity_t)
 	Some(c.clone())
 }
 
+/// A component registered with the world, see World::components
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentInfo {
+	pub entity: Entity,
+	pub name: String,
+	pub symbol: String,
+	pub size: usize,
+	pub alignment: usize,
+}
+
+// Builtin components live in the flecs root module, e.g. flecs::core::Identifier
+pub(crate) fn is_flecs_builtin(world: *mut ecs_world_t, mut entity: ecs_entity_t) -> bool {
+	unsafe {
+		while entity != 0 {
+			if entity == EcsFlecs {
+				return true;
+			}
+			entity = ecs_get_target(world, entity, EcsChildOf, 0);
+		}
+	}
+	false
+}
+
+impl World {
+	/// Returns every component with data registered with the world, typed and dynamic.
+	/// Tags have no size and aren't included. Builtin flecs components (e.g. the ones of
+	/// the core and meta modules) are only included with include_builtin.
+	pub fn components(&self, include_builtin: bool) -> impl Iterator<Item = ComponentInfo> {
+		let world = self.raw();
+		let mut desc: ecs_filter_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.terms[0].id = unsafe { FLECS__EEcsComponent };
+
+		let mut components = Vec::new();
+		unsafe {
+			let filter = ecs_filter_init(world, &desc);
+			let mut it = ecs_filter_iter(world, filter);
+			while ecs_filter_next(&mut it) {
+				for i in 0..it.count as isize {
+					let entity = Entity::new(world, *it.entities.offset(i));
+					if !include_builtin && is_flecs_builtin(world, entity.raw()) {
+						continue;
+					}
+					let info = get_component_info(world, entity.raw()).unwrap();
+					components.push(ComponentInfo {
+						entity,
+						name: entity.name().to_owned(),
+						symbol: entity.symbol().to_owned(),
+						size: info.size as usize,
+						alignment: info.alignment as usize,
+					});
+				}
+			}
+			ecs_filter_fini(filter);
+		}
+		components.into_iter()
+	}
+}
+
 #[derive(Debug)]
 pub struct ComponentDescriptor {
 	pub symbol: String, 
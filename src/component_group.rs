@@ -29,22 +29,52 @@ pub trait ComponentGroup<'c>: /*private::SealedComponentGroup +*/ Sized + 'stati
     type RefTuple: 'c;
     type MutRefTuple: 'c;
 
+    /// The number of components (and terms) in the group
+    const COUNT: usize;
+
     fn populate(target: &mut impl TermBuilder);
 
     /// Populates an ecs_filter_desc with the component type ids
     unsafe fn fill_descriptor(world: *mut ecs_world_t, desc: &mut ecs_filter_desc_t);
 
+    /// The component ids of the group in order, or the name of the first type which isn't
+    /// registered with the world
+    fn try_component_ids(world: *mut ecs_world_t) -> Result<Vec<ecs_entity_t>, &'static str>;
+
     /// Assembles a component tuple from an active iterator
     unsafe fn iter_as_ref_tuple(it: &ecs_iter_t, i: isize) -> Self::RefTuple;
 
     /// Assembles a mutable component tuple from an active iterator
     unsafe fn iter_as_mut_tuple(it: &ecs_iter_t, i: isize) -> Self::MutRefTuple;
+
+    /// The field pointers of the current result, fetched once per table so that per
+    /// entity access is only an offset
+    type Columns: Copy;
+
+    /// Fetches the field pointers of the current result of an active iterator
+    ///
+    /// # Safety
+    /// The first fields of the iterator must match the components of the group
+    unsafe fn fetch_columns(it: &ecs_iter_t) -> Self::Columns;
+
+    /// Assembles a component tuple from fetched columns
+    ///
+    /// # Safety
+    /// i must be less than the entity count of the result the columns were fetched for
+    unsafe fn columns_as_ref_tuple(columns: Self::Columns, i: isize) -> Self::RefTuple;
+
+    /// Assembles a mutable component tuple from fetched columns
+    ///
+    /// # Safety
+    /// Same as columns_as_ref_tuple, and the components must not be borrowed elsewhere
+    unsafe fn columns_as_mut_tuple(columns: Self::Columns, i: isize) -> Self::MutRefTuple;
 }
 
 
 impl<'c, T: Component + SealedComponentGroup> ComponentGroup<'c> for T {
     type RefTuple = &'c T;
     type MutRefTuple = &'c mut T;
+    const COUNT: usize = 1;
 
     fn populate(target: &mut impl TermBuilder) {
 		let world = target.world();
@@ -57,6 +87,11 @@ impl<'c, T: Component + SealedComponentGroup> ComponentGroup<'c> for T {
         desc.terms[0].id = WorldInfoCache::get_component_id_for_type::<T>(world).expect("Component type not registered!");
     }
 
+    fn try_component_ids(world: *mut ecs_world_t) -> Result<Vec<ecs_entity_t>, &'static str> {
+        let id = WorldInfoCache::get_component_id_for_type::<T>(world).ok_or(std::any::type_name::<T>())?;
+        Ok(vec![id])
+    }
+
     unsafe fn iter_as_ref_tuple(it: &ecs_iter_t, i: isize) -> Self::RefTuple {
         let v = ecs_field::<T>(it, 1).offset(i as isize).as_ref().unwrap();
         &*(v)
@@ -66,6 +101,51 @@ impl<'c, T: Component + SealedComponentGroup> ComponentGroup<'c> for T {
         let v = ecs_field::<T>(it, 1).offset(i as isize).as_mut().unwrap();
         &mut *(v)
     }
+
+    type Columns = *mut T;
+
+    unsafe fn fetch_columns(it: &ecs_iter_t) -> Self::Columns {
+        ecs_field::<T>(it, 1)
+    }
+
+    unsafe fn columns_as_ref_tuple(columns: Self::Columns, i: isize) -> Self::RefTuple {
+        &*columns.offset(i)
+    }
+
+    unsafe fn columns_as_mut_tuple(columns: Self::Columns, i: isize) -> Self::MutRefTuple {
+        &mut *columns.offset(i)
+    }
+}
+
+// Calls cb for every entity of the current result of an active iterator. The field
+// pointers are fetched once per result, per entity access is only an offset.
+#[inline]
+pub(crate) unsafe fn each_ref_tuple<'c, G: ComponentGroup<'c>>(it: &ecs_iter_t, cb: &mut impl FnMut(Entity, G::RefTuple)) {
+    let columns = G::fetch_columns(it);
+    for i in 0..it.count as isize {
+        cb(Entity::new(it.world, *it.entities.offset(i)), G::columns_as_ref_tuple(columns, i));
+    }
+}
+
+#[inline]
+pub(crate) unsafe fn each_mut_tuple<'c, G: ComponentGroup<'c>>(it: &ecs_iter_t, cb: &mut impl FnMut(Entity, G::MutRefTuple)) {
+    let columns = G::fetch_columns(it);
+    for i in 0..it.count as isize {
+        cb(Entity::new(it.world, *it.entities.offset(i)), G::columns_as_mut_tuple(columns, i));
+    }
+}
+
+// Panics if the components of G don't match the ids of the first terms of an existing filter
+pub(crate) unsafe fn check_filter_components<'c, G: ComponentGroup<'c>>(world: *mut ecs_world_t, filter: *const ecs_filter_t) {
+    let count = (*filter).term_count as usize;
+    assert!(G::COUNT <= count, "Component group has more components than the filter has terms!");
+    let ids = G::try_component_ids(world).unwrap_or_else(|name| panic!("Component type {} not registered!", name));
+    let terms = std::slice::from_raw_parts((*filter).terms, G::COUNT);
+    for (index, (term, id)) in terms.iter().zip(ids).enumerate() {
+        if term.id != id {
+            panic!("Component {} of the group doesn't match the id of term {}!", index, index + 1);
+        }
+    }
 }
 
 macro_rules! impl_component_tuple {
@@ -75,6 +155,7 @@ macro_rules! impl_component_tuple {
         {
             type RefTuple = ($(&'s $elem),*);
             type MutRefTuple = ($(&'s mut $elem),*);
+            const COUNT: usize = $len;
 
             fn populate(target: &mut impl TermBuilder) {
                 let world = target.world();
@@ -90,6 +171,12 @@ macro_rules! impl_component_tuple {
                     desc.terms[$elem_idx].id = WorldInfoCache::get_component_id_for_type::<$elem>(world).expect("Component type not registered!");
                 )*
             }
+
+            fn try_component_ids(world: *mut ecs_world_t) -> Result<Vec<ecs_entity_t>, &'static str> {
+                Ok(vec![$(
+                    WorldInfoCache::get_component_id_for_type::<$elem>(world).ok_or(std::any::type_name::<$elem>())?,
+                )*])
+            }
         
             // We should be able to split this into 2 phase to gain performance (ecs_term, and the offset maths on raw ptr)
             unsafe fn iter_as_ref_tuple(it: &ecs_iter_t, i: isize) -> Self::RefTuple {
@@ -103,6 +190,26 @@ macro_rules! impl_component_tuple {
                     &mut *((ecs_field::<$elem>(it, $elem_idx + 1)) as *mut $elem).offset(i as isize).as_mut().unwrap(),
                 )*)
             }
+
+            type Columns = ($(*mut $elem),*);
+
+            unsafe fn fetch_columns(it: &ecs_iter_t) -> Self::Columns {
+                ($(
+                    ecs_field::<$elem>(it, $elem_idx + 1),
+                )*)
+            }
+
+            unsafe fn columns_as_ref_tuple(columns: Self::Columns, i: isize) -> Self::RefTuple {
+                ($(
+                    &*tuple_index!(columns, $elem_idx).offset(i),
+                )*)
+            }
+
+            unsafe fn columns_as_mut_tuple(columns: Self::Columns, i: isize) -> Self::MutRefTuple {
+                ($(
+                    &mut *tuple_index!(columns, $elem_idx).offset(i),
+                )*)
+            }
         }
     }
 }
@@ -116,6 +223,420 @@ impl_component_tuple!(3, (T1, 0), (T2, 1), (T3, 2));
 impl_component_tuple!(2, (T1, 0), (T2, 1));
 // impl_component_tuple!(1, (T1, 0));       // macro errors result
 
+/// A component access in a for_each tuple: &T, &mut T, Option<&T> or Option<&mut T>, or
+/// a pair element (Pair, PairMut, PairTarget). Option elements match entities
+/// without the component, and are None for them.
+pub trait EachElement<'c>: 'static {
+    type Item: 'c;
+    type Column: Copy;
+
+    /// Whether the element gives mutable access to the component
+    const MUTABLE: bool;
+
+    /// Fills in an empty term with the component id, makes the term optional for Option
+    /// elements and marks it as In or InOut, unless it was annotated already
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t);
+
+    /// Whether the id of a finalized term matches the component of the element
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool;
+
+    /// Fetches the field pointer of the current result, null if the field isn't set. Fields
+    /// not owned by the entities (shared or singleton) have a stride of 0.
+    ///
+    /// # Safety
+    /// The field at index must match the component of the element
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column;
+
+    /// # Safety
+    /// i must be less than the entity count of the result the column was fetched for
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item;
+}
+
+fn apply_element_term<T: Component>(world: *mut ecs_world_t, term: &mut ecs_term_t, inout: ecs_inout_kind_t, optional: bool) {
+    apply_element_term_id(term, || WorldInfoCache::get_component_id_for_type::<T>(world).expect("Component type not registered!"), inout, optional);
+}
+
+fn apply_element_term_id(term: &mut ecs_term_t, id: impl FnOnce() -> ecs_id_t, inout: ecs_inout_kind_t, optional: bool) {
+    if term.id == 0 && term.first.id == 0 {
+        term.id = id();
+    }
+    if optional && term.oper == ecs_oper_kind_t_EcsAnd {
+        term.oper = ecs_oper_kind_t_EcsOptional;
+    }
+    if term.inout == ecs_inout_kind_t_EcsInOutDefault {
+        term.inout = inout;
+    }
+}
+
+fn component_term_matches<T: Component>(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+    WorldInfoCache::get_component_id_for_type::<T>(world) == Some(term.id)
+}
+
+// The field pointer and its stride, 0 for fields of another entity than the iterated one,
+// e.g. with instanced filters or singleton terms
+unsafe fn field_column<T: Component>(it: &ecs_iter_t, index: i32) -> (*mut T, isize) {
+    (ecs_field::<T>(it, index), if ecs_field_is_self(it, index) { 1 } else { 0 })
+}
+
+impl<'c, T: Component> EachElement<'c> for &'static T {
+    type Item = &'c T;
+    type Column = (*mut T, isize);
+    const MUTABLE: bool = false;
+
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t) {
+        apply_element_term::<T>(world, term, ecs_inout_kind_t_EcsIn, false);
+    }
+
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+        component_term_matches::<T>(world, term)
+    }
+
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column {
+        field_column::<T>(it, index)
+    }
+
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item {
+        &*column.0.offset(i * column.1)
+    }
+}
+
+impl<'c, T: Component> EachElement<'c> for &'static mut T {
+    type Item = &'c mut T;
+    type Column = (*mut T, isize);
+    const MUTABLE: bool = true;
+
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t) {
+        apply_element_term::<T>(world, term, ecs_inout_kind_t_EcsInOut, false);
+    }
+
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+        component_term_matches::<T>(world, term)
+    }
+
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column {
+        field_column::<T>(it, index)
+    }
+
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item {
+        &mut *column.0.offset(i * column.1)
+    }
+}
+
+impl<'c, T: Component> EachElement<'c> for Option<&'static T> {
+    type Item = Option<&'c T>;
+    type Column = (*mut T, isize);
+    const MUTABLE: bool = false;
+
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t) {
+        apply_element_term::<T>(world, term, ecs_inout_kind_t_EcsIn, true);
+    }
+
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+        component_term_matches::<T>(world, term)
+    }
+
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column {
+        field_column::<T>(it, index)
+    }
+
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item {
+        if column.0.is_null() { None } else { Some(&*column.0.offset(i * column.1)) }
+    }
+}
+
+impl<'c, T: Component> EachElement<'c> for Option<&'static mut T> {
+    type Item = Option<&'c mut T>;
+    type Column = (*mut T, isize);
+    const MUTABLE: bool = true;
+
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t) {
+        apply_element_term::<T>(world, term, ecs_inout_kind_t_EcsInOut, true);
+    }
+
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+        component_term_matches::<T>(world, term)
+    }
+
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column {
+        field_column::<T>(it, index)
+    }
+
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item {
+        if column.0.is_null() { None } else { Some(&mut *column.0.offset(i * column.1)) }
+    }
+}
+
+/// The (R, T) pair as a group element, which resolves to the data of the pair: R, unless R
+/// is a tag, in which case T (see PairData). As a for_each element, e.g. (&Position,
+/// Pair<Requires, Gold>), it yields a reference to the data. In a ComponentGroup, e.g.
+/// filter::<(Position, Pair<Requires, Gold>)>(), it has to be registered with
+/// World::component_pair first and derefs to the data.
+#[repr(transparent)]
+pub struct Pair<R: PairData<T>, T: 'static> {
+    data: R::Data,
+    _phantom: std::marker::PhantomData<fn() -> (R, T)>,
+}
+
+impl<R: PairData<T>, T: 'static> std::ops::Deref for Pair<R, T> {
+    type Target = R::Data;
+
+    fn deref(&self) -> &R::Data {
+        &self.data
+    }
+}
+
+impl<R: PairData<T>, T: 'static> std::ops::DerefMut for Pair<R, T> {
+    fn deref_mut(&mut self) -> &mut R::Data {
+        &mut self.data
+    }
+}
+
+/// Same as Pair as a for_each element, with mutable access to the data
+pub struct PairMut<R, T>(std::marker::PhantomData<(R, T)>);
+
+/// The (R, *) pair as a for_each element, which yields the matched target and the data of
+/// the relation R. Entities with several (R, *) pairs are visited once for each of them.
+pub struct PairTarget<R>(std::marker::PhantomData<R>);
+
+pub(crate) fn pair_term_id<R: Component, T: Component>(world: *mut ecs_world_t) -> ecs_id_t {
+    let relation = WorldInfoCache::get_component_id_for_type::<R>(world).expect("Relation type not registered!");
+    let target = WorldInfoCache::get_component_id_for_type::<T>(world).expect("Target type not registered!");
+    ecs_pair(relation, target)
+}
+
+// The pointer to the data of a pair field and its stride, tags have no storage
+unsafe fn pair_field<D: Component>(it: &ecs_iter_t, index: i32) -> (*mut D, isize) {
+    if std::mem::size_of::<D>() == 0 {
+        return (std::ptr::NonNull::dangling().as_ptr(), 0);
+    }
+    field_column::<D>(it, index)
+}
+
+impl<'c, R: PairData<T>, T: Component> EachElement<'c> for Pair<R, T> {
+    type Item = &'c R::Data;
+    type Column = (*mut R::Data, isize);
+    const MUTABLE: bool = false;
+
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t) {
+        apply_element_term_id(term, || pair_term_id::<R, T>(world), ecs_inout_kind_t_EcsIn, false);
+    }
+
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+        pair_term_id::<R, T>(world) == term.id
+    }
+
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column {
+        pair_field::<R::Data>(it, index)
+    }
+
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item {
+        &*column.0.offset(i * column.1)
+    }
+}
+
+impl<'c, R: PairData<T>, T: Component> EachElement<'c> for PairMut<R, T> {
+    type Item = &'c mut R::Data;
+    type Column = (*mut R::Data, isize);
+    const MUTABLE: bool = true;
+
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t) {
+        apply_element_term_id(term, || pair_term_id::<R, T>(world), ecs_inout_kind_t_EcsInOut, false);
+    }
+
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+        pair_term_id::<R, T>(world) == term.id
+    }
+
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column {
+        pair_field::<R::Data>(it, index)
+    }
+
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item {
+        &mut *column.0.offset(i * column.1)
+    }
+}
+
+impl<'c, R: Component> EachElement<'c> for PairTarget<R> {
+    type Item = (Entity, &'c R);
+    type Column = (Entity, (*mut R, isize));
+    const MUTABLE: bool = false;
+
+    fn apply_term(world: *mut ecs_world_t, term: &mut ecs_term_t) {
+        assert!(std::mem::size_of::<R>() > 0, "Relation is a tag and has no data!");
+        let relation = || {
+            let relation = WorldInfoCache::get_component_id_for_type::<R>(world).expect("Relation type not registered!");
+            ecs_pair(relation, unsafe { EcsWildcard })
+        };
+        apply_element_term_id(term, relation, ecs_inout_kind_t_EcsIn, false);
+    }
+
+    fn term_matches(world: *mut ecs_world_t, term: &ecs_term_t) -> bool {
+        let relation = WorldInfoCache::get_component_id_for_type::<R>(world).expect("Relation type not registered!");
+        is_pair(term.id) && pair_first(term.id) == strip_generation(relation)
+    }
+
+    unsafe fn fetch_column(it: &ecs_iter_t, index: i32) -> Self::Column {
+        let target = pair_second(ecs_field_id(it, index));
+        (Entity::new(it.world, target), pair_field::<R>(it, index))
+    }
+
+    unsafe fn column_item(column: Self::Column, i: isize) -> Self::Item {
+        (column.0, &*column.1.0.offset(i * column.1.1))
+    }
+}
+
+/// The elements of a for_each callback, a single EachElement or a tuple of them, e.g.
+/// (&mut Position, &Velocity, Option<&Target>). The elements map to the first terms.
+pub trait EachGroup<'c>: 'static {
+    type Item: 'c;
+    type Columns: Copy;
+
+    /// The number of elements (and terms) in the group
+    const COUNT: usize;
+
+    /// Applies the elements to the first terms, see EachElement::apply_term
+    fn apply_terms(world: *mut ecs_world_t, terms: &mut [ecs_term_t]);
+
+    /// Panics if a component is accessed mutably by one element and by any other element
+    /// of the group, as the callback would get aliasing references
+    fn check_access(_terms: &[ecs_term_t]) {}
+
+    /// Panics if an element doesn't match the id of its term, for the finalized terms of
+    /// an existing filter
+    fn check_ids(world: *mut ecs_world_t, terms: &[ecs_term_t]);
+
+    /// # Safety
+    /// The first fields of the iterator must match the elements of the group
+    unsafe fn fetch_columns(it: &ecs_iter_t) -> Self::Columns;
+
+    /// # Safety
+    /// i must be less than the entity count of the result the columns were fetched for,
+    /// and mutably accessed components must not be borrowed elsewhere
+    unsafe fn columns_item(columns: Self::Columns, i: isize) -> Self::Item;
+}
+
+impl<'c, A: EachElement<'c>> EachGroup<'c> for A {
+    type Item = A::Item;
+    type Columns = A::Column;
+    const COUNT: usize = 1;
+
+    fn apply_terms(world: *mut ecs_world_t, terms: &mut [ecs_term_t]) {
+        A::apply_term(world, &mut terms[0]);
+    }
+
+    fn check_ids(world: *mut ecs_world_t, terms: &[ecs_term_t]) {
+        check_element_id::<A>(world, terms, 0);
+    }
+
+    unsafe fn fetch_columns(it: &ecs_iter_t) -> Self::Columns {
+        A::fetch_column(it, 1)
+    }
+
+    unsafe fn columns_item(columns: Self::Columns, i: isize) -> Self::Item {
+        A::column_item(columns, i)
+    }
+}
+
+// Whether two terms match the same field of the same entity
+fn same_field(a: &ecs_term_t, b: &ecs_term_t) -> bool {
+    a.id == b.id && a.first.id == b.first.id && a.second.id == b.second.id && a.src.id == b.src.id && a.src.name == b.src.name
+}
+
+fn check_unique_access(terms: &[ecs_term_t], mutable: &[bool]) {
+    for i in 0..mutable.len() {
+        for j in i + 1..mutable.len() {
+            if (mutable[i] || mutable[j]) && same_field(&terms[i], &terms[j]) {
+                panic!("Component of each element {} is also accessed by element {}, a mutably accessed component can only be in a group once!", j, i);
+            }
+        }
+    }
+}
+
+fn check_element_id<'c, E: EachElement<'c>>(world: *mut ecs_world_t, terms: &[ecs_term_t], index: usize) {
+    if !E::term_matches(world, &terms[index]) {
+        panic!("Each element {} ({}) doesn't match the id of term {}!", index, std::any::type_name::<E>(), index + 1);
+    }
+}
+
+// Checks the ids and the access of G to the first terms of an existing filter, see
+// EachGroup::check_ids and EachGroup::check_access
+pub(crate) unsafe fn check_filter_terms<'c, G: EachGroup<'c>>(world: *mut ecs_world_t, filter: *const ecs_filter_t) {
+    let count = (*filter).term_count as usize;
+    assert!(G::COUNT <= count, "Each group has more elements than the filter has terms!");
+    let terms = std::slice::from_raw_parts((*filter).terms, G::COUNT);
+    G::check_ids(world, terms);
+    G::check_access(terms);
+}
+
+// Calls cb for every entity of the current result of an active iterator
+#[inline]
+pub(crate) unsafe fn each_group<'c, G: EachGroup<'c>>(it: &ecs_iter_t, cb: &mut impl FnMut(Entity, G::Item)) {
+    let columns = G::fetch_columns(it);
+    for i in 0..it.count as isize {
+        cb(Entity::new(it.world, *it.entities.offset(i)), G::columns_item(columns, i));
+    }
+}
+
+// Same as each_group, returns false once cb returned false, skipping the remaining entities
+pub(crate) unsafe fn each_group_while<'c, G: EachGroup<'c>>(it: &ecs_iter_t, cb: &mut impl FnMut(Entity, G::Item) -> bool) -> bool {
+    let columns = G::fetch_columns(it);
+    for i in 0..it.count as isize {
+        if !cb(Entity::new(it.world, *it.entities.offset(i)), G::columns_item(columns, i)) {
+            return false;
+        }
+    }
+    true
+}
+
+macro_rules! impl_each_tuple {
+    ($len:expr, $(($elem:ident, $elem_idx:tt)), *) => {
+        impl<'s, $($elem),*> EachGroup<'s> for ($($elem), *)
+        where $( $elem : EachElement<'s> ),*
+        {
+            type Item = ($($elem::Item),*);
+            type Columns = ($($elem::Column),*);
+            const COUNT: usize = $len;
+
+            fn apply_terms(world: *mut ecs_world_t, terms: &mut [ecs_term_t]) {
+                $(
+                    $elem::apply_term(world, &mut terms[$elem_idx]);
+                )*
+                Self::check_access(terms);
+            }
+
+            fn check_access(terms: &[ecs_term_t]) {
+                check_unique_access(terms, &[$($elem::MUTABLE),*]);
+            }
+
+            fn check_ids(world: *mut ecs_world_t, terms: &[ecs_term_t]) {
+                $(
+                    check_element_id::<$elem>(world, terms, $elem_idx);
+                )*
+            }
+
+            unsafe fn fetch_columns(it: &ecs_iter_t) -> Self::Columns {
+                ($(
+                    $elem::fetch_column(it, $elem_idx + 1),
+                )*)
+            }
+
+            unsafe fn columns_item(columns: Self::Columns, i: isize) -> Self::Item {
+                ($(
+                    $elem::column_item(tuple_index!(columns, $elem_idx), i),
+                )*)
+            }
+        }
+    }
+}
+
+impl_each_tuple!(8, (T1, 0), (T2, 1), (T3, 2), (T4, 3), (T5, 4), (T6, 5), (T7, 6), (T8, 7));
+impl_each_tuple!(7, (T1, 0), (T2, 1), (T3, 2), (T4, 3), (T5, 4), (T6, 5), (T7, 6));
+impl_each_tuple!(6, (T1, 0), (T2, 1), (T3, 2), (T4, 3), (T5, 4), (T6, 5));
+impl_each_tuple!(5, (T1, 0), (T2, 1), (T3, 2), (T4, 3), (T5, 4));
+impl_each_tuple!(4, (T1, 0), (T2, 1), (T3, 2), (T4, 3));
+impl_each_tuple!(3, (T1, 0), (T2, 1), (T3, 2));
+impl_each_tuple!(2, (T1, 0), (T2, 1));
+
 mod private {
 //     use crate::Component;
 
@@ -0,0 +1,68 @@
+use crate::*;
+
+// Doc addon support
+//
+// Human readable documentation for entities, which shows up in the REST explorer.
+// Flecs copies the strings, so they can be dropped after the call. The getters return
+// copies too, as flecs frees its string when the doc is set again.
+
+fn doc_str(str: *const ::std::os::raw::c_char) -> Option<String> {
+	if str.is_null() {
+		return None;
+	}
+	Some(unsafe { std::ffi::CStr::from_ptr(str) }.to_string_lossy().into_owned())
+}
+
+impl Entity {
+	pub fn set_doc_name(self, name: &str) -> Self {
+		let c_str = std::ffi::CString::new(name).unwrap();
+		unsafe { ecs_doc_set_name(self.world(), self.raw(), c_str.as_ptr()) };
+		self
+	}
+
+	pub fn set_doc_brief(self, brief: &str) -> Self {
+		let c_str = std::ffi::CString::new(brief).unwrap();
+		unsafe { ecs_doc_set_brief(self.world(), self.raw(), c_str.as_ptr()) };
+		self
+	}
+
+	pub fn set_doc_detail(self, detail: &str) -> Self {
+		let c_str = std::ffi::CString::new(detail).unwrap();
+		unsafe { ecs_doc_set_detail(self.world(), self.raw(), c_str.as_ptr()) };
+		self
+	}
+
+	pub fn set_doc_link(self, link: &str) -> Self {
+		let c_str = std::ffi::CString::new(link).unwrap();
+		unsafe { ecs_doc_set_link(self.world(), self.raw(), c_str.as_ptr()) };
+		self
+	}
+
+	/// Sets the color used by the explorer, e.g. "#ff0000"
+	pub fn set_doc_color(self, color: &str) -> Self {
+		let c_str = std::ffi::CString::new(color).unwrap();
+		unsafe { ecs_doc_set_color(self.world(), self.raw(), c_str.as_ptr()) };
+		self
+	}
+
+	/// Returns the doc name, falling back to the entity name like flecs does
+	pub fn doc_name(&self) -> Option<String> {
+		doc_str(unsafe { ecs_doc_get_name(self.world(), self.raw()) })
+	}
+
+	pub fn doc_brief(&self) -> Option<String> {
+		doc_str(unsafe { ecs_doc_get_brief(self.world(), self.raw()) })
+	}
+
+	pub fn doc_detail(&self) -> Option<String> {
+		doc_str(unsafe { ecs_doc_get_detail(self.world(), self.raw()) })
+	}
+
+	pub fn doc_link(&self) -> Option<String> {
+		doc_str(unsafe { ecs_doc_get_link(self.world(), self.raw()) })
+	}
+
+	pub fn doc_color(&self) -> Option<String> {
+		doc_str(unsafe { ecs_doc_get_color(self.world(), self.raw()) })
+	}
+}
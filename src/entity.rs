@@ -34,9 +34,48 @@ impl EntityTypeInfo {
 	}
 }
 
+/// Cleanup action for entities that reference a deleted entity, see Entity::on_delete
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupPolicy {
+	/// Removes the component or pair from the entities
+	Remove,
+	/// Deletes the entities
+	Delete,
+	/// Aborts, for entities that must never be deleted while referenced
+	Panic,
+}
+
+impl CleanupPolicy {
+	pub fn id(&self) -> EntityId {
+		unsafe {
+			match self {
+				CleanupPolicy::Remove => EcsRemove,
+				CleanupPolicy::Delete => EcsDelete,
+				CleanupPolicy::Panic => EcsPanic,
+			}
+		}
+	}
+}
+
 // WIP - This should become like the flecs::entity class
 //
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+/// Handle to an entity in a world. Handles are cheap to copy, compare by world and
+/// id, and can be used as keys in hashed collections.
+/// 
+/// ```
+/// use flecs::*;
+/// use std::collections::HashSet;
+/// 
+/// let world = World::new();
+/// let e = world.entity().named("Player");
+/// println!("{e}"); // Player
+/// 
+/// let mut set = HashSet::new();
+/// set.insert(e);
+/// assert!(set.contains(&e));
+/// assert!(!set.contains(&world.entity()));
+/// ```
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
 pub struct Entity {
 	entity: EntityId,	// todo: rename this id
 	world: *mut ecs_world_t,
@@ -57,14 +96,37 @@ impl Entity {
 		self.entity 
 	}
 
+	pub(crate) fn world(&self) -> *mut ecs_world_t { 
+		self.world 
+	}
+
 	pub fn id(&self) -> EntityId { 
 		self.entity 
 	}
 
+	/// Returns a handle to the same entity which records its operations in stage, e.g. an
+	/// async stage of a loader thread. Operations through the returned handle are applied
+	/// when the stage is merged.
+	pub fn in_stage(&self, stage: &World) -> Entity {
+		Entity::new(stage.raw(), self.entity)
+	}
+
+	/// Valid means the id could be used, i.e. it is alive or has never been created.
+	/// Use is_alive() to check that this exact generation currently exists.
     pub fn is_valid(&self) -> bool {
         !self.world.is_null() && unsafe { ecs_is_valid(self.world, self.entity) }
     }
 
+	/// Alive means this exact generation of the id currently exists in the world.
+	/// A stale handle to a deleted (and possibly recycled) entity is not alive.
+    pub fn is_alive(&self) -> bool {
+        !self.world.is_null() && unsafe { ecs_is_alive(self.world, self.entity) }
+    }
+
+	pub fn generation(&self) -> u32 {
+		entity_generation(self.entity)
+	}
+
 	// from base id type, which don't exist in rust
     pub fn id_str(&self) -> &str {
 		let id_str = unsafe { ecs_id_str(self.world, self.entity) };
@@ -89,16 +151,31 @@ impl Entity {
 		unsafe { flecs_to_rust_str(symbol_str) }
 	}
 
-	pub fn path(&self) -> &str {
-		let sep = NAME_SEP.as_ptr() as *const i8;	// for now only support :: as sep
-		let path_str = unsafe { ecs_get_path_w_sep(self.world, 0, self.entity, sep, sep) };
-		unsafe { flecs_to_rust_str(path_str) }
+	/// Returns the full hierarchical name, e.g. "Scene::Player::Weapon".
+	/// Entities without a name show up as their numeric id.
+	pub fn path(&self) -> String {
+		self.path_from(0)
+	}
+
+	/// Returns the hierarchical name relative to parent
+	pub fn path_from<T: AsEcsId>(&self, parent: T) -> String {
+		let sep = NAME_SEP.as_ptr() as *const i8;
+		let path_str = unsafe { ecs_get_path_w_sep(self.world, parent.id(), self.entity, sep, std::ptr::null()) };
+		unsafe { flecs_to_owned_string(path_str) }
+	}
+
+	pub fn path_w_sep(&self, sep: &str) -> String {
+		let sep_c_str = std::ffi::CString::new(sep).unwrap();
+		let path_str = unsafe { ecs_get_path_w_sep(self.world, 0, self.entity, sep_c_str.as_ptr(), std::ptr::null()) };
+		unsafe { flecs_to_owned_string(path_str) }
 	}
 
 	pub fn type_info(&self) -> EntityTypeInfo {
 		EntityTypeInfo::new(self.world, self.id())
 	}
 
+	/// Names the entity. flecs aborts if a sibling already has the name, use set_name
+	/// to get an error instead.
 	pub fn named(self, name: &str) -> Self {
         unsafe { 
 			let name_c_str = std::ffi::CString::new(name).unwrap();
@@ -107,18 +184,98 @@ impl Entity {
 		self
 	}
 
+	/// Renames the entity, lookups find it by the new name right away. Fails if another
+	/// entity with the same parent already has the name.
+	pub fn set_name(&self, name: &str) -> Result<(), NameError> {
+		let name_c_str = std::ffi::CString::new(name).unwrap();
+		let parent = unsafe { ecs_get_target(self.world, self.entity, EcsChildOf, 0) };
+		let existing = unsafe { ecs_lookup_child(self.world, parent, name_c_str.as_ptr()) };
+		if existing != 0 && existing != self.entity {
+			return Err(NameError { name: name.to_owned(), existing: Entity::new(self.world, existing) });
+		}
+		unsafe { ecs_set_name(self.world, self.entity, name_c_str.as_ptr()) };
+		Ok(())
+	}
+
+	/// Removes the name of the entity, it can no longer be looked up by name
+	pub fn remove_name(&self) {
+		unsafe { ecs_remove_id(self.world, self.entity, ecs_pair(FLECS__EEcsIdentifier, EcsName)) };
+	}
+
 	pub fn is_a<T: AsEcsId>(self, object: T) -> Self {
-        unsafe { self.add_relation_ids(EcsIsA, object.id()) }
+        let instance = unsafe { self.add_relation_ids(EcsIsA, object.id()) };
+		instance.resolve_slots(Entity::new(self.world, object.id()));
+		instance
+	}
+
+	// Slots
+	//
+	// This version of flecs has no builtin SlotOf relation, so the wrapper provides it.
+	// The prefab keeps a (Slot, child) pair for each of its slots (a SlotOf pair on the
+	// child itself would be copied into nested instance children, which flecs 3.0 does
+	// not clean up correctly). When a prefab is instantiated with is_a(), the instance
+	// gets a (slot, child) pair for every slot, pointing at its own copy of the slot.
+
+	/// Marks this prefab child as a slot of prefab, which must be one of its ancestors.
+	/// Instances of prefab can then find their copy with instance.target(slot, 0).
+	pub fn slot_of(self, prefab: Entity) -> Self {
+		let slot = register_component_typed::<Slot>(self.world, None);
+		assert!(!self.name().is_empty(), "Slots must be named!");
+		prefab.add_relation_ids(slot, self);
+		self
+	}
+
+	/// Marks this prefab child as a slot of its parent
+	pub fn slot(self) -> Self {
+		let parent = self.parent().expect("Slot entity must have a parent!");
+		self.slot_of(parent)
+	}
+
+	fn resolve_slots(&self, prefab: Entity) {
+		let slot = match WorldInfoCache::get_component_id_for_type::<Slot>(self.world) {
+			Some(slot) => slot,
+			None => return,	// no slots were ever created
+		};
+
+		let sep = NAME_SEP.as_ptr();
+		for slot in prefab.targets(slot) {
+			let path = std::ffi::CString::new(slot.path_from(prefab)).unwrap();
+			let target = unsafe { ecs_lookup_path_w_sep(self.world, self.entity, path.as_ptr(), sep, sep, false) };
+			if target != 0 {
+				self.add_relation_ids(slot, target);
+			}
+		}
 	}
 
 	pub fn child_of<T: AsEcsId>(self, object: T) -> Self {
         unsafe { self.add_relation_ids(EcsChildOf, object.id()) }
 	}
 
+	pub fn depends_on<T: AsEcsId>(self, object: T) -> Self {
+        unsafe { self.add_relation_ids(EcsDependsOn, object.id()) }
+	}
+
+	/// Returns false for types that were never registered as a component
+	pub fn has<T: Component>(&self) -> bool {
+		match WorldInfoCache::get_component_id_for_type::<T>(self.world) {
+			Some(comp_id) => self.has_id(comp_id),
+			None => false,
+		}
+	}
+
     pub fn has_id<T: AsEcsId>(self, id: T) -> bool {
         unsafe { ecs_has_id(self.world, self.entity, id.id()) }
     }
 
+	/// Like has_id, but ignores ids inherited from an IsA base
+    pub fn owns_id<T: AsEcsId>(self, id: T) -> bool {
+		let table = unsafe { ecs_get_table(self.world, self.entity) };
+		if table.is_null() {
+			return false;
+		}
+		unsafe { ecs_search(self.world, table, id.id(), std::ptr::null_mut()) != -1 }
+    }
+
 	pub fn add_id<T: AsEcsId>(self, id: T) -> Self {
         unsafe { ecs_add_id(self.world, self.entity, id.id()) };
 		self
@@ -134,6 +291,84 @@ impl Entity {
         unsafe { ecs_has_id(self.world, self.entity, pair) }
     }
 
+	/// Sets what happens to entities with this relation or component when it is deleted,
+	/// i.e. the (OnDelete, policy) pair. The default is to remove it from the entities.
+	pub fn on_delete(self, policy: CleanupPolicy) -> Self {
+		self.add_relation_ids(unsafe { EcsOnDelete }, policy.id())
+	}
+
+	/// Sets what happens to entities with a (self, target) pair when the target is
+	/// deleted, i.e. the (OnDeleteTarget, policy) pair. ChildOf uses Delete.
+	pub fn on_delete_target(self, policy: CleanupPolicy) -> Self {
+		self.add_relation_ids(unsafe { EcsOnDeleteTarget }, policy.id())
+	}
+
+	pub fn on_delete_remove(self) -> Self { self.on_delete(CleanupPolicy::Remove) }
+	pub fn on_delete_delete(self) -> Self { self.on_delete(CleanupPolicy::Delete) }
+	pub fn on_delete_panic(self) -> Self { self.on_delete(CleanupPolicy::Panic) }
+	pub fn on_delete_target_remove(self) -> Self { self.on_delete_target(CleanupPolicy::Remove) }
+	pub fn on_delete_target_delete(self) -> Self { self.on_delete_target(CleanupPolicy::Delete) }
+	pub fn on_delete_target_panic(self) -> Self { self.on_delete_target(CleanupPolicy::Panic) }
+
+	/// Entities created by the world inside f become children of this entity.
+	/// The previous scope is restored afterwards, so scopes can be nested.
+	pub fn scope(self, f: impl FnOnce(&World)) -> Self {
+		let world = World::new_from(self.world);
+		let prev = unsafe { ecs_set_scope(self.world, self.entity) };
+		let _restore = RestoreOnDrop { world: self.world, prev, set: ecs_set_scope };
+		f(&world);
+		self
+	}
+
+	/// Entities created by the world inside f get a (relation, self) pair
+	pub fn with_rel<R: AsEcsId>(self, relation: R, f: impl FnOnce(&World)) -> Self {
+		let world = World::new_from(self.world);
+		let prev = unsafe { ecs_set_with(self.world, ecs_make_pair(relation.id(), self.entity)) };
+		let _restore = RestoreOnDrop { world: self.world, prev, set: ecs_set_with };
+		f(&world);
+		self
+	}
+
+	/// Returns the parent of this entity, i.e. the target of its ChildOf pair
+	pub fn parent(&self) -> Option<Entity> {
+		let parent = unsafe { ecs_get_target(self.world, self.entity, EcsChildOf, 0) };
+		if parent != 0 {
+			return Some(Entity::new(self.world, parent));
+		}
+		None
+	}
+
+	/// Returns the target of the relation at index, for entities with multiple
+	/// pairs of the same relation. Returns None once index is out of range.
+	pub fn target<R: AsEcsId>(&self, relation: R, index: i32) -> Option<Entity> {
+		let target = unsafe { ecs_get_target(self.world, self.entity, relation.id(), index) };
+		if target != 0 {
+			return Some(Entity::new(self.world, target));
+		}
+		None
+	}
+
+	pub fn target_of<R: Component>(&self) -> Option<Entity> {
+		let relation = WorldInfoCache::get_component_id_for_type::<R>(self.world).expect("Relation type not registered!");
+		self.target(relation, 0)
+	}
+
+	/// Iterates all targets of the relation
+	pub fn targets<R: AsEcsId>(&self, relation: R) -> EntityTargets {
+		EntityTargets { entity: *self, relation: relation.id(), index: 0 }
+	}
+
+	/// Returns the entity which provides component T by following the relation,
+	/// e.g. the prefab an instance inherits T from with EcsIsA.
+	pub fn target_for<T: Component, R: AsEcsId>(&self, relation: R) -> Option<Entity> {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+		let target = unsafe { ecs_get_target_for_id(self.world, self.entity, relation.id(), comp_id) };
+		if target != 0 {
+			return Some(Entity::new(self.world, target));
+		}
+		None
+	}
+
     pub fn is_child_of<T: AsEcsId>(self, parent: T) -> bool {
         let pair = unsafe { ecs_make_pair(EcsChildOf, parent.id()) };
         unsafe { ecs_has_id(self.world, self.entity, pair) }
@@ -150,24 +385,155 @@ impl Entity {
 		unsafe { (value as *const T).as_ref().unwrap() }
 	}
 
-    pub fn get_mut<T: Component>(&mut self) -> &mut T  {
+	// Adds the component if the entity doesn't have it yet
+    fn ensure_mut<T: Component>(&mut self) -> &mut T  {
 		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
 		let value = unsafe { ecs_get_mut_id(self.world, self.entity, comp_id) };
 		unsafe { (value as *mut T).as_mut().unwrap() }
     }
 
+	/// Returns the component for in-place mutation, or None if the entity doesn't have it.
+	/// An inherited component is copied into the entity first.
+	/// 
+	/// The reference points directly into table storage. Holding it across structural
+	/// changes (adding/removing components, deleting entities) is undefined behavior.
+	/// Call modified() afterwards to notify OnSet observers, or prefer update() which
+	/// bounds the borrow and does both.
+    pub fn get_mut<T: Component>(&mut self) -> Option<&mut T>  {
+		if !self.has::<T>() {
+			return None;
+		}
+		Some(self.ensure_mut::<T>())
+    }
+
+	/// Signals that the component was changed in place, which runs OnSet observers
+	pub fn modified<T: Component>(&self) {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+		unsafe { ecs_modified_id(self.world, self.entity, comp_id) };
+	}
+
+	/// Mutates the component with f and calls modified() afterwards.
+	/// Returns false if the entity doesn't have the component.
+	pub fn update<T: Component>(mut self, f: impl FnOnce(&mut T)) -> bool {
+		match self.get_mut::<T>() {
+			Some(value) => f(value),
+			None => return false,
+		}
+		self.modified::<T>();
+		true
+	}
+
+	/// Returns a cached reference to component T, which is faster than get() when
+	/// the same component is accessed repeatedly.
+	pub fn get_ref<T: Component>(&self) -> Ref<T> {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+		Ref {
+			world: self.world,
+			comp_id,
+			reference: unsafe { ecs_ref_init_id(self.world, self.entity, comp_id) },
+			_phantom: std::marker::PhantomData,
+		}
+	}
+
+	/// Sets the component value and notifies OnSet observers
 	pub fn set<T: Component>(mut self, value: T) -> Self {
-		let dest = self.get_mut::<T>();
+		let dest = self.ensure_mut::<T>();
 		*dest = value;
+		self.modified::<T>();
+		self
+	}
+
+	/// Constructs the component directly in table storage, which avoids building large
+	/// values on the stack. OnSet observers run after f. The entity must not own the
+	/// component yet, a component inherited through IsA is overridden.
+	///
+	/// # Safety
+	///
+	/// f must fully initialize the value, it is used as a T afterwards. The storage may
+	/// hold a copy of the inherited value, which is overwritten without being dropped.
+	pub unsafe fn emplace<T: Component>(self, f: impl FnOnce(&mut MaybeUninit<T>)) -> Self {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+		assert!(!self.owns_id(comp_id), "Cannot emplace a component the entity already has!");
+		let ptr = if self.has_id(comp_id) {
+			// flecs only emplaces ids the entity doesn't have, overriding copies the base value
+			ecs_add_id(self.world, self.entity, comp_id);
+			ecs_get_mut_id(self.world, self.entity, comp_id)
+		} else {
+			ecs_emplace_id(self.world, self.entity, comp_id)
+		};
+		let dest = (ptr as *mut MaybeUninit<T>).as_mut().unwrap();
+		f(dest);
+		ecs_modified_id(self.world, self.entity, comp_id);
 		self
 	}
 
+	/// On a prefab this makes every instance get a private copy of the component.
+	/// On an instance which currently shares the component with its prefab, the
+	/// value is copied in so the instance owns it from now on.
 	pub fn override_component<T: Component>(self) -> Self {
 		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
         unsafe { ecs_add_id(self.world, self.entity, ECS_OVERRIDE | comp_id) };
+		if self.has_id(comp_id) && !self.owns_id(comp_id) {
+			// adding an inherited id copies the value from the base
+			unsafe { ecs_add_id(self.world, self.entity, comp_id) };
+		}
+		self
+	}
+
+	// Component traits
+	//
+	// This version of flecs has no per-component OnInstantiate setting. Components are
+	// shared with instances by default, overrides are declared on the prefab itself
+	// via override_component() and DontInherit can be set on the component entity.
+	// override_on_instantiate() declares the override on every prefab with the component.
+
+	/// Marks this component so that it is never inherited from an IsA base
+	pub fn dont_inherit(self) -> Self {
+		self.add_id(unsafe { EcsDontInherit })
+	}
+
+	/// Marks this component so that instances always get a private copy of it, initialized
+	/// from the prefab value. The override is added to the prefabs which have the component,
+	/// now and when it is added later.
+	pub fn override_on_instantiate(self) -> Self {
+		let comp_id = self.entity;
+		if !WorldInfoCache::add_override_component(self.world, comp_id) {
+			return self;
+		}
+		let world = World::new_from(self.world);
+		let prefab = unsafe { EcsPrefab };
+
+		let mut prefabs = Vec::new();
+		world.filter_builder().term_id(comp_id).term_id(prefab).build().iter(|it| {
+			for i in 0..it.count() {
+				prefabs.push(it.entity(i as i32));
+			}
+		});
+		for prefab in prefabs {
+			prefab.override_id(comp_id);
+		}
+
+		world.observer()
+			.term_id(comp_id)
+			.term_id(prefab)
+			.event_on_add()
+			.iter(move |it| {
+				// flecs defers operations while the add which triggered the observer runs,
+				// the override is added to the prefab right after it
+				for i in 0..it.count() {
+					it.entity(i as i32).override_id(comp_id);
+				}
+			});
 		self
 	}
 
+	/// Marks a component on a prefab so that every new instance automatically gets
+	/// its own copy of it, initialized from the prefab value.
+	pub fn auto_override<T: Component>(self) -> Self {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+		self.override_id(comp_id)
+	}
+
 	pub fn override_id(self, comp_id: ecs_id_t) -> Self {
         unsafe { ecs_add_id(self.world, self.entity, ECS_OVERRIDE | comp_id) };
 		self
@@ -202,20 +568,170 @@ impl Entity {
 		self
 	}
 
+	/// Disables the entity by adding EcsDisabled, so filters, queries and systems skip it
+	pub fn disable(self) -> Self {
+        unsafe { ecs_enable(self.world, self.entity, false) };
+		self
+	}
+
+	pub fn enable(self) -> Self {
+        unsafe { ecs_enable(self.world, self.entity, true) };
+		self
+	}
+
+	pub fn is_enabled(&self) -> bool {
+        !self.has_id(unsafe { EcsDisabled })
+	}
+
+	// Component toggling
+	//
+	// Disabling a component keeps it on the entity (no table move) but queries and
+	// systems skip the entity. Note that uncached filters do not check the toggle
+	// bitset in this version of flecs. Any component can be toggled, the first
+	// call adds the ECS_TOGGLE bitset for it automatically.
+
+	pub fn enable_component<T: Component>(self) -> Self {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+        unsafe { ecs_enable_id(self.world, self.entity, comp_id, true) };
+		self
+	}
+
+	pub fn disable_component<T: Component>(self) -> Self {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+        unsafe { ecs_enable_id(self.world, self.entity, comp_id, false) };
+		self
+	}
+
+	pub fn is_component_enabled<T: Component>(&self) -> bool {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+        unsafe { ecs_is_enabled_id(self.world, self.entity, comp_id) }
+	}
+
+	// Pairs
+	//
+	// A pair holds data when one of its elements is a component. Following flecs
+	// rules the data type is the relation if it is not a tag, otherwise the object.
+
+	fn pair_id<R: Component, T: Component>(&self) -> ecs_id_t {
+		let relation = WorldInfoCache::get_component_id_for_type::<R>(self.world).expect("Relation type not registered!");
+		let object = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Object type not registered!");
+		unsafe { ecs_make_pair(relation, object) }
+	}
+
+	fn pair_second_id<R: Component>(&self, target: Entity) -> ecs_id_t {
+		let relation = WorldInfoCache::get_component_id_for_type::<R>(self.world).expect("Relation type not registered!");
+		unsafe { ecs_make_pair(relation, target.raw()) }
+	}
+
+	fn pair_data_type<R: Component, T: Component>() -> TypeId {
+		if std::mem::size_of::<R>() > 0 {
+			TypeId::of::<R>()
+		} else {
+			TypeId::of::<T>()
+		}
+	}
+
+	fn get_pair_data<D: Component>(&self, pair: ecs_id_t) -> Option<&D> {
+		let value = unsafe { ecs_get_id(self.world, self.entity, pair) };
+		unsafe { (value as *const D).as_ref() }
+	}
+
+	fn set_pair_data<D: Component>(self, pair: ecs_id_t, value: D) -> Self {
+		let ptr = unsafe { ecs_get_mut_id(self.world, self.entity, pair) };
+		let dest = unsafe { (ptr as *mut D).as_mut().unwrap() };
+		*dest = value;
+		unsafe { ecs_modified_id(self.world, self.entity, pair) };
+		self
+	}
+
+	pub fn add_pair<R: Component, T: Component>(self) -> Self {
+		let pair = self.pair_id::<R, T>();
+		self.add_id(pair)
+	}
+
+	pub fn add_pair_second<R: Component>(self, target: Entity) -> Self {
+		let pair = self.pair_second_id::<R>(target);
+		self.add_id(pair)
+	}
+
+	/// Sets the data of the (R, T) pair. The value must be of the relation type, or
+	/// of the object type when the relation is a tag.
+	pub fn set_pair<R: Component, T: Component>(self, value: impl Component) -> Self {
+		fn type_id_of<D: Component>(_: &D) -> TypeId { TypeId::of::<D>() }
+		assert!(type_id_of(&value) == Self::pair_data_type::<R, T>(), "Value type does not match the pair data type!");
+		let pair = self.pair_id::<R, T>();
+		self.set_pair_data(pair, value)
+	}
+
+	/// Sets the data of the (R, target) pair, where R is a component
+	pub fn set_pair_second<R: Component>(self, target: Entity, value: R) -> Self {
+		assert!(std::mem::size_of::<R>() > 0, "Relation is a tag and cannot hold data!");
+		let pair = self.pair_second_id::<R>(target);
+		self.set_pair_data(pair, value)
+	}
+
+	/// Gets the data of the (R, T) pair when the relation holds the data
+	pub fn get_pair<R: Component, T: Component>(&self) -> Option<&R> {
+		assert!(Self::pair_data_type::<R, T>() == TypeId::of::<R>(), "Relation is a tag, use get_pair_object!");
+		self.get_pair_data(self.pair_id::<R, T>())
+	}
+
+	/// Gets the data of the (R, T) pair when the relation is a tag and the object holds the data
+	pub fn get_pair_object<R: Component, T: Component>(&self) -> Option<&T> {
+		assert!(Self::pair_data_type::<R, T>() == TypeId::of::<T>(), "Relation holds the data, use get_pair!");
+		self.get_pair_data(self.pair_id::<R, T>())
+	}
+
+	pub fn get_pair_second<R: Component>(&self, target: Entity) -> Option<&R> {
+		assert!(std::mem::size_of::<R>() > 0, "Relation is a tag and cannot hold data!");
+		self.get_pair_data(self.pair_second_id::<R>(target))
+	}
+
+	/// Switches the (R, target) state pair of the entity. R must be an exclusive relation
+	/// (see World::component_exclusive), so flecs replaces the previous state pair in the
+	/// same table move instead of adding a second one.
+	pub fn set_state<R: Component>(self, target: Entity) -> Self {
+		let relation = WorldInfoCache::get_component_id_for_type::<R>(self.world).expect("Relation type not registered!");
+		assert!(unsafe { ecs_has_id(self.world, relation, EcsExclusive) }, "State relation is not exclusive!");
+		self.add_pair_second::<R>(target)
+	}
+
+	/// Returns the current target of the exclusive relation R, see set_state
+	pub fn state<R: Component>(&self) -> Option<Entity> {
+		self.target_of::<R>()
+	}
+
+	pub fn has_pair<R: Component, T: Component>(&self) -> bool {
+		self.has_id(self.pair_id::<R, T>())
+	}
+
+	pub fn has_pair_second<R: Component>(&self, target: Entity) -> bool {
+		self.has_id(self.pair_second_id::<R>(target))
+	}
+
+	pub fn remove_pair<R: Component, T: Component>(self) -> Self {
+		self.remove_id(self.pair_id::<R, T>())
+	}
+
+	pub fn remove_pair_second<R: Component>(self, target: Entity) -> Self {
+		self.remove_id(self.pair_second_id::<R>(target))
+	}
+
 	pub fn remove<T: Component>(self) -> Self {
 		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
         unsafe { ecs_remove_id(self.world, self.entity, comp_id) };
 		self
 	}
 
-	// Added to assess performance impact of Type lookup within Benchmarks
-	pub fn remove_id(&self, comp_id: u64) {
-        unsafe { ecs_remove_id(self.world, self.entity, comp_id) };
+	/// Removes a component, tag or pair id. Removing an id the entity doesn't have is a no-op.
+	pub fn remove_id<T: AsEcsId>(self, id: T) -> Self {
+        unsafe { ecs_remove_id(self.world, self.entity, id.id()) };
+		self
 	}
 
 	// Dynamic Components
 	//
-    fn get_mut_dynamic(&mut self, symbol: &'static str) -> &mut [u8]  {
+    fn get_mut_dynamic(&mut self, symbol: &str) -> &mut [u8]  {
 		let comp_info = WorldInfoCache::get_component_id_for_symbol(self.world, symbol).unwrap();
 		let value = unsafe { ecs_get_mut_id(self.world, self.entity, comp_info.id) };
 		unsafe { 
@@ -226,23 +742,32 @@ impl Entity {
 		}
     }
 
-	pub fn set_dynamic(mut self, symbol: &'static str, src: &[u8]) -> Self {
+	pub fn set_dynamic(mut self, symbol: &str, src: &[u8]) -> Self {
 		let dest = self.get_mut_dynamic(symbol);
 		dest.copy_from_slice(src);
 		self
 	}
 
-	pub fn add_dynamic(self, symbol: &'static str) -> Self {
+	pub fn add_dynamic(self, symbol: &str) -> Self {
 		let comp_info = WorldInfoCache::get_component_id_for_symbol(self.world, symbol).unwrap();
         unsafe { ecs_add_id(self.world, self.entity, comp_info.id) };
 		self
 	}
 
-	/// Call this to remove the entity from the world
+	/// Call this to remove the entity from the world. Systems and the each/iter functions
+	/// of filters and queries iterate in deferred mode, so deleting the iterated entities
+	/// is safe: the deletion happens once the iteration is done, and the entity stays
+	/// alive until then.
 	pub fn destruct(self) {
 		unsafe { ecs_delete(self.world, self.entity) }; 
 	}
 
+	/// Removes all components from the entity while keeping the id alive.
+	/// Like in flecs this includes the name, which is stored as a component too.
+	pub fn clear(&self) {
+		unsafe { ecs_clear(self.world, self.entity) }; 
+	}
+
 	pub fn each(&self, mut cb: impl FnMut(Id)) {
 		unsafe {
 			let e_type: *const ecs_type_t = ecs_get_type(self.world, self.entity);
@@ -269,10 +794,91 @@ impl Entity {
 		}
 	}
 
+	/// Calls cb with every id of the entity: components, tags and pairs.
+	/// Pair ids can be decomposed with pair_first() and pair_second().
+	pub fn each_component(&self, mut cb: impl FnMut(EntityId)) {
+		unsafe {
+			let e_type: *const ecs_type_t = ecs_get_type(self.world, self.entity);
+			if let Some(ty) = e_type.as_ref() {
+				if ty.count > 0 {
+					for id in std::slice::from_raw_parts(ty.array, ty.count as usize) {
+						cb(*id);
+					}
+				}
+			}
+		}
+	}
+
+	/// Returns the ids of the entity as a string, e.g. "Position, (ChildOf,Scene)"
+	pub fn type_string(&self) -> String {
+		unsafe {
+			let e_type: *const ecs_type_t = ecs_get_type(self.world, self.entity);
+			flecs_to_owned_string(ecs_type_str(self.world, e_type))
+		}
+	}
+
+	/// The ids of the components, tags and pairs of the entity, sorted like flecs
+	/// sorts the type of a table
+	pub fn ids(&self) -> Vec<RawId> {
+		self.raw_ids().iter().map(|&id| RawId::new(id).unwrap()).collect()
+	}
+
+	#[deprecated(note = "use Entity::ids, which returns RawIds")]
+	pub fn type_id_list(&self) -> Vec<EntityId> {
+		self.raw_ids().to_vec()
+	}
+
+	fn raw_ids(&self) -> &[EntityId] {
+		unsafe {
+			match ecs_get_type(self.world, self.entity).as_ref() {
+				Some(ty) if ty.count > 0 => std::slice::from_raw_parts(ty.array, ty.count as usize),
+				_ => &[],
+			}
+		}
+	}
+
+	/// Returns true if both entities have the same set of ids, regardless of the order in
+	/// which they were added. Within a world that means they share a table, entities of
+	/// different worlds are compared by their id lists.
+	pub fn same_type_as(&self, other: Entity) -> bool {
+		unsafe {
+			if ecs_get_world(self.world as *const ecs_poly_t) == ecs_get_world(other.world as *const ecs_poly_t) {
+				return ecs_get_table(self.world, self.entity) == ecs_get_table(other.world, other.entity);
+			}
+		}
+		self.raw_ids() == other.raw_ids()
+	}
+
+	/// A hash of the sorted id list (FNV-1a), which is stable across runs and platforms
+	/// as long as the ids are the same, e.g. when components are registered in the same order
+	pub fn type_hash(&self) -> u64 {
+		let mut hash: u64 = 0xcbf29ce484222325;
+		for &id in self.raw_ids() {
+			for byte in id.to_le_bytes() {
+				hash ^= byte as u64;
+				hash = hash.wrapping_mul(0x100000001b3);
+			}
+		}
+		hash
+	}
+
 	pub fn children(&self, mut cb: impl FnMut(Entity)) {
+		for child in self.children_iter() {
+			cb(child);
+		}
+	}
+
+	/// Iterates the direct children of this entity
+	pub fn children_iter(&self) -> impl Iterator<Item = Entity> {
+		self.children_of_rel(unsafe { EcsChildOf })
+	}
+
+	/// Iterates all entities which have a (relation, self) pair
+	pub fn children_of_rel<R: AsEcsId>(&self, relation: R) -> impl Iterator<Item = Entity> {
+		let mut children = Vec::new();
 		unsafe {
 			let mut desc: ecs_filter_desc_t = MaybeUninit::zeroed().assume_init();
-			desc.terms[0].id = ecs_make_pair(EcsChildOf, self.id());
+			desc.terms[0].id = ecs_make_pair(relation.id(), self.id());
 			desc.terms[1].id = EcsPrefab;
 			desc.terms[1].oper = ecs_oper_kind_t_EcsOptional;
 
@@ -282,11 +888,93 @@ impl Entity {
 			while ecs_filter_next(&mut it) {
 				for i in 0..it.count {
                     let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(self.world, *eid);
-					cb(e);
+					children.push(Entity::new(self.world, *eid));
 				}
 			}
+
+			ecs_filter_fini(filter);
 		}
+		children.into_iter()
+	}
+}
+
+/// Cached reference to a component of an entity. It stays valid when the entity
+/// moves to another table, and returns None once the entity or component is gone.
+pub struct Ref<T: Component> {
+	world: *mut ecs_world_t,
+	comp_id: ecs_id_t,
+	reference: ecs_ref_t,
+	_phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: Component> Ref<T> {
+	pub fn entity(&self) -> Entity {
+		Entity::new(self.world, self.reference.entity)
+	}
+
+	fn get_ptr(&mut self) -> *mut T {
+		// the cached record is not valid anymore once the entity is deleted
+		if !unsafe { ecs_is_alive(self.world, self.reference.entity) } {
+			return std::ptr::null_mut();
+		}
+		unsafe { ecs_ref_get_id(self.world, &mut self.reference, self.comp_id) as *mut T }
+	}
+
+	pub fn get(&mut self) -> Option<&T> {
+		unsafe { self.get_ptr().as_ref() }
+	}
+
+	pub fn get_mut(&mut self) -> Option<&mut T> {
+		unsafe { self.get_ptr().as_mut() }
+	}
+}
+
+/// Relation from a prefab to the children which are its slots, see Entity::slot_of()
+pub struct Slot;
+
+pub struct EntityTargets {
+	entity: Entity,
+	relation: ecs_id_t,
+	index: i32,
+}
+
+impl Iterator for EntityTargets {
+	type Item = Entity;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let target = self.entity.target(self.relation, self.index);
+		if target.is_some() {
+			self.index += 1;
+		}
+		target
+	}
+}
+
+// Formatting must not touch dead entities, those only show the raw id
+impl std::fmt::Display for Entity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.is_alive() {
+			let name = self.name();
+			if !name.is_empty() {
+				return write!(f, "{}", name);
+			}
+		}
+		write!(f, "{}", self.entity)
+	}
+}
+
+impl std::fmt::Debug for Entity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut s = f.debug_struct("Entity");
+		s.field("id", &strip_generation(self.entity))
+			.field("generation", &self.generation());
+		if self.is_alive() {
+			s.field("name", &self.name())
+				.field("type", &self.type_string());
+		} else {
+			s.field("alive", &false);
+		}
+		s.finish()
 	}
 }
 
@@ -295,3 +983,31 @@ impl From<Entity> for u64 {
         e.raw()
     }
 }
+
+/// Returned by Entity::set_name when a sibling already has the name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameError {
+	pub name: String,
+	pub existing: Entity,
+}
+
+impl std::fmt::Display for NameError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Name '{}' is already used by entity {}", self.name, self.existing.raw())
+	}
+}
+
+impl std::error::Error for NameError {}
+
+// Restores the previous scope or with id of scope() and with_rel(), also when f panics
+struct RestoreOnDrop {
+	world: *mut ecs_world_t,
+	prev: ecs_id_t,
+	set: unsafe extern "C" fn(*mut ecs_world_t, ecs_id_t) -> ecs_id_t,
+}
+
+impl Drop for RestoreOnDrop {
+	fn drop(&mut self) {
+		unsafe { (self.set)(self.world, self.prev) };
+	}
+}
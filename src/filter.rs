@@ -20,70 +20,198 @@ impl Filter {
 		Filter { world, filter }
 	}
 
+	/// Same as new_1, the filter is cached with the world and reused by later calls
+	pub(crate) fn cached_1<A: Component>(world: *mut ecs_world_t) -> Self {
+		let id = WorldInfoCache::get_component_id_for_type::<A>(world).expect("Component type not registered!");
+		let filter = WorldInfoCache::get_or_init_filter(world, &[id]);
+		Filter { world, filter }
+	}
+
 	pub fn each_1<A: Component>(&self, mut cb: impl FnMut(Entity, &A)) {
 		unsafe {
-			let mut it = ecs_filter_iter(self.world, self.filter);
-			while ecs_filter_next(&mut it) {
-				let a = ecs_field::<A>(&it, 1);
-				for i in 0..it.count {
-                    let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(self.world, *eid);
-					let va = a.offset(i as isize);
-					cb(e, va.as_ref().unwrap());
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, self.filter);
+				while ecs_filter_next(&mut it) {
+					let a = ecs_field::<A>(&it, 1);
+					for i in 0..it.count {
+						let eid = it.entities.offset(i as isize).as_ref().unwrap();
+						let e = Entity::new(self.world, *eid);
+						let va = a.offset(i as isize);
+						cb(e, va.as_ref().unwrap());
+					}
 				}
-			}
+			});
 		}		
 	}
 
 	pub fn each<'w, G: ComponentGroup<'w>>(&'w self, mut cb: impl FnMut(Entity, G::RefTuple)) {
 		unsafe {
-			let mut it = ecs_filter_iter(self.world, self.filter);
-			while ecs_filter_next(&mut it) {
-				// Iterate all entities for the type
-				for i in 0..it.count {
-                    let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(self.world, *eid);
-					let rt = G::iter_as_ref_tuple(&it, i as isize);
-					cb(e, rt);
+			check_filter_components::<G>(self.world, self.filter);
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, self.filter);
+				while ecs_filter_next(&mut it) {
+					each_ref_tuple::<G>(&it, &mut cb);
 				}
-			}
+			});
 		}				
 	}
 
+	/// Calls cb for every matched entity with the elements of G, which map to the first
+	/// terms. Option elements are None when their (optional) term isn't set. Takes the
+	/// filter mutably, so the &mut elements can't alias those of a nested for_each.
+	pub fn for_each<'w, G: EachGroup<'w>>(&'w mut self, mut cb: impl FnMut(Entity, G::Item)) {
+		unsafe {
+			check_filter_terms::<G>(self.world, self.filter);
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, self.filter);
+				while ecs_filter_next(&mut it) {
+					each_group::<G>(&it, &mut cb);
+				}
+			});
+		}				
+	}
+
+	/// Same as for_each, the iteration stops as soon as cb returns false
+	pub fn each_while<'w, G: EachGroup<'w>>(&'w mut self, mut cb: impl FnMut(Entity, G::Item) -> bool) {
+		unsafe {
+			check_filter_terms::<G>(self.world, self.filter);
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, self.filter);
+				while ecs_filter_next(&mut it) {
+					if !each_group_while::<G>(&it, &mut cb) {
+						// flecs only frees the iterator when it runs to the end
+						ecs_iter_fini(&mut it);
+						break;
+					}
+				}
+			});
+		}
+	}
+
+	/// Same as for_each, cb also gets the running index of the entity in the iteration,
+	/// e.g. for writing results into a preallocated buffer
+	pub fn each_indexed<'w, G: EachGroup<'w>>(&'w mut self, mut cb: impl FnMut(usize, Entity, G::Item)) {
+		let mut index = 0;
+		self.for_each::<G>(|e, item| {
+			cb(index, e, item);
+			index += 1;
+		});
+	}
+
+	#[deprecated(note = "use for_each with &mut elements, e.g. for_each::<(&mut A, &mut B)>")]
 	pub fn each_mut<'w, G: ComponentGroup<'w>>(&mut self, mut cb: impl FnMut(Entity, G::MutRefTuple)) {
 		unsafe {
-			let mut it = ecs_filter_iter(self.world, self.filter);
-			while ecs_filter_next(&mut it) {
-				// Iterate all entities for the type
-				for i in 0..it.count {
-                    let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(self.world, *eid);
-
-					// TODO - performance is poor here due to looking up terms for each tuple entry * each entity
-					// we need to rework this to take slices of components, determined outside the loop
-					// so optimal iteration can occur
-					let rt = G::iter_as_mut_tuple(&it, i as isize);
-					cb(e, rt);
+			check_filter_components::<G>(self.world, self.filter);
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, self.filter);
+				while ecs_filter_next(&mut it) {
+					each_mut_tuple::<G>(&it, &mut cb);
 				}
-			}
+			});
 		}				
 	}
 
 	pub fn iter<F: FnMut(&Iter)>(&self, mut func: F) {
 		unsafe {
-			let mut it = ecs_filter_iter(self.world, self.filter);
-			while ecs_filter_next(&mut it) {
-				let iter = Iter::new(&mut it);
-				func(&iter);
-			}
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, self.filter);
+				while ecs_filter_next(&mut it) {
+					let iter = Iter::new(&mut it);
+					func(&iter);
+				}
+			});
 		}				
 	}
 
+	/// Same as iter, with a mutable Iter for the accessors which hand out mutable
+	/// references, such as Iter::field_slice_mut
+	pub fn iter_mut<F: FnMut(&mut Iter)>(&self, mut func: F) {
+		unsafe {
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, self.filter);
+				while ecs_filter_next(&mut it) {
+					let mut iter = Iter::new(&mut it);
+					func(&mut iter);
+				}
+			});
+		}
+	}
+
+	/// Returns an iterator over the matched entities
+	pub fn iter_entities(&self) -> EntityIter<'_> {
+		EntityIter::new(unsafe { ecs_filter_iter(self.world, self.filter) })
+	}
+
+	/// Returns an iterator over the matched entities and their components G
+	pub fn iter_tuples<'w, G: ComponentGroup<'w>>(&'w self) -> TupleIter<'w, G> {
+		unsafe { check_filter_components::<G>(self.world, self.filter) };
+		TupleIter::new(self.iter_entities())
+	}
+
+	/// Returns the first matched entity
+	pub fn first(&self) -> Option<Entity> {
+		self.iter_entities().next()
+	}
+
+	/// Returns the first matched entity for which pred returns true, iteration stops there
+	pub fn find(&self, pred: impl FnMut(&Entity) -> bool) -> Option<Entity> {
+		self.iter_entities().find(pred)
+	}
+
+	/// Returns the number of matched entities, without accessing component data
+	pub fn count(&self) -> usize {
+		unsafe {
+			let mut it = ecs_filter_iter(self.world, self.filter);
+			ecs_iter_count(&mut it) as usize
+		}
+	}
+
+	/// Serializes all matched results to a single JSON document, component values
+	/// are only written for components with reflection data
+	pub fn to_json(&self, opts: &IterToJsonOptions) -> String {
+		unsafe {
+			let mut it = ecs_filter_iter(self.world, self.filter);
+			opts.iter_to_json(self.world, &mut it)
+		}
+	}
+
+	/// Returns true if no entities are matched, without accessing component data
+	pub fn is_empty(&self) -> bool {
+		unsafe {
+			let mut it = ecs_filter_iter(self.world, self.filter);
+			!ecs_iter_is_true(&mut it)
+		}
+	}
+
+}
+
+// Shows the terms of the filter in the query language, e.g. "[in] Position, !Frozen"
+impl std::fmt::Display for Filter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let expr = unsafe { flecs_to_owned_string(ecs_filter_str(self.world, self.filter)) };
+		write!(f, "{}", expr)
+	}
 }
 
+/// Returned when building a filter or query fails, e.g. because its expression can't be
+/// parsed. flecs logs the details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+	pub expr: String,
+}
+
+impl std::fmt::Display for FilterError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "invalid filter expression \"{}\"", self.expr)
+	}
+}
+
+impl std::error::Error for FilterError {}
+
 pub struct FilterBuilder<'w> {
 	world: &'w World,
 	desc: ecs_filter_desc_t,
+	expr_temp: String,
 	next_term_index: usize,
 }
 
@@ -100,6 +228,11 @@ impl<'w> TermBuilder for FilterBuilder<'w> {
         &mut self.desc.terms[self.next_term_index]
     }
 
+    fn last_term(&mut self) -> &mut ecs_term_t {
+        assert!(self.next_term_index > 0, "No term to modify, add one first!");
+        &mut self.desc.terms[self.next_term_index - 1]
+    }
+
     fn next_term(&mut self) {
         self.next_term_index += 1;
     }
@@ -110,21 +243,41 @@ impl<'w> FilterBuilder<'w> {
 		Self { 
 			world,
 			desc: unsafe { MaybeUninit::zeroed().assume_init() },
+			expr_temp: "".to_owned(),
 			next_term_index: 0
 		}
 	}
 
+	/// Adds the terms of a query language expression, e.g. "Position, !Velocity". The
+	/// fields of these terms are accessed with Iter::field_dynamic.
+	pub fn expr(mut self, expr: &str) -> Self {
+		self.expr_temp = expr.to_owned();
+		self
+	}
+
 	pub fn with_components<'c, G: ComponentGroup<'c>>(mut self) -> Self {
 		G::populate(&mut self);
 		self
 	}
 
+	/// Panics if the filter is invalid, see try_build
 	pub fn build(self) -> Filter {
+		self.try_build().unwrap()
+	}
+
+	pub fn try_build(mut self) -> Result<Filter, FilterError> {
+		let expr_c_str = std::ffi::CString::new(self.expr_temp.as_str()).unwrap();
+		if !self.expr_temp.is_empty() {
+			self.desc.expr = expr_c_str.as_ptr();
+		}
 		let filter = unsafe { ecs_filter_init(self.world.raw(), &self.desc) };
-		Filter { 
+		if filter.is_null() {
+			return Err(FilterError { expr: self.expr_temp });
+		}
+		Ok(Filter { 
 			world: self.world.raw(), 
 			filter 
-		}
+		})
 	}
 }
 
@@ -137,9 +290,15 @@ pub struct FilterGroup<'c, G: ComponentGroup<'c>> {
 // TODO - need to support generalized API via tuples or something
 impl<'c, G: ComponentGroup<'c>> FilterGroup<'c, G> {
 	pub fn new(world: &'c World) -> Self {
+		Self::new_inout(world, ecs_inout_kind_t_EcsInOutDefault)
+	}
+
+	// Annotates the terms with inout, for filters only used with either each or each_mut
+	pub(crate) fn new_inout(world: &'c World, inout: ecs_inout_kind_t) -> Self {
 		let world_raw = world.raw();
 		let mut desc: ecs_filter_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
 		unsafe { G::fill_descriptor(world_raw, &mut desc) };
+		set_default_inout(&mut desc.terms[..G::COUNT], inout);
 
 		let filter = unsafe { ecs_filter_init(world_raw, &desc) };
 		FilterGroup { 
@@ -153,16 +312,12 @@ impl<'c, G: ComponentGroup<'c>> FilterGroup<'c, G> {
 		let world_raw = self.world.raw();
 		// println!("each - filter: {}, {}, {}", f.term_cache_used, f.terms as u64, f.term_cache.as_ptr() as u64);
 		unsafe {
-			let mut it = ecs_filter_iter(world_raw, self.filter);
-			while ecs_filter_next(&mut it) {
-				// Iterate all entities for the type
-				for i in 0..it.count {
-                    let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(world_raw, *eid);
-					let rt = G::iter_as_ref_tuple(&it, i as isize);
-					cb(e, rt);
+			defer_iter(world_raw, || {
+				let mut it = ecs_filter_iter(world_raw, self.filter);
+				while ecs_filter_next(&mut it) {
+					each_ref_tuple::<G>(&it, &mut cb);
 				}
-			}
+			});
 		}				
 	}
 
@@ -170,20 +325,102 @@ impl<'c, G: ComponentGroup<'c>> FilterGroup<'c, G> {
 		let world_raw = self.world.raw();
 		// println!("each_mut - filter: {}, {}, {}", f.term_cache_used, f.terms as u64, f.term_cache.as_ptr() as u64);
 		unsafe {
-			let mut it = ecs_filter_iter(world_raw, self.filter);
-			while ecs_filter_next(&mut it) {
-				// Iterate all entities for the type
-				for i in 0..it.count {
-                    let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(world_raw, *eid);
-
-					// TODO - performance is poor here due to looking up terms for each tuple entry * each entity
-					// we need to rework this to take slices of components, determined outside the loop
-					// so optimal iteration can occur
-					let rt = G::iter_as_mut_tuple(&it, i as isize);
-					cb(e, rt);
+			defer_iter(world_raw, || {
+				let mut it = ecs_filter_iter(world_raw, self.filter);
+				while ecs_filter_next(&mut it) {
+					each_mut_tuple::<G>(&it, &mut cb);
 				}
-			}
+			});
 		}				
 	}
 }
+
+/// Iterates the results of a filter or query one entity at a time. Dropping it before
+/// the end finalizes the flecs iterator.
+pub struct EntityIter<'a> {
+	// boxed, flecs iterators point into themselves once iterated
+	it: Box<ecs_iter_t>,
+	index: i32,
+	count: i32,
+	done: bool,
+	_borrow: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> EntityIter<'a> {
+	pub(crate) fn new(it: ecs_iter_t) -> Self {
+		Self {
+			it: Box::new(it),
+			index: 0,
+			count: 0,
+			done: false,
+			_borrow: Default::default(),
+		}
+	}
+
+	// Moves to the next entity, returns its index in the current result
+	fn advance(&mut self) -> Option<isize> {
+		loop {
+			if self.index < self.count {
+				self.index += 1;
+				return Some((self.index - 1) as isize);
+			}
+			if self.done {
+				return None;
+			}
+			// flecs finalizes the iterator when it returns false
+			if unsafe { ecs_iter_next(&mut *self.it) } {
+				self.index = 0;
+				self.count = self.it.count;
+			} else {
+				self.done = true;
+			}
+		}
+	}
+
+	fn entity(&self, index: isize) -> Entity {
+		unsafe { Entity::new(self.it.world, *self.it.entities.offset(index)) }
+	}
+}
+
+impl<'a> Iterator for EntityIter<'a> {
+	type Item = Entity;
+
+	fn next(&mut self) -> Option<Entity> {
+		let index = self.advance()?;
+		Some(self.entity(index))
+	}
+}
+
+impl<'a> Drop for EntityIter<'a> {
+	fn drop(&mut self) {
+		if !self.done {
+			unsafe { ecs_iter_fini(&mut *self.it) };
+		}
+	}
+}
+
+/// Iterates the results of a filter or query with the components G of each entity.
+/// The references point into table storage, see Entity::get_mut.
+pub struct TupleIter<'a, G: ComponentGroup<'a>> {
+	entities: EntityIter<'a>,
+	_phantom: std::marker::PhantomData<G>,
+}
+
+impl<'a, G: ComponentGroup<'a>> TupleIter<'a, G> {
+	pub(crate) fn new(entities: EntityIter<'a>) -> Self {
+		Self {
+			entities,
+			_phantom: Default::default(),
+		}
+	}
+}
+
+impl<'a, G: ComponentGroup<'a>> Iterator for TupleIter<'a, G> {
+	type Item = (Entity, G::RefTuple);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let index = self.entities.advance()?;
+		let components = unsafe { G::iter_as_ref_tuple(&self.entities.it, index) };
+		Some((self.entities.entity(index), components))
+	}
+}
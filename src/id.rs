@@ -12,6 +12,27 @@ impl AsEcsId for Id {
 	}
 }
 
+/// Matches any id in a pair, e.g. term_pair(likes, Wildcard) matches (Likes, *).
+/// An entity with several matching pairs is returned once per pair.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Wildcard;
+
+impl AsEcsId for Wildcard {
+	fn id(&self) -> ecs_id_t {
+		unsafe { EcsWildcard }
+	}
+}
+
+/// Like Wildcard, but an entity is returned only once no matter how many pairs match
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Any;
+
+impl AsEcsId for Any {
+	fn id(&self) -> ecs_id_t {
+		unsafe { EcsAny }
+	}
+}
+
 // Bindgen creates i64 for some large ull :(
 const RUST_ECS_ID_FLAGS_MASK: u64 = ECS_ID_FLAGS_MASK as u64;
 
@@ -23,6 +44,16 @@ pub fn ecs_pair(pred: u64, obj: u64) -> u64 {
 	unsafe { ECS_PAIR | ecs_entity_t_comb(obj, pred) }
 }
 
+/// Removes the generation bits from an entity id, leaving only the index part
+pub fn strip_generation(entity: EntityId) -> EntityId {
+	unsafe { ecs_strip_generation(entity) }
+}
+
+/// Returns the generation of an entity id, which is bumped each time the id is recycled
+pub fn entity_generation(entity: EntityId) -> u32 {
+	((entity & ECS_GENERATION_MASK) >> 32) as u32
+}
+
 fn ecs_pair_relation(e: u64) -> u64 {
 	(ecs_entity_t_hi(e & ECS_COMPONENT_MASK)) as u64
 }
@@ -31,6 +62,22 @@ fn ecs_pair_object(e: u64) -> u64 {
 	(ecs_entity_t_lo(e)) as u64
 }
 
+/// Returns the first element (relation) of a pair id. The generation is not part
+/// of a pair, use World::get_alive() to recover the full entity id.
+pub fn pair_first(pair: ecs_id_t) -> EntityId {
+	ecs_pair_relation(pair)
+}
+
+/// Returns the second element (object/target) of a pair id, without generation
+pub fn pair_second(pair: ecs_id_t) -> EntityId {
+	ecs_pair_object(pair)
+}
+
+/// Returns true if the id is a pair
+pub fn is_pair(id: ecs_id_t) -> bool {
+	unsafe { (id & RUST_ECS_ID_FLAGS_MASK) == ECS_PAIR }
+}
+
 /* TODO: Review these macro like functions again since v3.0
 /* Get object from pair with the correct (current) generation count */
 #define ecs_pair_first(world, pair) ecs_get_alive(world, ECS_PAIR_FIRST(pair))
@@ -101,4 +148,125 @@ impl Id {
 		unsafe { flecs_to_rust_str(id_str) }
     }
 
-}
\ No newline at end of file
+}
+/// An id which is not bound to a world: an entity, component, pair or an id with flags.
+/// Unlike the EntityId alias it can't be zero, so Option<RawId> is as small as a u64.
+#[repr(transparent)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone)]
+pub struct RawId(std::num::NonZeroU64);
+
+const _: () = assert!(std::mem::size_of::<Option<RawId>>() == 8);
+
+impl RawId {
+	/// Returns None for 0, which is not a valid id
+	pub fn new(id: ecs_id_t) -> Option<Self> {
+		std::num::NonZeroU64::new(id).map(Self)
+	}
+
+	pub fn raw(&self) -> ecs_id_t {
+		self.0.get()
+	}
+
+	pub fn is_pair(&self) -> bool {
+		is_pair(self.raw())
+	}
+
+	/// The relation of a pair, None if this is not a pair
+	pub fn first(&self) -> Option<RawId> {
+		self.as_pair().map(|p| p.first())
+	}
+
+	/// The target of a pair, None if this is not a pair
+	pub fn second(&self) -> Option<RawId> {
+		self.as_pair().map(|p| p.second())
+	}
+
+	/// Returns true if the id has the flag, e.g. ECS_OVERRIDE or ECS_TOGGLE
+	pub fn has_flag(&self, flag: ecs_id_t) -> bool {
+		(self.raw() & RUST_ECS_ID_FLAGS_MASK) & flag == flag
+	}
+
+	pub fn as_pair(&self) -> Option<PairId> {
+		if self.is_pair() { Some(PairId(self.0)) } else { None }
+	}
+}
+
+impl AsEcsId for RawId {
+	fn id(&self) -> ecs_id_t {
+		self.raw()
+	}
+}
+
+impl TryFrom<u64> for RawId {
+	type Error = std::num::TryFromIntError;
+
+	fn try_from(id: u64) -> Result<Self, Self::Error> {
+		std::num::NonZeroU64::try_from(id).map(Self)
+	}
+}
+
+impl From<RawId> for u64 {
+	fn from(id: RawId) -> u64 {
+		id.raw()
+	}
+}
+
+impl From<Entity> for RawId {
+	fn from(entity: Entity) -> Self {
+		RawId::new(entity.raw()).expect("Entity has no id!")
+	}
+}
+
+/// A pair id (relation, target), see RawId
+#[repr(transparent)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone)]
+pub struct PairId(std::num::NonZeroU64);
+
+const _: () = assert!(std::mem::size_of::<Option<PairId>>() == 8);
+
+impl PairId {
+	pub fn new<R: AsEcsId, T: AsEcsId>(first: R, second: T) -> Self {
+		Self(std::num::NonZeroU64::new(ecs_pair(first.id(), second.id())).unwrap())
+	}
+
+	pub fn raw(&self) -> ecs_id_t {
+		self.0.get()
+	}
+
+	/// The relation, without generation like pair_first()
+	pub fn first(&self) -> RawId {
+		RawId::new(pair_first(self.raw())).expect("Pair has no relation!")
+	}
+
+	/// The target, without generation like pair_second()
+	pub fn second(&self) -> RawId {
+		RawId::new(pair_second(self.raw())).expect("Pair has no target!")
+	}
+}
+
+impl AsEcsId for PairId {
+	fn id(&self) -> ecs_id_t {
+		self.raw()
+	}
+}
+
+impl From<PairId> for RawId {
+	fn from(pair: PairId) -> Self {
+		RawId(pair.0)
+	}
+}
+
+impl TryFrom<RawId> for PairId {
+	type Error = RawId;
+
+	/// Fails with the id if it isn't a pair
+	fn try_from(id: RawId) -> Result<Self, Self::Error> {
+		id.as_pair().ok_or(id)
+	}
+}
+
+impl From<PairId> for u64 {
+	fn from(pair: PairId) -> u64 {
+		pair.raw()
+	}
+}
@@ -0,0 +1,565 @@
+use crate::*;
+use std::collections::HashMap;
+
+// Json addon support
+//
+// Component values are only serialized for components with reflection data, see meta.rs.
+// Anything else shows up as an id without a value.
+
+/// Controls what Entity::to_json serializes, mirrors ecs_entity_to_json_desc_t.
+/// The default matches the flecs defaults: the path and base components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntityToJsonOptions {
+	pub serialize_path: bool,
+	pub serialize_meta_ids: bool,
+	pub serialize_label: bool,
+	pub serialize_brief: bool,
+	pub serialize_link: bool,
+	pub serialize_color: bool,
+	pub serialize_id_labels: bool,
+	pub serialize_base: bool,
+	pub serialize_private: bool,
+	pub serialize_hidden: bool,
+	pub serialize_values: bool,
+	/// Requires serialize_values
+	pub serialize_type_info: bool,
+}
+
+impl Default for EntityToJsonOptions {
+	fn default() -> Self {
+		EntityToJsonOptions {
+			serialize_path: true,
+			serialize_meta_ids: false,
+			serialize_label: false,
+			serialize_brief: false,
+			serialize_link: false,
+			serialize_color: false,
+			serialize_id_labels: false,
+			serialize_base: true,
+			serialize_private: false,
+			serialize_hidden: false,
+			serialize_values: false,
+			serialize_type_info: false,
+		}
+	}
+}
+
+impl EntityToJsonOptions {
+	fn to_desc(self) -> ecs_entity_to_json_desc_t {
+		ecs_entity_to_json_desc_t {
+			serialize_path: self.serialize_path,
+			serialize_meta_ids: self.serialize_meta_ids,
+			serialize_label: self.serialize_label,
+			serialize_brief: self.serialize_brief,
+			serialize_link: self.serialize_link,
+			serialize_color: self.serialize_color,
+			serialize_id_labels: self.serialize_id_labels,
+			serialize_base: self.serialize_base,
+			serialize_private: self.serialize_private,
+			serialize_hidden: self.serialize_hidden,
+			serialize_values: self.serialize_values,
+			serialize_type_info: self.serialize_type_info,
+		}
+	}
+}
+
+/// Controls what Filter::to_json and Query::to_json serialize, mirrors ecs_iter_to_json_desc_t.
+/// The default matches the flecs defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IterToJsonOptions {
+	pub serialize_term_ids: bool,
+	pub serialize_ids: bool,
+	pub serialize_sources: bool,
+	pub serialize_variables: bool,
+	pub serialize_is_set: bool,
+	pub serialize_values: bool,
+	pub serialize_entities: bool,
+	pub serialize_entity_labels: bool,
+	pub serialize_entity_ids: bool,
+	pub serialize_variable_labels: bool,
+	pub serialize_variable_ids: bool,
+	pub serialize_colors: bool,
+	pub measure_eval_duration: bool,
+	pub serialize_type_info: bool,
+}
+
+impl Default for IterToJsonOptions {
+	fn default() -> Self {
+		IterToJsonOptions {
+			serialize_term_ids: true,
+			serialize_ids: true,
+			serialize_sources: true,
+			serialize_variables: true,
+			serialize_is_set: true,
+			serialize_values: true,
+			serialize_entities: true,
+			serialize_entity_labels: false,
+			serialize_entity_ids: false,
+			serialize_variable_labels: false,
+			serialize_variable_ids: false,
+			serialize_colors: false,
+			measure_eval_duration: false,
+			serialize_type_info: false,
+		}
+	}
+}
+
+impl IterToJsonOptions {
+	// Serializes all results of the iterator as a single {"results":[...]} document
+	pub(crate) unsafe fn iter_to_json(self, world: *mut ecs_world_t, it: &mut ecs_iter_t) -> String {
+		let desc = ecs_iter_to_json_desc_t {
+			serialize_term_ids: self.serialize_term_ids,
+			serialize_ids: self.serialize_ids,
+			serialize_sources: self.serialize_sources,
+			serialize_variables: self.serialize_variables,
+			serialize_is_set: self.serialize_is_set,
+			serialize_values: self.serialize_values,
+			serialize_entities: self.serialize_entities,
+			serialize_entity_labels: self.serialize_entity_labels,
+			serialize_entity_ids: self.serialize_entity_ids,
+			serialize_variable_labels: self.serialize_variable_labels,
+			serialize_variable_ids: self.serialize_variable_ids,
+			serialize_colors: self.serialize_colors,
+			measure_eval_duration: self.measure_eval_duration,
+			serialize_type_info: self.serialize_type_info,
+		};
+		flecs_to_owned_string(ecs_iter_to_json(world, it, &desc))
+	}
+}
+
+impl Entity {
+	/// Serializes the entity with its ids and (reflected) component values.
+	/// Returns an empty string if a component holds a value that can't be serialized.
+	pub fn to_json(&self, opts: &EntityToJsonOptions) -> String {
+		let desc = opts.to_desc();
+		unsafe { flecs_to_owned_string(ecs_entity_to_json(self.world(), self.raw(), &desc)) }
+	}
+}
+
+impl World {
+	/// Serializes the type info of a component, or of the data type of a pair.
+	/// Returns an empty string if the id has no reflection data.
+	pub fn to_json_id<T: AsEcsId>(&self, id: T) -> String {
+		unsafe {
+			let type_id = ecs_get_typeid(self.raw(), id.id());
+			if type_id == 0 {
+				return String::new();
+			}
+			flecs_to_owned_string(ecs_type_info_to_json(self.raw(), type_id))
+		}
+	}
+}
+
+/// Error returned when a JSON string can't be applied, offset is where reading stopped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+	pub offset: usize,
+	pub reason: String,
+}
+
+impl std::fmt::Display for JsonError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Invalid JSON at offset {}: {}", self.offset, self.reason)
+	}
+}
+
+impl std::error::Error for JsonError {}
+
+// flecs 3.0 has no entity deserializer, this reads the format written by ecs_entity_to_json
+// and only leaves component values to flecs (ecs_parse_json)
+struct JsonReader<'a> {
+	json: &'a [u8],
+	c_json: std::ffi::CString,
+	pos: usize,
+	// entities created for the paths of a World::from_json document, see ensure_path
+	paths: &'a HashMap<String, EntityId>,
+}
+
+impl<'a> JsonReader<'a> {
+	fn new(json: &'a str, paths: &'a HashMap<String, EntityId>) -> Result<Self, JsonError> {
+		let c_json = std::ffi::CString::new(json).map_err(|e| JsonError { offset: e.nul_position(), reason: "unexpected nul character".to_owned() })?;
+		Ok(JsonReader { json: json.as_bytes(), c_json, pos: 0, paths })
+	}
+
+	fn error<T>(&self, reason: impl Into<String>) -> Result<T, JsonError> {
+		Err(JsonError { offset: self.pos, reason: reason.into() })
+	}
+
+	fn peek(&mut self) -> Option<u8> {
+		while self.pos < self.json.len() && self.json[self.pos].is_ascii_whitespace() {
+			self.pos += 1;
+		}
+		self.json.get(self.pos).copied()
+	}
+
+	fn expect(&mut self, ch: u8) -> Result<(), JsonError> {
+		if self.peek() != Some(ch) {
+			return self.error(format!("expected '{}'", ch as char));
+		}
+		self.pos += 1;
+		Ok(())
+	}
+
+	// Consumes ch if it is the next character
+	fn accept(&mut self, ch: u8) -> bool {
+		let found = self.peek() == Some(ch);
+		if found {
+			self.pos += 1;
+		}
+		found
+	}
+
+	fn string(&mut self) -> Result<String, JsonError> {
+		self.expect(b'"')?;
+		let mut bytes = Vec::new();
+		while let Some(&ch) = self.json.get(self.pos) {
+			self.pos += 1;
+			match ch {
+				b'"' => return Ok(String::from_utf8_lossy(&bytes).into_owned()),
+				b'\\' => {
+					let escaped = self.json.get(self.pos).copied();
+					self.pos += 1;
+					let decoded = match escaped {
+						Some(b'n') => '\n',
+						Some(b't') => '\t',
+						Some(b'r') => '\r',
+						Some(b'b') => '\u{8}',
+						Some(b'f') => '\u{c}',
+						Some(b'u') => self.unicode_escape()?,
+						Some(ch) => {
+							bytes.push(ch);
+							continue;
+						}
+						None => break,
+					};
+					bytes.extend_from_slice(decoded.encode_utf8(&mut [0; 4]).as_bytes());
+				}
+				_ => bytes.push(ch),
+			}
+		}
+		self.error("unterminated string")
+	}
+
+	// The character of a \uXXXX escape, read after the u. A UTF-16 surrogate pair is
+	// written as two escapes.
+	fn unicode_escape(&mut self) -> Result<char, JsonError> {
+		let high = self.hex4()?;
+		let code = if (0xD800..0xDC00).contains(&high) {
+			if self.json.get(self.pos..self.pos + 2) != Some(b"\\u") {
+				return self.error("unpaired surrogate in \\u escape");
+			}
+			self.pos += 2;
+			let low = self.hex4()?;
+			if !(0xDC00..0xE000).contains(&low) {
+				return self.error("unpaired surrogate in \\u escape");
+			}
+			0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+		} else {
+			high
+		};
+		match char::from_u32(code) {
+			Some(ch) => Ok(ch),
+			None => self.error("unpaired surrogate in \\u escape"),
+		}
+	}
+
+	fn hex4(&mut self) -> Result<u32, JsonError> {
+		let digits = self.json.get(self.pos..self.pos + 4)
+			.filter(|digits| digits.iter().all(u8::is_ascii_hexdigit))
+			.and_then(|digits| u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok());
+		match digits {
+			Some(code) => {
+				self.pos += 4;
+				Ok(code)
+			}
+			None => self.error("expected 4 hex digits in \\u escape"),
+		}
+	}
+
+	// A string naming an entity, flecs reads it as a C string so it can't contain nul
+	fn path(&mut self) -> Result<String, JsonError> {
+		self.peek();
+		let start = self.pos;
+		let path = self.string()?;
+		if path.contains('\0') {
+			self.pos = start;
+			return self.error("unexpected nul character in path");
+		}
+		Ok(path)
+	}
+
+	// Comma separated elements until close, calls element for each of them
+	fn list(&mut self, open: u8, close: u8, mut element: impl FnMut(&mut Self) -> Result<(), JsonError>) -> Result<(), JsonError> {
+		self.expect(open)?;
+		if self.accept(close) {
+			return Ok(());
+		}
+		loop {
+			element(self)?;
+			if self.accept(close) {
+				return Ok(());
+			}
+			self.expect(b',')?;
+		}
+	}
+
+	fn skip_value(&mut self) -> Result<(), JsonError> {
+		match self.peek() {
+			Some(b'"') => self.string().map(|_| ()),
+			Some(b'{') => self.list(b'{', b'}', |r| {
+				r.string()?;
+				r.expect(b':')?;
+				r.skip_value()
+			}),
+			Some(b'[') => self.list(b'[', b']', |r| r.skip_value()),
+			Some(_) => {
+				let start = self.pos;
+				while self.pos < self.json.len() && !b",]} \t\r\n".contains(&self.json[self.pos]) {
+					self.pos += 1;
+				}
+				if self.pos == start {
+					return self.error("expected a value");
+				}
+				Ok(())
+			}
+			None => self.error("unexpected end of input"),
+		}
+	}
+
+	// ["Component"] or ["Relation", "Object"]
+	fn id(&mut self, world: *mut ecs_world_t) -> Result<EntityId, JsonError> {
+		let mut parts = Vec::new();
+		self.list(b'[', b']', |r| {
+			let start = r.pos;
+			let path = r.path()?;
+			let e = match r.paths.get(&path) {
+				Some(&e) => e,
+				None => {
+					let c_path = std::ffi::CString::new(path.as_str()).unwrap();
+					let sep = std::ffi::CString::new(".").unwrap();
+					unsafe { ecs_lookup_path_w_sep(world, 0, c_path.as_ptr(), sep.as_ptr(), std::ptr::null(), true) }
+				}
+			};
+			if e == 0 {
+				r.pos = start;
+				return r.error(format!("unknown entity '{}'", path));
+			}
+			parts.push(e);
+			Ok(())
+		})?;
+
+		match parts[..] {
+			[id] => Ok(id),
+			[relation, object] => Ok(ecs_pair(relation, object)),
+			_ => self.error("expected an id or pair"),
+		}
+	}
+
+	fn value(&mut self, world: *mut ecs_world_t, entity: EntityId, id: EntityId) -> Result<(), JsonError> {
+		unsafe {
+			let type_id = ecs_get_typeid(world, id);
+			if type_id == 0 || !ecs_has_id(world, type_id, FLECS__EEcsMetaTypeSerialized) {
+				// Written as 0, there is no value to read
+				return self.skip_value();
+			}
+
+			self.peek();
+			let base = self.c_json.as_ptr();
+			let ptr = ecs_get_mut_id(world, entity, id);
+			let end = ecs_parse_json(world, base.add(self.pos), type_id, ptr, std::ptr::null());
+			if end.is_null() {
+				return self.error(format!("invalid value for '{}'", flecs_to_owned_string(ecs_id_str(world, id))));
+			}
+			self.pos = end.offset_from(base) as usize;
+			ecs_modified_id(world, entity, id);
+		}
+		Ok(())
+	}
+}
+
+impl Entity {
+	/// Adds the ids of a JSON object written by to_json to this entity, and sets the values of
+	/// components with reflection data. The path is not applied, the entity keeps its name.
+	/// All referenced entities must exist. Ids applied before an error are kept.
+	pub fn from_json(&self, json: &str) -> Result<(), JsonError> {
+		self.apply_json(json, &HashMap::new())
+	}
+
+	fn apply_json(&self, json: &str, paths: &HashMap<String, EntityId>) -> Result<(), JsonError> {
+		let world = self.world();
+		let entity = self.raw();
+		let mut reader = JsonReader::new(json, paths)?;
+		let mut ids = Vec::new();
+
+		reader.list(b'{', b'}', |r| {
+			let key = r.string()?;
+			r.expect(b':')?;
+			match key.as_str() {
+				"ids" => r.list(b'[', b']', |r| {
+					let id = r.id(world)?;
+					unsafe { ecs_add_id(world, entity, id) };
+					ids.push(id);
+					Ok(())
+				}),
+				"values" => {
+					let mut index = 0;
+					r.list(b'[', b']', |r| {
+						let id = match ids.get(index) {
+							Some(&id) => id,
+							None => return r.error("more values than ids"),
+						};
+						index += 1;
+						r.value(world, entity, id)
+					})
+				}
+				_ => r.skip_value(),
+			}
+		})?;
+
+		if reader.peek().is_some() {
+			return reader.error("unexpected characters after the object");
+		}
+		Ok(())
+	}
+}
+
+/// Controls what World::to_json serializes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldToJsonOptions {
+	/// Also serialize modules, components, systems, observers and their children
+	pub serialize_builtin: bool,
+	pub serialize_values: bool,
+}
+
+impl Default for WorldToJsonOptions {
+	fn default() -> Self {
+		WorldToJsonOptions { serialize_builtin: false, serialize_values: true }
+	}
+}
+
+// Entities created by flecs or by registering components/systems, which the target world creates itself
+fn is_builtin(world: *mut ecs_world_t, mut e: EntityId) -> bool {
+	unsafe {
+		while e != 0 {
+			let builtin = [FLECS__EEcsComponent, EcsModule, EcsSystem, EcsObserver, FLECS__EEcsPipeline, ecs_pair(FLECS__EEcsPoly, EcsWildcard)];
+			if builtin.iter().any(|&id| ecs_has_id(world, e, id)) {
+				return true;
+			}
+			e = ecs_get_target(world, e, EcsChildOf, 0);
+		}
+	}
+	false
+}
+
+// Looks up an entity by the path written by to_json, creating it and its parents if needed.
+// Unnamed entities are written as their id, which may belong to another entity in the target
+// world. They are created as new entities instead, once per path, which paths records.
+fn ensure_path(world: *mut ecs_world_t, path: &str, paths: &mut HashMap<String, EntityId>) -> EntityId {
+	if let Some(&e) = paths.get(path) {
+		return e;
+	}
+	let (parent, name) = match path.rfind('.') {
+		Some(i) => (ensure_path(world, &path[..i], paths), &path[i + 1..]),
+		None => (0, path),
+	};
+
+	let e = unsafe {
+		let numeric = name.parse::<EntityId>().is_ok();
+		let c_name = std::ffi::CString::new(name).unwrap();
+		// flecs reads numeric names as ids, so search for an entity actually named like that
+		let existing = if numeric { lookup_named_child(world, parent, name) } else { ecs_lookup_child(world, parent, c_name.as_ptr()) };
+		if existing != 0 {
+			existing
+		} else {
+			let e = ecs_new_id(world);
+			if parent != 0 {
+				ecs_add_id(world, e, ecs_pair(EcsChildOf, parent));
+			}
+			if !numeric {
+				ecs_set_name(world, e, c_name.as_ptr());
+			}
+			e
+		}
+	};
+	paths.insert(path.to_owned(), e);
+	e
+}
+
+// The child of parent (0 for the root) with the given name, without the id lookup of ecs_lookup_child
+fn lookup_named_child(world: *mut ecs_world_t, parent: EntityId, name: &str) -> EntityId {
+	let mut term: ecs_term_t = unsafe { MaybeUninit::zeroed().assume_init() };
+	term.id = unsafe { ecs_pair(FLECS__EEcsIdentifier, EcsName) };
+	unsafe {
+		let mut it = ecs_term_iter(world, &mut term);
+		while ecs_term_next(&mut it) {
+			for i in 0..it.count as isize {
+				let e = *it.entities.offset(i);
+				let e_name = ecs_get_name(world, e);
+				if !e_name.is_null() && std::ffi::CStr::from_ptr(e_name).to_bytes() == name.as_bytes()
+					&& ecs_get_target(world, e, EcsChildOf, 0) == parent {
+					ecs_iter_fini(&mut it);
+					return e;
+				}
+			}
+		}
+	}
+	0
+}
+
+impl World {
+	/// Serializes all entities as a JSON array of entity objects (see Entity::to_json)
+	pub fn to_json(&self, opts: &WorldToJsonOptions) -> String {
+		let entity_opts = EntityToJsonOptions { serialize_base: false, serialize_values: opts.serialize_values, ..Default::default() };
+		let mut entities = Vec::new();
+		self.each_id(unsafe { EcsAny }, |e| {
+			if opts.serialize_builtin || !is_builtin(self.raw(), e.raw()) {
+				entities.push(e.to_json(&entity_opts));
+			}
+		});
+		format!("[{}]", entities.join(", "))
+	}
+
+	/// Applies the entities of a JSON array written by to_json. Entities that already exist
+	/// (by path) are updated, others are created first so they can refer to each other.
+	/// Unnamed entities are written as their id, they are always created as new entities.
+	pub fn from_json(&self, json: &str) -> Result<(), JsonError> {
+		let world = self.raw();
+		let no_paths = HashMap::new();
+		let mut reader = JsonReader::new(json, &no_paths)?;
+		let mut objects = Vec::new();
+
+		reader.list(b'[', b']', |r| {
+			r.peek();
+			let start = r.pos;
+			let mut path = None;
+			r.list(b'{', b'}', |r| {
+				let key = r.string()?;
+				r.expect(b':')?;
+				if key == "path" {
+					path = Some(r.path()?);
+					Ok(())
+				} else {
+					r.skip_value()
+				}
+			})?;
+			match path {
+				Some(path) => objects.push((start, r.pos, path)),
+				None => return r.error("entity without path"),
+			}
+			Ok(())
+		})?;
+		if reader.peek().is_some() {
+			return reader.error("unexpected characters after the array");
+		}
+
+		let mut paths = HashMap::new();
+		let entities: Vec<Entity> = objects.iter()
+			.map(|(_, _, path)| Entity::new(world, ensure_path(world, path, &mut paths)))
+			.collect();
+
+		for ((start, end, _), e) in objects.iter().zip(entities) {
+			e.apply_json(&json[*start..*end], &paths).map_err(|err| JsonError { offset: err.offset + start, ..err })?;
+		}
+		Ok(())
+	}
+}
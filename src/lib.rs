@@ -26,9 +26,19 @@ mod bindings {
 }
 pub use bindings::*;
 
+// Lets the derive macros refer to ::flecs from within this crate too
+extern crate self as flecs;
+pub use flecs_derive::Component;
+
+mod app;
+pub use app::*;
+
 mod binding_util;
 pub use binding_util::*;
 
+mod bulk;
+pub use bulk::*;
+
 mod cache;	// Internal only
 
 mod component;
@@ -37,24 +47,61 @@ pub use component::*;
 mod component_group;
 pub use component_group::*;
 
+mod doc;
+
 mod entity;
 pub use entity::*;
 
 pub mod filter;
 pub use filter::*;
 
+mod json;
+pub use json::*;
+
+pub mod log;
+
+mod meta;
+
+mod module;
+pub use module::*;
+
+mod observer;
+pub use observer::*;
+
+pub mod os;
+
+mod pipeline;
+pub use pipeline::*;
+
 pub mod id;
 pub use id::*;
 
 pub mod query;
 pub use query::*;
 
+mod rule;
+pub use rule::*;
+
+mod script;
+pub use script::*;
+
+mod stats;
+pub use stats::*;
+
 pub mod system;
 pub use system::*;
 
+mod table;
+pub use table::*;
+
+pub mod units;
+
 pub mod terms;
 pub use terms::*;
 
+mod timer;
+pub use timer::*;
+
 pub mod world;
 pub use world::*;
 
@@ -170,6 +217,3707 @@ mod tests {
 		world.add::<Serializable>(pos_e);	
 	}
 
+    #[test]
+    fn flecs_get_mut_and_modified() {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+		static ON_SET: AtomicUsize = AtomicUsize::new(0);
+
+		unsafe extern "C" fn on_set(it: *mut ecs_iter_t) {
+			ON_SET.fetch_add((*it).count as usize, Ordering::SeqCst);
+		}
+
+		let mut world = World::new();
+		let pos = world.component::<Position>();
+		world.component::<Velocity>();
+
+		let mut desc: ecs_observer_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.filter.terms[0].id = pos.id();
+		desc.events[0] = unsafe { EcsOnSet };
+		desc.callback = Some(on_set);
+		unsafe { ecs_observer_init(world.raw(), &desc) };
+
+		let mut e = world.entity().set(Position { x: 1.0, y: 2.0 });
+		assert!(e.get_mut::<Velocity>().is_none());
+		assert!(!e.has::<Velocity>());
+
+		let before = ON_SET.load(Ordering::SeqCst);
+		e.get_mut::<Position>().unwrap().x = 5.0;
+		assert_eq!(ON_SET.load(Ordering::SeqCst), before);
+		e.modified::<Position>();
+		assert_eq!(ON_SET.load(Ordering::SeqCst), before + 1);
+
+		assert!(e.update::<Position>(|p| p.y = 6.0));
+		assert_eq!(ON_SET.load(Ordering::SeqCst), before + 2);
+		assert!(!e.update::<Velocity>(|v| v.x = 1.0));
+		assert_eq!(world.get::<Position>(e), Some(&Position { x: 5.0, y: 6.0 }));
+
+		world.get_mut::<Position>(e).unwrap().x = 7.0;
+		assert_eq!(e.get::<Position>(), &Position { x: 7.0, y: 6.0 });
+		assert!(world.get_mut::<Velocity>(e).is_none());
+		assert_eq!(ON_SET.load(Ordering::SeqCst), before + 2);
+
+		assert!(world.modify::<Position>(e, |p| p.x = 8.0));
+		assert_eq!(ON_SET.load(Ordering::SeqCst), before + 3);
+		assert!(!world.modify::<Velocity>(e, |v| v.x = 1.0));
+		assert_eq!(ON_SET.load(Ordering::SeqCst), before + 3);
+		assert_eq!(e.get::<Position>(), &Position { x: 8.0, y: 6.0 });
+	}
+
+    #[test]
+    fn flecs_cached_refs() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let e = world.entity().set(Position { x: 1.0, y: 2.0 });
+		let mut pos = e.get_ref::<Position>();
+		assert_eq!(pos.entity(), e);
+		assert_eq!(pos.get(), Some(&Position { x: 1.0, y: 2.0 }));
+
+		// moving the entity to another table keeps the ref valid
+		e.set(Velocity::default());
+		pos.get_mut().unwrap().x = 3.0;
+		assert_eq!(e.get::<Position>(), &Position { x: 3.0, y: 2.0 });
+
+		e.remove::<Position>();
+		assert_eq!(pos.get(), None);
+
+		e.set(Position { x: 4.0, y: 5.0 });
+		assert_eq!(pos.get(), Some(&Position { x: 4.0, y: 5.0 }));
+
+		e.destruct();
+		assert_eq!(pos.get(), None);
+		world.entity().set(Position::default());
+		assert_eq!(pos.get(), None);
+	}
+
+    #[test]
+    fn flecs_emplace_component() {
+		struct Buffer { data: [u8; 65536] }
+
+		let mut world = World::new();
+		world.component::<Buffer>();
+		world.component::<Position>();
+
+		let mut storage = std::ptr::null();
+		let e = unsafe { 
+			world.entity().emplace::<Buffer>(|buffer| {
+				storage = buffer.as_ptr();
+				let data = std::ptr::addr_of_mut!((*buffer.as_mut_ptr()).data) as *mut u8;
+				for i in 0..65536 {
+					data.add(i).write(i as u8);
+				}
+			})
+		};
+
+		// the value was written straight into the table
+		let buffer = e.get::<Buffer>();
+		assert!(std::ptr::eq(buffer, storage));
+		assert!(buffer.data.iter().enumerate().all(|(i, v)| *v == i as u8));
+
+		// a component inherited through IsA is overridden
+		let base = world.prefab("Base").set(Position { x: 1.0, y: 2.0 });
+		let instance = world.entity().is_a(base);
+		unsafe { instance.emplace::<Position>(|pos| { pos.write(Position { x: 3.0, y: 4.0 }); }) };
+		assert_eq!(instance.get::<Position>().x, 3.0);
+		assert_eq!(base.get::<Position>().x, 1.0);
+	}
+
+    #[test]
+    fn flecs_remove_components() {
+		struct Likes;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		let vel = world.component::<Velocity>();
+		let likes = world.component::<Likes>();
+
+		let bob = world.entity();
+		let e = world.entity()
+			.set(Position::default())
+			.set(Velocity::default())
+			.add_relation_ids(likes, bob)
+			.remove::<Position>();
+		assert!(!e.has::<Position>());
+		assert!(e.has::<Velocity>());
+
+		// removing something that isn't there is fine
+		let e = e.remove::<Position>()
+			.remove_id(ecs_pair(likes.id(), bob.id()))
+			.remove_id(ecs_pair(likes.id(), e.id()));
+		assert!(!e.has_relation(likes, bob));
+		assert!(e.has::<Velocity>());
+
+		let e = e.remove_id(vel);
+		assert!(!e.has::<Velocity>());
+	}
+
+    #[test]
+    fn flecs_has_checks() {
+		struct Tag;
+		struct Likes;
+		struct Unregistered;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		let tag = world.component::<Tag>();
+		world.component::<Likes>();
+
+		let bob = world.entity();
+		let e = world.entity()
+			.set(Position::default())
+			.add::<Tag>()
+			.add_pair_second::<Likes>(bob);
+
+		assert!(e.has::<Position>());
+		assert!(world.has::<Position>(e));
+		assert!(!e.has::<Velocity>());
+
+		assert!(e.has::<Tag>());
+		assert!(e.has_id(tag));
+		assert!(!bob.has::<Tag>());
+
+		let likes_bob = ecs_pair(world.id::<Likes>().unwrap().id(), bob.id());
+		assert!(e.has_id(likes_bob));
+		assert!(!bob.has_id(likes_bob));
+		assert!(!e.has::<Likes>());
+
+		// unknown types are not registered as a side effect
+		assert!(!e.has::<Unregistered>());
+		assert!(world.id::<Unregistered>().is_none());
+	}
+
+    #[test]
+    fn flecs_entity_formatting() {
+		let mut world = World::new();
+		world.component_named::<Position>("Position");
+
+		let named = world.entity().named("Player").set(Position::default());
+		let unnamed = world.entity();
+		assert_eq!(format!("{}", named), "Player");
+		assert_eq!(format!("{}", unnamed), unnamed.id().to_string());
+
+		let debug = format!("{:?}", named);
+		assert!(debug.contains("name: \"Player\""), "{}", debug);
+		assert!(debug.contains("Position"), "{}", debug);
+
+		// dead entities fall back to the raw id
+		unnamed.destruct();
+		assert_eq!(format!("{}", unnamed), unnamed.id().to_string());
+		assert!(format!("{:?}", unnamed).contains("alive: false"));
+
+		let mut set = std::collections::HashSet::new();
+		set.insert(named);
+		set.insert(named);
+		set.insert(unnamed);
+		assert_eq!(set.len(), 2);
+	}
+
+    #[test]
+    fn flecs_entity_type_introspection() {
+		struct Tag;
+		struct Likes;
+
+		let mut world = World::new();
+		let pos = world.component_named::<Position>("Position");
+		let tag = world.component_named::<Tag>("Tag");
+		let likes = world.component_named::<Likes>("Likes");
+
+		let bob = world.entity().named("Bob");
+		let e = world.entity()
+			.set(Position::default())
+			.add::<Tag>()
+			.add_relation_ids(likes, bob);
+
+		let mut ids = Vec::new();
+		e.each_component(|id| ids.push(id));
+		assert_eq!(ids.len(), 3);
+		assert!(ids.contains(&pos));
+		assert!(ids.contains(&tag));
+
+		let pair = *ids.iter().find(|id| is_pair(**id)).unwrap();
+		assert_eq!(pair_first(pair), likes);
+		assert_eq!(pair_second(pair), bob.id());
+
+		assert_eq!(e.type_string(), "Position, Tag, (Likes,Bob)");
+		assert_eq!(world.entity().type_string(), "");
+	}
+
+    #[test]
+    fn flecs_entity_delete_and_clear() {
+		let mut world = World::new();
+		world.component::<Position>();
+
+		let e = world.entity().named("Deleted").set(Position::default());
+		let id = e.id();
+		e.destruct();
+		assert_eq!(world.find_entity(id), None);
+		assert_eq!(world.lookup("Deleted"), None);
+
+		let e = world.entity();
+		world.delete(e);
+		assert_eq!(world.find_entity(e.id()), None);
+
+		let e = world.entity();
+		world.delete_by_id(e.id());
+		assert_eq!(world.find_entity(e.id()), None);
+
+		// clear keeps the entity alive but strips everything, including the name
+		let e = world.entity().named("Cleared").set(Position::default());
+		e.clear();
+		assert!(e.is_alive());
+		assert_eq!(world.find_entity(e.id()), Some(e));
+		assert!(world.get::<Position>(e).is_none());
+		assert_eq!(world.lookup("Cleared"), None);
+		assert_eq!(e.name(), "");
+	}
+
+    #[test]
+    fn flecs_entity_generations() {
+		let world = World::new();
+		let e = world.entity();
+		assert!(e.is_alive());
+		assert!(world.is_alive(e.id()));
+		assert_eq!(e.generation(), 0);
+
+		e.destruct();
+		assert!(!e.is_alive());
+		assert_eq!(world.get_alive(e.id()), None);
+
+		// create entities until the index is recycled with a new generation
+		let recycled = (0..100).map(|_| world.entity())
+			.find(|n| strip_generation(n.id()) == strip_generation(e.id()))
+			.expect("Entity id was not recycled!");
+		assert_ne!(recycled.id(), e.id());
+		assert_eq!(recycled.generation(), 1);
+
+		// the stale handle must not be mistaken for the new entity
+		assert!(!e.is_alive());
+		assert!(!world.is_alive(e.id()));
+		assert!(recycled.is_alive());
+		assert_eq!(world.get_alive(strip_generation(e.id())), Some(recycled));
+	}
+
+    #[test]
+    fn flecs_hierarchies() {
+		let world = World::new();
+		let parent = world.entity().named("Parent");
+		let child = world.entity_child_of(parent).named("Child");
+		let grand_child = world.entity().child_of(child).named("GrandChild");
+
+		assert_eq!(child.parent(), Some(parent));
+		assert_eq!(grand_child.parent(), Some(child));
+		assert_eq!(parent.parent(), None);
+		assert!(child.is_child_of(parent));
+
+		assert_eq!(world.lookup("Parent::Child"), Some(child));
+		assert_eq!(world.lookup("Parent::Child::GrandChild"), Some(grand_child));
+		assert_eq!(world.lookup("Child"), None);
+
+		// deleting the parent cleans up the whole subtree
+		parent.destruct();
+		assert!(!child.is_valid());
+		assert!(!grand_child.is_valid());
+	}
+
+    #[test]
+    fn flecs_entity_paths() {
+		let world = World::new();
+		let scene = world.entity().named("Scene");
+		let player = world.entity_child_of(scene).named("Player");
+		let weapon = world.entity_child_of(player).named("Weapon");
+
+		assert_eq!(scene.path(), "Scene");
+		assert_eq!(weapon.path(), "Scene::Player::Weapon");
+		assert_eq!(weapon.path_from(scene), "Player::Weapon");
+		assert_eq!(weapon.path_w_sep("/"), "Scene/Player/Weapon");
+		assert_eq!(world.lookup(&weapon.path()), Some(weapon));
+
+		let unnamed = world.entity_child_of(player);
+		assert_eq!(unnamed.path(), format!("Scene::Player::{}", unnamed.id()));
+	}
+
+    #[test]
+    fn flecs_entity_scopes() {
+		struct Owns;
+
+		let mut world = World::new();
+		let owns = world.component::<Owns>();
+
+		let mut c = None;
+		let a = world.entity().named("A").scope(|world| {
+			world.entity().named("B").scope(|world| {
+				c = Some(world.entity().named("C"));
+			});
+			world.entity().named("D");
+		});
+		let c = c.unwrap();
+
+		assert_eq!(c.path(), "A::B::C");
+		assert_eq!(world.lookup("A::B::C"), Some(c));
+		assert_eq!(world.lookup("A::D").unwrap().parent(), Some(a));
+
+		// the scope is restored afterwards
+		assert_eq!(world.entity().parent(), None);
+
+		let player = world.entity();
+		player.with_rel(owns, |world| {
+			world.entity();
+			world.entity();
+		});
+		assert_eq!(player.children_of_rel(owns).count(), 2);
+		assert!(!world.entity().has_relation(owns, player));
+
+		// and also when the closure panics
+		let scoped = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			a.scope(|_| panic!("failed to load"));
+		}));
+		assert!(scoped.is_err());
+		assert_eq!(world.entity().parent(), None);
+
+		let scoped = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			player.with_rel(owns, |_| panic!("failed to load"));
+		}));
+		assert!(scoped.is_err());
+		assert!(!world.entity().has_relation(owns, player));
+	}
+
+    #[test]
+    fn flecs_children_iteration() {
+		struct Owns;
+
+		let mut world = World::new();
+		let owns = world.component::<Owns>();
+
+		let parent = world.entity();
+		let a = world.entity_child_of(parent);
+		let b = world.entity_child_of(parent);
+		let c = world.entity_child_of(parent);
+		let grand_child = world.entity_child_of(a);
+
+		let mut children: Vec<Entity> = parent.children_iter().collect();
+		children.sort_by_key(|e| e.id());
+		assert_eq!(children, vec![a, b, c]);
+		assert!(!children.contains(&grand_child));
+		assert_eq!(world.children(parent).count(), 3);
+		assert_eq!(world.children(a).collect::<Vec<_>>(), vec![grand_child]);
+
+		let mut count = 0;
+		parent.children(|_| count += 1);
+		assert_eq!(count, 3);
+
+		let item = world.entity();
+		world.entity().add_relation_ids(owns, item);
+		world.entity().add_relation_ids(owns, item);
+		assert_eq!(item.children_of_rel(owns).count(), 2);
+		assert_eq!(item.children_iter().count(), 0);
+	}
+
+    #[test]
+    fn flecs_pairs() {
+		struct Likes;
+		struct Apples;
+		#[derive(Debug, PartialEq)]
+		struct Eats { amount: u32 }
+		#[derive(Debug, PartialEq)]
+		struct Owes { amount: u32 }
+
+		let mut world = World::new();
+		world.component::<Likes>();
+		world.component::<Apples>();
+		world.component::<Eats>();
+		world.component::<Owes>();
+
+		// tag + tag
+		let e = world.entity().add_pair::<Likes, Apples>();
+		assert!(e.has_pair::<Likes, Apples>());
+		assert!(!e.has_pair::<Apples, Likes>());
+
+		// component + entity target holds the relation data
+		let bob = world.entity().named("Bob");
+		let alice = world.entity().named("Alice");
+		let e = e.set_pair_second::<Owes>(bob, Owes { amount: 10 })
+			.add_pair_second::<Likes>(alice);
+		assert!(e.has_pair_second::<Owes>(bob));
+		assert!(!e.has_pair_second::<Owes>(alice));
+		assert!(e.has_pair_second::<Likes>(alice));
+		assert_eq!(e.get_pair_second::<Owes>(bob), Some(&Owes { amount: 10 }));
+		assert_eq!(e.get_pair_second::<Owes>(alice), None);
+
+		// data comes from the first non-tag element
+		let e = e.set_pair::<Eats, Apples>(Eats { amount: 3 })
+			.set_pair::<Likes, Eats>(Eats { amount: 5 });
+		assert_eq!(e.get_pair::<Eats, Apples>(), Some(&Eats { amount: 3 }));
+		assert_eq!(e.get_pair_object::<Likes, Eats>(), Some(&Eats { amount: 5 }));
+
+		let e = e.remove_pair::<Likes, Apples>()
+			.remove_pair_second::<Owes>(bob);
+		assert!(!e.has_pair::<Likes, Apples>());
+		assert!(!e.has_pair_second::<Owes>(bob));
+		assert!(e.has_pair::<Eats, Apples>());
+	}
+
+    #[test]
+    fn flecs_relation_targets() {
+		struct Likes;
+		struct Health { _value: f32 }
+
+		let mut world = World::new();
+		let likes = world.component::<Likes>();
+		world.component::<Health>();
+
+		let bob = world.entity().named("Bob");
+		let alice = world.entity().named("Alice");
+		let e = world.entity()
+			.add_pair_second::<Likes>(bob)
+			.add_pair_second::<Likes>(alice);
+
+		assert_eq!(e.target(likes, 0), Some(bob));
+		assert_eq!(e.target(likes, 1), Some(alice));
+		assert_eq!(e.target(likes, 2), None);
+		assert_eq!(e.target_of::<Likes>(), Some(bob));
+		assert_eq!(e.targets(likes).collect::<Vec<_>>(), vec![bob, alice]);
+
+		let none = world.entity();
+		assert_eq!(none.target_of::<Likes>(), None);
+		assert_eq!(none.targets(likes).count(), 0);
+
+		let base = world.entity().set(Health { _value: 10.0 });
+		let instance = world.entity().is_a(base);
+		assert_eq!(instance.target_for::<Health, _>(unsafe { EcsIsA }), Some(base));
+		assert_eq!(base.target_for::<Health, _>(unsafe { EcsIsA }), Some(base));
+		assert_eq!(none.target_for::<Health, _>(unsafe { EcsIsA }), None);
+
+		let dependency = world.entity();
+		let dependent = world.entity().depends_on(dependency);
+		assert_eq!(dependent.target(unsafe { EcsDependsOn }, 0), Some(dependency));
+	}
+
+    #[test]
+    fn flecs_component_toggling() {
+		let mut world = World::new();
+		world.component::<Position>();
+
+		let entities: Vec<Entity> = (0..10).map(|_| world.entity().set(Position::default())).collect();
+		for e in &entities[..3] {
+			e.disable_component::<Position>();
+		}
+
+		assert!(!entities[0].is_component_enabled::<Position>());
+		assert!(entities[5].is_component_enabled::<Position>());
+		assert!(entities[0].has_id(world.id::<Position>().unwrap()));
+
+		let query = world.query().term::<Position>().build();
+		let count_enabled = || {
+			let mut count = 0;
+			query.iter(|it| count += it.count());
+			count
+		};
+		assert_eq!(count_enabled(), 7);
+
+		entities[0].enable_component::<Position>();
+		assert!(entities[0].is_component_enabled::<Position>());
+		assert_eq!(count_enabled(), 8);
+	}
+
+    #[test]
+    fn flecs_entity_disabling() {
+		use std::{cell::Cell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+
+		let a = world.entity().set(Position::default());
+		let b = world.entity().set(Position::default()).disable();
+		assert!(a.is_enabled());
+		assert!(!b.is_enabled());
+
+		let seen = Rc::new(Cell::new(0));
+		let seen_sys = seen.clone();
+		world.system().term::<Position>().iter(move |it| {
+			seen_sys.set(seen_sys.get() + it.count());
+		});
+
+		world.progress(0.0);
+		assert_eq!(seen.get(), 1);
+
+		b.enable();
+		assert!(b.is_enabled());
+		world.progress(0.0);
+		assert_eq!(seen.get(), 3);
+
+		b.disable();
+		let mut count = 0;
+		world.filter_builder().term::<Position>().build().iter(|it| count += it.count());
+		assert_eq!(count, 1);
+
+		let mut count = 0;
+		world.filter_builder().term::<Position>().with_disabled().build().iter(|it| count += it.count());
+		assert_eq!(count, 2);
+	}
+
+    #[test]
+    fn flecs_prefab_instantiation() {
+		let mut world = World::new();
+		world.component::<Position>();
+
+		let ship = world.prefab("Ship").set(Position { x: 1.0, y: 2.0 });
+		let turret = world.prefab("Turret").child_of(ship).set(Position { x: 0.0, y: 1.0 });
+
+		let instance = world.instantiate(ship);
+		assert!(instance.has_relation(unsafe { EcsIsA }, ship));
+		assert!(std::ptr::eq(instance.get::<Position>(), ship.get::<Position>()));
+
+		let children: Vec<Entity> = instance.children_iter().collect();
+		assert_eq!(children.len(), 1);
+		let instance_turret = children[0];
+		assert_ne!(instance_turret, turret);
+		assert_eq!(instance_turret.name(), "Turret");
+		assert_eq!(instance_turret.get::<Position>(), &Position { x: 0.0, y: 1.0 });
+		assert!(!instance_turret.has_id(unsafe { EcsPrefab }));
+	}
+
+    #[test]
+    fn flecs_prefab_slots() {
+		let world = World::new();
+		let tank = world.prefab("Tank");
+		let turret = world.prefab("Turret").child_of(tank);
+		let cannon = world.prefab("Cannon").child_of(turret).slot_of(tank);
+		let hatch = world.prefab("Hatch").child_of(tank).slot();
+
+		let a = world.instantiate(tank);
+		let b = world.instantiate(tank);
+
+		let a_cannon = a.target(cannon, 0).unwrap();
+		let b_cannon = b.target(cannon, 0).unwrap();
+		assert_ne!(a_cannon, b_cannon);
+		assert_ne!(a_cannon, cannon);
+		assert_eq!(a_cannon.path_from(a), "Turret::Cannon");
+		assert_eq!(b_cannon.parent().unwrap().parent(), Some(b));
+
+		assert_eq!(a.target(hatch, 0).unwrap().parent(), Some(a));
+		assert_eq!(a.target(turret, 0), None);
+	}
+
+    #[test]
+    fn flecs_prefab_override_behavior() {
+		#[derive(Debug, PartialEq)]
+		struct Mesh { id: u32 }
+		#[derive(Debug, PartialEq)]
+		struct Health { value: f32 }
+		struct EditorOnly { }
+
+		let mut world = World::new();
+		world.component::<Mesh>();
+		world.component::<Health>();
+		world.component::<EditorOnly>().dont_inherit();
+
+		let prefab = world.prefab("Ship")
+			.set(Mesh { id: 7 })
+			.set(Health { value: 100.0 })
+			.override_component::<Health>()
+			.add::<EditorOnly>();
+
+		let mut a = world.entity().is_a(prefab);
+		let b = world.entity().is_a(prefab);
+		a.get_mut::<Health>().unwrap().value = 50.0;
+
+		assert_eq!(a.get::<Health>(), &Health { value: 50.0 });
+		assert_eq!(b.get::<Health>(), &Health { value: 100.0 });
+		assert_eq!(prefab.get::<Health>(), &Health { value: 100.0 });
+
+		// Mesh is shared, so both instances point at the prefab's value
+		assert!(!a.owns_id(world.id::<Mesh>().unwrap()));
+		assert!(std::ptr::eq(a.get::<Mesh>(), prefab.get::<Mesh>()));
+		assert!(std::ptr::eq(b.get::<Mesh>(), prefab.get::<Mesh>()));
+
+		assert!(!a.has_id(world.id::<EditorOnly>().unwrap()));
+
+		// instances can also be overridden after the fact
+		let c = world.entity().is_a(prefab).override_component::<Mesh>();
+		assert!(c.owns_id(world.id::<Mesh>().unwrap()));
+		assert_eq!(c.get::<Mesh>(), &Mesh { id: 7 });
+
+		// components can be overridden for every prefab, existing or new
+		#[derive(Debug, PartialEq)]
+		struct Shield { value: f32 }
+		world.component::<Shield>();
+		let existing = world.prefab("Cruiser").set(Shield { value: 10.0 });
+		world.component::<Shield>().override_on_instantiate();
+		let new = world.prefab("Frigate").set(Mesh { id: 8 }).set(Shield { value: 20.0 });
+
+		let shield = world.id::<Shield>().unwrap();
+		let mut d = world.entity().is_a(existing);
+		let e = world.entity().is_a(existing);
+		let f = world.entity().is_a(new);
+		assert!(d.owns_id(shield) && e.owns_id(shield) && f.owns_id(shield));
+		assert!(!f.owns_id(world.id::<Mesh>().unwrap()));
+		d.get_mut::<Shield>().unwrap().value = 5.0;
+		assert_eq!(e.get::<Shield>(), &Shield { value: 10.0 });
+		assert_eq!(existing.get::<Shield>(), &Shield { value: 10.0 });
+		assert_eq!(f.get::<Shield>(), &Shield { value: 20.0 });
+
+		// the observer is only created once
+		let observers = unsafe { ecs_count_id(world.raw(), EcsObserver) };
+		world.component::<Shield>().override_on_instantiate();
+		assert_eq!(unsafe { ecs_count_id(world.raw(), EcsObserver) }, observers);
+	}
+
+    #[test]
+    fn flecs_instance_overrides() {
+		#[derive(Debug, PartialEq)]
+		struct Health { value: f32 }
+
+		let mut world = World::new();
+		world.component::<Health>();
+		world.component::<Position>();
+
+		let prefab = world.prefab("Enemy")
+			.set(Health { value: 100.0 })
+			.set(Position { x: 1.0, y: 1.0 })
+			.auto_override::<Position>();
+
+		let a = world.instantiate(prefab).set_override(Health { value: 25.0 });
+		let b = world.instantiate(prefab);
+
+		assert_eq!(a.get::<Health>(), &Health { value: 25.0 });
+		assert_eq!(b.get::<Health>(), &Health { value: 100.0 });
+		assert_eq!(prefab.get::<Health>(), &Health { value: 100.0 });
+		assert!(a.owns_id(world.id::<Health>().unwrap()));
+		assert!(!b.owns_id(world.id::<Health>().unwrap()));
+
+		// auto overridden components are copied into every instance
+		assert!(a.owns_id(world.id::<Position>().unwrap()));
+		assert!(b.owns_id(world.id::<Position>().unwrap()));
+		assert_eq!(b.get::<Position>(), &Position { x: 1.0, y: 1.0 });
+
+		// queries match both the owned and the shared component
+		let mut matched = Vec::new();
+		world.each1::<Health>(|e, _| matched.push(e));
+		matched.sort_by_key(|e| e.id());
+		assert_eq!(matched, vec![a, b]);
+	}
+
+    #[test]
+    fn flecs_dynamic_component_hooks() {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+		static CTORS: AtomicUsize = AtomicUsize::new(0);
+		static DTORS: AtomicUsize = AtomicUsize::new(0);
+
+		let mut world = World::new();
+		world.component::<Position>();
+
+		let hooks = DynamicComponentHooks {
+			ctor: Some(|ptr| { CTORS.fetch_add(1, Ordering::SeqCst); unsafe { std::ptr::write_bytes(ptr, 7, 64) }; }),
+			dtor: Some(|_ptr| { DTORS.fetch_add(1, Ordering::SeqCst); }),
+			copy: None,
+		};
+
+		// the table storage can't honor alignments above what the C allocator provides
+		let over_aligned = Layout::from_size_align(64, 32).unwrap();
+		assert_eq!(world.component_dynamic_with_hooks("script.OverAligned", None, over_aligned, hooks),
+			Err(DynamicComponentError::OverAligned { align: 32, max: MAX_DYNAMIC_COMPONENT_ALIGN }));
+
+		// symbols generated at runtime must be accepted
+		let symbol = format!("script.{}", "Aligned");
+		let layout = Layout::from_size_align(64, 16).unwrap();
+		let comp = world.component_dynamic_with_hooks(&symbol, Some("Aligned"), layout, hooks).unwrap();
+
+		let info = get_component_info(world.raw(), comp).unwrap();
+		assert_eq!(info.alignment, 16);
+
+		let entities: Vec<Entity> = (0..5).map(|_| world.entity().add_dynamic(&symbol)).collect();
+		for e in &entities {
+			let ptr = unsafe { ecs_get_id(world.raw(), e.raw(), comp) } as usize;
+			assert_eq!(ptr % 16, 0);
+			assert_eq!(world.read_component(e.raw(), comp).unwrap(), &[7u8; 64][..]);
+		}
+		assert_eq!(CTORS.load(Ordering::SeqCst), 5);
+
+		// moving to another table must not run the dtor
+		entities[0].set(Position::default());
+		assert_eq!(DTORS.load(Ordering::SeqCst), 0);
+		assert_eq!(world.read_component(entities[0].raw(), comp).unwrap(), &[7u8; 64][..]);
+
+		entities[1].destruct();
+		assert_eq!(DTORS.load(Ordering::SeqCst), 1);
+
+		drop(world);
+		assert_eq!(DTORS.load(Ordering::SeqCst), 5);
+	}
+
+    #[test]
+    fn flecs_derived_components() {
+		#[derive(Component)]
+		#[flecs(name = "Transform", symbol = "game.Transform")]
+		struct LocalTransform { _x: f32 }
+
+		#[derive(Component)]
+		#[flecs(tag)]
+		struct Player;
+
+		let mut world = World::new();
+		let transform = world.component_type::<LocalTransform>();
+		let player = world.component_type::<Player>();
+
+		assert_eq!(transform.name(), "Transform");
+		assert_eq!(transform.symbol(), "game.Transform");
+		assert_eq!(world.lookup("Transform"), Some(transform));
+		assert_eq!(player.name(), "Player");
+		assert!(get_component_info(world.raw(), player.raw()).is_none());
+
+		// the regular registration picks up the cached id
+		assert_eq!(world.component::<LocalTransform>(), transform);
+	}
+
+    #[test]
+    fn flecs_derived_member_components() {
+		#[derive(Component)]
+		#[flecs(name = "Velocity2", symbol = "game.Velocity2", meta)]
+		#[repr(C)]
+		struct Vel { x: f32, y: f32 }
+
+		#[derive(Component)]
+		#[flecs(meta)]
+		#[repr(C)]
+		struct Body { vel: Vel, layers: [u8; 2] }
+
+		// Vel is recorded as a member of Body, component::<Vel>() then uses its name
+		let mut world = World::new();
+		let body = world.component_type::<Body>();
+		let vel = world.component::<Vel>();
+		assert_eq!(vel.name(), "Velocity2");
+		assert_eq!(vel.symbol(), "game.Velocity2");
+		assert_eq!(body.name(), "Body");
+
+		let value = Body { vel: Vel { x: 1.0, y: -2.5 }, layers: [4, 5] };
+		let json = unsafe { 
+			flecs_to_owned_string(ecs_ptr_to_json(world.raw(), body.raw(), &value as *const Body as *const std::os::raw::c_void))
+		};
+		assert_eq!(json, r#"{"vel":{"x":1, "y":-2.5}, "layers":[4, 5]}"#);
+
+		// also in other worlds
+		let mut world = World::new();
+		assert_eq!(world.component::<Vel>().symbol(), "game.Velocity2");
+	}
+
+    #[test]
+    fn flecs_meta_members() {
+		#[repr(C)]
+		struct Vec2 { x: f32, y: f32 }
+
+		#[repr(C)]
+		struct Line { start: Vec2, end: Vec2, flags: [u8; 3], visible: bool }
+
+		let mut world = World::new();
+		world.component::<Vec2>()
+			.member::<f32>("x")
+			.member::<f32>("y");
+		let line = world.component::<Line>()
+			.member::<Vec2>("start")
+			.member::<Vec2>("end")
+			.member_array::<u8>("flags", 3)
+			.member::<bool>("visible");
+
+		let value = Line { start: Vec2 { x: 1.0, y: 2.0 }, end: Vec2 { x: 3.5, y: -4.0 }, flags: [1, 2, 3], visible: true };
+		let json = unsafe { 
+			flecs_to_owned_string(ecs_ptr_to_json(world.raw(), line.raw(), &value as *const Line as *const std::os::raw::c_void))
+		};
+		assert_eq!(json, r#"{"start":{"x":1, "y":2}, "end":{"x":3.5, "y":-4}, "flags":[1, 2, 3], "visible":true}"#);
+	}
+
+    #[test]
+    fn flecs_meta_units() {
+		use units::Unit;
+
+		#[repr(C)]
+		struct Vehicle { speed: f32 }
+
+		let mut world = World::new();
+		world.import_units();
+		let vehicle = world.component::<Vehicle>()
+			.member_with_unit::<f32>("speed", units::MetersPerSecond);
+
+		let speed_name = std::ffi::CString::new("speed").unwrap();
+		let speed = unsafe { ecs_lookup_child(world.raw(), vehicle.raw(), speed_name.as_ptr()) };
+		assert_ne!(speed, 0);
+		let member = unsafe { &*(ecs_get_id(world.raw(), speed, FLECS__EEcsMember) as *const EcsMember) };
+		assert_eq!(member.unit, units::MetersPerSecond.id());
+
+		let entity = world.entity().set(Vehicle { speed: 12.5 });
+		let mut desc: ecs_entity_to_json_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.serialize_values = true;
+		desc.serialize_type_info = true;
+		let json = unsafe { flecs_to_owned_string(ecs_entity_to_json(world.raw(), entity.raw(), &desc)) };
+		assert!(json.contains(r#""symbol":"m/s""#), "{}", json);
+	}
+
+    #[test]
+    fn flecs_doc_descriptions() {
+		let world = World::new();
+		let e = world.entity().named("Player")
+			.set_doc_name("The Player")
+			.set_doc_brief("Controlled by the user")
+			.set_doc_detail("Spawns at the start of every level")
+			.set_doc_link("https://example.com/player")
+			.set_doc_color("#ff0000");
+
+		assert_eq!(e.doc_name().as_deref(), Some("The Player"));
+		assert_eq!(e.doc_brief().as_deref(), Some("Controlled by the user"));
+		assert_eq!(e.doc_detail().as_deref(), Some("Spawns at the start of every level"));
+		assert_eq!(e.doc_link().as_deref(), Some("https://example.com/player"));
+		assert_eq!(e.doc_color().as_deref(), Some("#ff0000"));
+
+		// the getters return copies, which stay valid when the doc changes
+		let brief = e.doc_brief();
+		e.set_doc_brief("Controlled by the AI");
+		assert_eq!(brief.as_deref(), Some("Controlled by the user"));
+		assert_eq!(e.doc_brief().as_deref(), Some("Controlled by the AI"));
+
+		// the doc name falls back to the entity name
+		let named = world.entity().named("Enemy");
+		assert_eq!(named.doc_name().as_deref(), Some("Enemy"));
+		assert_eq!(named.doc_brief(), None);
+
+		let unnamed = world.entity();
+		assert_eq!(unnamed.doc_name(), None);
+		assert_eq!(unnamed.doc_brief(), None);
+		assert_eq!(unnamed.doc_detail(), None);
+		assert_eq!(unnamed.doc_link(), None);
+		assert_eq!(unnamed.doc_color(), None);
+	}
+
+    #[test]
+    fn flecs_entity_events() {
+		use std::{cell::RefCell, rc::Rc};
+
+		#[derive(Debug, PartialEq)]
+		struct Damaged { amount: u32 }
+
+		let mut world = World::new();
+		world.component::<Position>();
+
+		let a = world.entity().set(Position::default());
+		let b = world.entity().set(Position::default());
+
+		let a_log = Rc::new(RefCell::new(Vec::new()));
+		let b_log = Rc::new(RefCell::new(Vec::new()));
+		let log = a_log.clone();
+		a.observe::<Damaged>(move |e, ev| log.borrow_mut().push((e, ev.amount)));
+		let log = b_log.clone();
+		b.observe::<Damaged>(move |e, ev| log.borrow_mut().push((e, ev.amount)));
+
+		a.emit(Damaged { amount: 10 });
+		a.emit(Damaged { amount: 20 });
+		b.emit(Damaged { amount: 5 });
+
+		assert_eq!(*a_log.borrow(), vec![(a, 10), (a, 20)]);
+		assert_eq!(*b_log.borrow(), vec![(b, 5)]);
+	}
+
+    #[test]
+    fn flecs_system_phases() {
+		use std::{cell::RefCell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let log = Rc::new(RefCell::new(Vec::new()));
+
+		// created out of order on purpose
+		let l = log.clone();
+		world.system().term::<Position>().on_store().iter(move |_| l.borrow_mut().push("render"));
+		let l = log.clone();
+		world.system().term::<Position>().iter(move |_| l.borrow_mut().push("update"));
+		let l = log.clone();
+		world.system().term::<Position>().pre_update().iter(move |_| l.borrow_mut().push("input"));
+		let l = log.clone();
+		world.system().term::<Position>().kind(0).iter(move |_| l.borrow_mut().push("manual"));
+
+		world.progress(0.0);
+		assert_eq!(*log.borrow(), vec!["input", "update", "render"]);
+	}
+
+    #[test]
+    fn flecs_system_interval_and_rate() {
+		use std::{cell::Cell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let interval_runs = Rc::new(Cell::new(0));
+		let interval_time = Rc::new(Cell::new(0.0f32));
+		let (runs, time) = (interval_runs.clone(), interval_time.clone());
+		let sys = world.system().term::<Position>().interval(0.05).iter(move |it| {
+			runs.set(runs.get() + 1);
+			time.set(time.get() + it.delta_system_time());
+		});
+		assert!((sys.get_interval() - 0.05).abs() < 0.0001);
+
+		let rate_runs = Rc::new(Cell::new(0));
+		let runs = rate_runs.clone();
+		world.system().term::<Position>().rate(3).iter(move |_| runs.set(runs.get() + 1));
+
+		for _ in 0..10 {
+			world.progress(0.016);
+		}
+
+		// 0.16s elapsed in total, an interval of 0.05 fires 3 times and each run
+		// sees the time accumulated since the timer last fired, not the frame time
+		assert_eq!(interval_runs.get(), 3);
+		assert!(interval_time.get() >= 0.15 && interval_time.get() < 0.2);
+		assert_eq!(rate_runs.get(), 3);
+
+		sys.set_interval(0.0);
+		world.progress(0.016);
+		assert_eq!(interval_runs.get(), 4);
+	}
+
+    #[test]
+    fn flecs_timer_tick_sources() {
+		use std::{cell::Cell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let timer = world.timer().interval(0.0);
+		let (a, b) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+		let c = a.clone();
+		world.system().term::<Position>().tick_source(timer).iter(move |_| c.set(c.get() + 1));
+		let c = b.clone();
+		world.system().term::<Position>().tick_source(timer).iter(move |_| c.set(c.get() + 1));
+
+		world.progress(0.016);
+		assert_eq!((a.get(), b.get()), (1, 1));
+
+		timer.stop();
+		for _ in 0..5 {
+			world.progress(0.016);
+		}
+		assert_eq!((a.get(), b.get()), (1, 1));
+
+		timer.start();
+		world.progress(0.016);
+		assert_eq!((a.get(), b.get()), (2, 2));
+
+		let once = world.timer().timeout(0.03);
+		let c = Rc::new(Cell::new(0));
+		let d = c.clone();
+		world.system().term::<Position>().tick_source(once).iter(move |_| d.set(d.get() + 1));
+		for _ in 0..5 {
+			world.progress(0.016);
+		}
+		assert_eq!(c.get(), 1);
+	}
+
+    #[test]
+    fn flecs_no_readonly_systems() {
+		use std::{cell::Cell, rc::Rc};
+
+		struct Spawner;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Spawner>();
+		world.entity().add::<Spawner>();
+		// keeps the counter system active, inactive systems are left out of the frame's schedule
+		world.entity().set(Position::default());
+
+		world.system().term::<Spawner>().no_readonly(true).iter(|it| {
+			let world = it.world();
+			for _ in 0..3 {
+				world.entity().set(Position::default());
+			}
+		});
+
+		let count = Rc::new(Cell::new(0));
+		let c = count.clone();
+		world.system().term::<Position>().iter(move |it| c.set(c.get() + it.count()));
+
+		world.progress(0.0);
+		assert_eq!(count.get(), 4);
+	}
+
+    #[test]
+    fn flecs_system_toggling() {
+		use std::{cell::Cell, rc::Rc};
+
+		struct DropCounter(Rc<Cell<u32>>);
+		impl Drop for DropCounter {
+			fn drop(&mut self) {
+				self.0.set(self.0.get() + 1);
+			}
+		}
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let runs = Rc::new(Cell::new(0));
+		let drops = Rc::new(Cell::new(0));
+		let (r, guard) = (runs.clone(), DropCounter(drops.clone()));
+		let sys = world.system().term::<Position>().iter(move |_| {
+			let _guard = &guard;
+			r.set(r.get() + 1);
+		});
+		assert!(sys.is_enabled());
+
+		world.progress(0.0);
+		assert_eq!(runs.get(), 1);
+
+		sys.disable();
+		assert!(!sys.is_enabled());
+		world.progress(0.0);
+		world.progress(0.0);
+		assert_eq!(runs.get(), 1);
+
+		sys.enable();
+		assert!(sys.is_enabled());
+		world.progress(0.0);
+		assert_eq!(runs.get(), 2);
+
+		assert_eq!(drops.get(), 0);
+		let entity = sys.entity();
+		sys.destruct();
+		assert_eq!(drops.get(), 1);
+		assert!(!entity.is_alive());
+
+		world.progress(0.0);
+		assert_eq!(runs.get(), 2);
+	}
+
+    #[test]
+    fn flecs_system_ordering() {
+		use std::{cell::RefCell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let log = Rc::new(RefCell::new(Vec::new()));
+		let collision = world.create_phase("Collision", unsafe { EcsOnUpdate });
+
+		let l = log.clone();
+		world.system().term::<Position>().kind(collision).iter(move |_| l.borrow_mut().push("collision"));
+		let l = log.clone();
+		world.system().term::<Position>().iter(move |_| l.borrow_mut().push("input"));
+		let l = log.clone();
+		world.system().term::<Position>().iter(move |_| l.borrow_mut().push("movement"));
+
+		world.progress(0.0);
+		world.progress(0.0);
+		assert_eq!(*log.borrow(), vec!["input", "movement", "collision", "input", "movement", "collision"]);
+	}
+
+    #[test]
+    fn flecs_system_ctx() {
+		use std::{cell::Cell, rc::Rc};
+
+		struct Score {
+			value: u32,
+			drops: Rc<Cell<u32>>,
+		}
+		impl Drop for Score {
+			fn drop(&mut self) {
+				self.drops.set(self.drops.get() + 1);
+			}
+		}
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let drops = Rc::new(Cell::new(0));
+		let last = Rc::new(Cell::new(0));
+		let l = last.clone();
+		let sys = world.system()
+			.term::<Position>()
+			.ctx(Score { value: 0, drops: drops.clone() })
+			.iter_mut(move |it| {
+				assert!(it.ctx::<u32>().is_none());
+				let score = it.ctx::<Score>().unwrap();
+				score.value += 10;
+				l.set(score.value);
+			});
+
+		for _ in 0..3 {
+			world.progress(0.0);
+		}
+		assert_eq!(last.get(), 30);
+		assert_eq!(drops.get(), 0);
+
+		sys.destruct();
+		assert_eq!(drops.get(), 1);
+
+		// the ctx of a system which is still alive is dropped at world fini
+		let mut world = World::new();
+		world.component::<Position>();
+		world.system().term::<Position>().ctx(Score { value: 0, drops: drops.clone() }).iter(|_| {});
+		drop(world);
+		assert_eq!(drops.get(), 2);
+	}
+
+    #[test]
+    fn flecs_singleton_terms() {
+		#[derive(Default)]
+		struct Gravity { value: f32 }
+		#[derive(Default)]
+		struct Frames { count: u32 }
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.set_singleton(Gravity { value: 1.0 });
+		world.set_singleton(Frames::default());
+
+		let entities: Vec<Entity> = (0..100).map(|_| world.entity().set(Position::default())).collect();
+
+		world.system()
+			.term::<Position>()
+			.term_singleton::<Gravity>()
+			.term_singleton_mut::<Frames>()
+			.iter_mut(|it| {
+				let gravity = it.singleton::<Gravity>().unwrap().value;
+				it.singleton_mut::<Frames>().unwrap().count += 1;
+				let positions = it.field::<Position>(1);
+				for i in 0..it.count() {
+					positions.get_mut(i).y -= gravity;
+				}
+			});
+
+		world.progress(0.0);
+		world.get_singleton_mut::<Gravity>().unwrap().value = 2.5;
+		world.progress(0.0);
+
+		for e in &entities {
+			assert_eq!(e.get::<Position>().y, -3.5);
+		}
+		assert_eq!(world.get_singleton::<Frames>().unwrap().count, 2);
+
+		let filter = world.filter_builder().term::<Position>().term_singleton::<Gravity>().build();
+		let mut matched = 0;
+		filter.iter(|it| {
+			assert_eq!(it.singleton::<Gravity>().unwrap().value, 2.5);
+			assert!(it.singleton::<Frames>().is_none());
+			matched += it.count();
+		});
+		assert_eq!(matched, 100);
+	}
+
+    #[test]
+    fn flecs_instanced_iteration() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let prefab = world.prefab("Mover").set(Velocity { x: 1.0, y: 2.0 });
+		let instances: Vec<Entity> = (0..3).map(|_| world.instantiate(prefab).set(Position::default())).collect();
+		let owner = world.entity().set(Position::default()).set(Velocity { x: 10.0, y: 10.0 });
+
+		for instanced in [false, true] {
+			let filter = world.filter_builder()
+				.with_components::<(Position, Velocity)>()
+				.instanced(instanced)
+				.build();
+
+			let mut largest_shared_count = 0;
+			filter.iter(|it| {
+				let positions = it.field::<Position>(1);
+				let velocities = it.field::<Velocity>(2);
+				assert!(it.is_self(1));
+				assert_eq!(it.is_self(2), !velocities.is_shared());
+				if velocities.is_shared() {
+					largest_shared_count = largest_shared_count.max(it.count());
+				}
+
+				for i in 0..it.count() {
+					let v = if velocities.is_shared() { velocities.get(0) } else { velocities.get(i) };
+					let p = positions.get_mut(i);
+					p.x += v.x;
+					p.y += v.y;
+				}
+			});
+
+			// without instancing the entities sharing Velocity are iterated one by one
+			assert_eq!(largest_shared_count, if instanced { 3 } else { 1 });
+		}
+
+		for e in &instances {
+			assert_eq!(*e.get::<Position>(), Position { x: 2.0, y: 4.0 });
+		}
+		assert_eq!(*owner.get::<Position>(), Position { x: 20.0, y: 20.0 });
+	}
+
+    #[test]
+    fn flecs_term_builder() {
+		#[derive(Default)]
+		struct Transform { scale: f32 }
+		struct Likes;
+		#[derive(Default)]
+		struct Settings { speed: f32 }
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Transform>();
+		world.component::<Settings>();
+		let likes = world.component::<Likes>();
+		let alice = world.entity().named("Alice");
+		let bob = world.entity().named("Bob");
+		world.entity().named("Game").set(Settings { speed: 2.0 });
+
+		let parent = world.entity().set(Transform { scale: 3.0 });
+		let matching = world.entity_child_of(parent).set(Position::default()).add_relation_ids(likes, alice);
+		// each of these misses one of the terms
+		world.entity_child_of(parent).set(Position::default()).add_relation_ids(likes, bob);
+		world.entity().set(Position::default()).add_relation_ids(likes, alice);
+		world.entity_child_of(parent).add_relation_ids(likes, alice);
+
+		let filter = world.filter_builder()
+			.term::<Position>()
+			.term::<Transform>().parent()
+			.term_pair(likes, alice)
+			.build();
+		let mut found = Vec::new();
+		filter.iter(|it| {
+			assert!(it.is_self(1));
+			assert!(!it.is_self(2));
+			assert_eq!(it.field::<Transform>(2).get(0).scale, 3.0);
+			for i in 0..it.count() {
+				found.push(it.entity(i as i32));
+			}
+		});
+		assert_eq!(found, vec![matching]);
+
+		// the same pair built from its parts, plus a term on a fixed source
+		let filter = world.filter_builder()
+			.term_id(likes).second(alice)
+			.term::<Settings>().src_name("Game")
+			.build();
+		let mut found = Vec::new();
+		filter.iter(|it| {
+			assert_eq!(it.field::<Settings>(2).get(0).speed, 2.0);
+			for i in 0..it.count() {
+				found.push(it.entity(i as i32));
+			}
+		});
+		assert_eq!(found.len(), 3);
+		assert!(found.contains(&matching));
+
+		// the same filters with a builder per term
+		let filter = world.filter_builder()
+			.with::<Position>().self_()
+			.with::<Transform>().parent()
+			.with_pair(likes, alice)
+			.end()
+			.build();
+		let mut found = Vec::new();
+		filter.iter(|it| {
+			for i in 0..it.count() {
+				found.push(it.entity(i as i32));
+			}
+		});
+		assert_eq!(found, vec![matching]);
+
+		let filter = world.filter_builder()
+			.with_term(likes).second(alice)
+			.with::<Settings>().src_name("Game").in_()
+			.end()
+			.build();
+		let mut count = 0;
+		filter.iter(|it| {
+			assert_eq!(it.field::<Settings>(2).get(0).speed, 2.0);
+			count += it.count();
+		});
+		assert_eq!(count, 3);
+	}
+
+    #[test]
+    fn flecs_custom_pipelines() {
+		use std::{cell::RefCell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let physics = world.entity().named("Physics");
+		let render = world.entity().named("Render");
+		let simulation = world.pipeline().named("Simulation").term_id(physics).build();
+		let presentation = world.pipeline().term_id(render).build();
+
+		let log = Rc::new(RefCell::new(Vec::new()));
+		for (name, phase) in [("integrate", physics), ("draw", render), ("collide", physics)] {
+			let l = log.clone();
+			world.system().term::<Position>().kind(phase).iter(move |_| l.borrow_mut().push(name));
+		}
+		let l = log.clone();
+		world.system().term::<Position>().iter(move |_| l.borrow_mut().push("update"));
+
+		world.run_pipeline(simulation, 1.0 / 60.0);
+		assert_eq!(*log.borrow(), vec!["integrate", "collide"]);
+		log.borrow_mut().clear();
+
+		world.run_pipeline(presentation, 1.0 / 30.0);
+		assert_eq!(*log.borrow(), vec!["draw"]);
+		log.borrow_mut().clear();
+
+		// custom phases aren't part of the builtin pipeline
+		world.progress(0.0);
+		assert_eq!(*log.borrow(), vec!["update"]);
+		log.borrow_mut().clear();
+
+		let builtin = world.get_pipeline();
+		world.set_pipeline(simulation);
+		assert_eq!(world.get_pipeline(), simulation);
+		world.progress(0.0);
+		assert_eq!(*log.borrow(), vec!["integrate", "collide"]);
+
+		world.set_pipeline(builtin);
+		assert_eq!(world.get_pipeline().name(), "BuiltinPipeline");
+	}
+
+    #[test]
+    fn flecs_stats() {
+		let mut world = World::new();
+		world.import_stats_module();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		for _ in 0..10 {
+			world.entity().set(Position::default());
+		}
+
+		let sys = world.system().named("Move").term::<Position>().iter(|it| {
+			let positions = it.field::<Position>(1);
+			for i in 0..it.count() {
+				positions.get_mut(i).x += 1.0;
+			}
+		});
+		let idle = world.system().term::<Velocity>().iter(|_| {});
+
+		for _ in 0..5 {
+			world.progress(0.016);
+		}
+
+		let stats = world.system_stats(sys.entity()).unwrap();
+		assert_eq!(stats.invoke_count, 5);
+		assert_eq!(stats.matched_entity_count, 10);
+		assert!(stats.active && stats.enabled);
+		assert!(world.system_stats(world.entity()).is_none());
+
+		let pipeline = world.pipeline_stats(world.get_pipeline()).unwrap();
+		assert!(pipeline.system_count >= 2);
+		assert!(pipeline.active_system_count < pipeline.system_count);
+		let move_stats = pipeline.systems.iter().find(|s| s.system == sys.entity()).unwrap();
+		assert_eq!(move_stats.invoke_count, 5);
+		let idle_stats = pipeline.systems.iter().find(|s| s.system == idle.entity()).unwrap();
+		assert_eq!(idle_stats.invoke_count, 0);
+		assert!(!idle_stats.active);
+
+		let world_stats = world.world_stats();
+		assert_eq!(world_stats.frame_count, 5);
+		assert!(world_stats.entity_count >= 10);
+		assert!(world_stats.system_count >= 2);
+	}
+
+    #[test]
+    fn flecs_observers() {
+		use std::{cell::{Cell, RefCell}, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let (adds, sets) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+		let removed = Rc::new(RefCell::new(Vec::new()));
+
+		let c = adds.clone();
+		world.observer()
+			.with_components::<(Position, Velocity)>()
+			.event_on_add()
+			.iter(move |it| {
+				assert_eq!(it.event_id().raw(), unsafe { EcsOnAdd });
+				c.set(c.get() + it.count());
+			});
+		let c = sets.clone();
+		world.observer().term::<Position>().event_on_set().iter(move |it| c.set(c.get() + it.count()));
+		// the value being removed is still readable
+		let r = removed.clone();
+		world.observer()
+			.term::<Position>()
+			.event_on_remove()
+			.iter(move |it| {
+				let positions = it.field::<Position>(1);
+				for i in 0..it.count() {
+					r.borrow_mut().push(positions.get(i).x);
+				}
+			});
+
+		let e = world.entity().set(Position { x: 1.0, y: 0.0 });
+		assert_eq!((adds.get(), sets.get()), (0, 1));
+		let e = e.set(Velocity::default());
+		assert_eq!((adds.get(), sets.get()), (1, 1));
+		let e = e.set(Position { x: 2.0, y: 0.0 });
+		assert_eq!((adds.get(), sets.get()), (1, 2));
+
+		e.remove::<Position>();
+		assert_eq!(*removed.borrow(), vec![2.0]);
+
+		world.entity().set(Position { x: 3.0, y: 0.0 }).destruct();
+		assert_eq!(*removed.borrow(), vec![2.0, 3.0]);
+
+		// each observers with multiple events
+		let events = Rc::new(Cell::new(0));
+		let c = events.clone();
+		world.observer()
+			.with_components::<(Position, Velocity)>()
+			.event_on_set()
+			.event_on_remove()
+			.each::<(Position, Velocity)>(move |_, (p, _)| {
+				assert_eq!(p.x, 4.0);
+				c.set(c.get() + 1);
+			});
+		world.entity().set(Velocity::default()).set(Position { x: 4.0, y: 0.0 });
+		assert_eq!(events.get(), 1);
+
+		// entities still alive at world teardown are removed too
+		drop(world);
+		assert_eq!(*removed.borrow(), vec![2.0, 3.0, 4.0]);
+		assert_eq!(events.get(), 2);
+	}
+
+    #[test]
+    fn flecs_observer_yield_existing() {
+		use std::{cell::Cell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		for i in 0..5 {
+			let e = world.entity().set(Position::default());
+			if i < 2 {
+				e.set(Velocity::default());
+			}
+		}
+
+		let sets = Rc::new(Cell::new(0));
+		let c = sets.clone();
+		world.observer()
+			.term::<Position>()
+			.event_on_set()
+			.yield_existing(true)
+			.iter(move |it| c.set(c.get() + it.count()));
+		assert_eq!(sets.get(), 5);
+
+		// only entities matching all terms are yielded
+		let both = Rc::new(Cell::new(0));
+		let c = both.clone();
+		world.observer()
+			.with_components::<(Position, Velocity)>()
+			.event_on_add()
+			.yield_existing(true)
+			.each::<(Position, Velocity)>(move |_, _| c.set(c.get() + 1));
+		assert_eq!(both.get(), 2);
+
+		let without = Rc::new(Cell::new(0));
+		let c = without.clone();
+		world.observer().term::<Position>().event_on_set().iter(move |it| c.set(c.get() + it.count()));
+		assert_eq!(without.get(), 0);
+	}
+
+    #[test]
+    fn flecs_monitor_observers() {
+		use std::{cell::RefCell, rc::Rc};
+
+		struct RigidBody;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<RigidBody>();
+
+		let log = Rc::new(RefCell::new(Vec::new()));
+		let l = log.clone();
+		world.observer()
+			.term::<Position>()
+			.term::<RigidBody>()
+			.event_monitor()
+			.iter(move |it| {
+				let event = if it.event_id().raw() == unsafe { EcsOnAdd } { "match" } else { "unmatch" };
+				l.borrow_mut().push((event, it.entity(0)));
+			});
+
+		let e = world.entity().set(Position::default());
+		assert!(log.borrow().is_empty());
+
+		let e = e.add::<RigidBody>();
+		assert_eq!(*log.borrow(), vec![("match", e)]);
+
+		// still matching, no new notification
+		let e = e.set(Position { x: 1.0, y: 1.0 });
+		assert_eq!(log.borrow().len(), 1);
+
+		e.remove::<Position>();
+		assert_eq!(*log.borrow(), vec![("match", e), ("unmatch", e)]);
+	}
+
+    #[test]
+    fn flecs_query_order_by() {
+		struct Depth { value: i32 }
+
+		fn compare_depth(_e1: EntityId, d1: &Depth, _e2: EntityId, d2: &Depth) -> i32 {
+			d1.value - d2.value
+		}
+
+		fn depths(query: &Query) -> Vec<i32> {
+			let mut result = Vec::new();
+			query.iter(|it| {
+				let depths = it.field::<Depth>(1);
+				for i in 0..it.count() {
+					result.push(depths.get(i).value);
+				}
+			});
+			result
+		}
+
+		let mut world = World::new();
+		world.component::<Depth>();
+		world.component::<Position>();
+
+		let mut entities = Vec::new();
+		for (i, value) in [5, -3, 12, 0, 7, 2].into_iter().enumerate() {
+			let e = world.entity().set(Depth { value });
+			// spread over two tables, the order must hold across tables
+			if i % 2 == 0 {
+				e.set(Position::default());
+			}
+			entities.push(e);
+		}
+
+		let query = world.query().term::<Depth>().order_by(compare_depth).build();
+		assert_eq!(depths(&query), vec![-3, 0, 2, 5, 7, 12]);
+
+		let mut e = entities[2];
+		e.get_mut::<Depth>().unwrap().value = -10;
+		e.modified::<Depth>();
+		let e = entities[1].set(Depth { value: 20 });
+		assert_eq!(depths(&query), vec![-10, 0, 2, 5, 7, 20]);
+
+		e.destruct();
+		let query = world.query().term::<Depth>().order_by(|_, a: &Depth, _, b: &Depth| b.value - a.value).build();
+		assert_eq!(depths(&query), vec![7, 5, 2, 0, -10]);
+	}
+
+    #[test]
+    fn flecs_query_ordered_by_entity() {
+		struct Tag;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		world.component::<Tag>();
+
+		// creation alternates between three tables, which are iterated one by one otherwise
+		let mut created = Vec::new();
+		for i in 0..9 {
+			let e = world.entity().set(Position::default());
+			match i % 3 {
+				0 => { e.set(Velocity::default()); }
+				1 => { e.add::<Tag>(); }
+				_ => {}
+			}
+			created.push(e.id());
+		}
+
+		let ids = |query: &Query| {
+			let mut ids = Vec::new();
+			query.iter(|it| ids.extend_from_slice(it.entities()));
+			ids
+		};
+		assert_ne!(ids(&world.query().term::<Position>().build()), created);
+
+		let query = world.query().term::<Position>().ordered().build();
+		assert_eq!(ids(&query), created);
+
+		let query = world.query().term::<Position>().order_by_entity(|e1: EntityId, e2: EntityId| (e2 > e1) as i32 - (e2 < e1) as i32).build();
+		created.reverse();
+		assert_eq!(ids(&query), created);
+	}
+
+    #[test]
+    fn flecs_query_group_by() {
+		struct InChunk;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		let in_chunk = world.component::<InChunk>();
+
+		let chunks: Vec<Entity> = (0..3).map(|_| world.entity()).collect();
+		let mut expected = vec![Vec::new(); 3];
+		for i in 0..12 {
+			let chunk = i % 3;
+			let e = world.entity().set(Position::default()).add_relation_ids(in_chunk, chunks[chunk]);
+			// more than one table per group
+			if i % 2 == 0 {
+				e.set(Velocity::default());
+			}
+			expected[chunk].push(e);
+		}
+		world.entity().set(Position::default());
+
+		let query = world.query().term::<Position>().group_by(in_chunk).build();
+		for (chunk, expected) in chunks.iter().zip(&expected) {
+			let mut found = Vec::new();
+			query.iter_group(chunk.raw(), |it| {
+				for i in 0..it.count() {
+					found.push(it.entity(i as i32));
+				}
+			});
+			found.sort_by_key(|e| e.raw());
+			assert_eq!(&found, expected);
+		}
+
+		let mut ungrouped = 0;
+		query.iter_group(0, |it| ungrouped += it.count());
+		assert_eq!(ungrouped, 1);
+
+		// groups are iterated together
+		let mut groups = Vec::new();
+		query.iter(|it| {
+			let group = it.entity(0).target(in_chunk, 0).map_or(0, |t| t.raw());
+			if groups.last() != Some(&group) {
+				groups.push(group);
+			}
+		});
+		assert_eq!(groups.len(), 4);
+
+		// custom group function, by component count
+		let query = world.query().term::<Position>().group_by_fn(|ids| ids.len() as u64).build();
+		let mut count = 0;
+		query.iter_group(3, |it| count += it.count());
+		assert_eq!(count, 6);
+	}
+
+    #[test]
+    fn flecs_query_changed() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let e = world.entity().set(Position::default());
+		world.entity().set(Position::default()).set(Velocity::default());
+
+		// a query with write access marks its own tables as modified, so read only
+		let mut builder = world.query().term::<Position>();
+		builder.last_term().inout = ecs_inout_kind_t_EcsIn;
+		let query = builder.build();
+		query.iter(|_| {});
+		assert!(!query.changed());
+
+		e.set(Position { x: 1.0, y: 2.0 });
+		assert!(query.changed());
+		query.iter(|_| {});
+		assert!(!query.changed());
+
+		// iterating with write access marks tables as modified, unless skipped
+		let writer = world.query().term::<Position>().build();
+		writer.iter(|_| {});
+		assert!(query.changed());
+		query.iter(|_| {});
+		assert!(!query.changed());
+
+		writer.iter(|it| it.skip());
+		assert!(!query.changed());
+	}
+
+    #[test]
+    fn flecs_optional_terms() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		// one table with and one without the optional component
+		let still = world.entity().set(Position { x: 1.0, y: 1.0 });
+		let moving = world.entity()
+			.set(Position { x: 1.0, y: 1.0 })
+			.set(Velocity { x: 2.0, y: 3.0 });
+
+		let system = world.system()
+			.term::<Position>()
+			.term::<Velocity>().optional()
+			.iter(|it| {
+				let pos = it.field::<Position>(1);
+				let vel = it.field_opt::<Velocity>(2);
+				for i in 0..it.count() {
+					if let Some(vel) = &vel {
+						pos.get_mut(i).x += vel.get(i).x;
+						pos.get_mut(i).y += vel.get(i).y;
+					}
+				}
+			});
+		system.run(0.0);
+
+		assert_eq!(still.get::<Position>(), &Position { x: 1.0, y: 1.0 });
+		assert_eq!(moving.get::<Position>(), &Position { x: 3.0, y: 4.0 });
+
+		let query = world.query().term::<Position>().term::<Velocity>().optional().build();
+		let (mut with, mut without) = (0, 0);
+		query.iter(|it| {
+			match it.field_opt::<Velocity>(2) {
+				Some(_) => with += it.count(),
+				None => without += it.count(),
+			}
+		});
+		assert_eq!((with, without), (1, 1));
+	}
+
+    #[test]
+    fn flecs_not_and_or_terms() {
+		struct Frozen;
+		struct Dog;
+		struct Cat;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		let frozen = world.component::<Frozen>();
+		let dog = world.component::<Dog>();
+		let cat = world.component::<Cat>();
+
+		let moving = world.entity().set(Position::default());
+		world.entity().set(Position::default()).add_id(frozen);
+
+		let filter = world.filter_builder().term::<Position>().term::<Frozen>().not().build();
+		let mut found = Vec::new();
+		filter.iter(|it| {
+			for i in 0..it.count() {
+				found.push(it.entity(i as i32));
+			}
+		});
+		assert_eq!(found, vec![moving]);
+
+		let a = world.entity().set(Position::default()).add_id(dog);
+		let b = world.entity().set(Position::default()).add_id(cat);
+		world.entity().set(Position::default());
+
+		let query = world.query().term::<Dog>().or().term::<Cat>().term::<Position>().build();
+		let mut found = Vec::new();
+		query.iter(|it| {
+			// the Or chain is a single field, Position is the second one
+			let _ = it.field::<Position>(2);
+			for i in 0..it.count() {
+				found.push((it.entity(i as i32), it.id(1).raw()));
+			}
+		});
+		found.sort_by_key(|(e, _)| e.raw());
+		assert_eq!(found, vec![(a, dog.raw()), (b, cat.raw())]);
+	}
+
+    #[test]
+    #[should_panic(expected = "has no data")]
+    fn flecs_not_term_has_no_field() {
+		struct Frozen;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Frozen>();
+		world.entity().set(Position::default());
+
+		let query = world.query().term::<Position>().term::<Frozen>().not().build();
+		query.iter(|it| {
+			it.field::<Frozen>(2);
+		});
+	}
+
+    #[test]
+    fn flecs_inout_annotations() {
+		struct Frozen;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		world.component::<Frozen>();
+		world.entity().set(Position::default()).set(Velocity::default()).add::<Frozen>();
+
+		let query = world.query()
+			.term::<Position>().out()
+			.term::<Velocity>().in_()
+			.term::<Frozen>().inout_none()
+			.build();
+		assert_eq!(query.to_string(), "[out] Position, [in] Velocity, [none] Frozen");
+
+		let filter = world.filter_builder().term::<Position>().inout().build();
+		assert_eq!(filter.to_string(), "[inout] Position");
+
+		let mut count = 0;
+		query.iter(|it| {
+			let _ = it.field::<Position>(1);
+			let _ = it.field::<Velocity>(2);
+			count += it.count();
+		});
+		assert_eq!(count, 1);
+
+		// each & each_mut annotate the terms of the component group
+		let system = world.system().term::<Position>().term::<Velocity>()
+			.each::<(Position, Velocity)>(|_, _| {});
+		let query = unsafe { ecs_system_get_query(world.raw(), system.entity().raw()) };
+		let expr = unsafe { flecs_to_owned_string(ecs_query_str(query)) };
+		assert_eq!(expr, "[in] Position, [in] Velocity");
+
+		#[allow(deprecated)]
+		let system = world.system().term::<Position>().term::<Velocity>().in_()
+			.each_mut::<(Position, Velocity)>(|_, _| {});
+		let query = unsafe { ecs_system_get_query(world.raw(), system.entity().raw()) };
+		let expr = unsafe { flecs_to_owned_string(ecs_query_str(query)) };
+		assert_eq!(expr, "[inout] Position, [in] Velocity");
+	}
+
+    #[test]
+    fn flecs_filter_expressions() {
+		let mut world = World::new();
+		world.component_named::<Position>("Position");
+		world.component_named::<Velocity>("Velocity");
+
+		let still = world.entity().set(Position { x: 1.0, y: 2.0 });
+		world.entity().set(Position::default()).set(Velocity::default());
+
+		let filter = world.filter_builder().expr("Position, !Velocity").try_build().unwrap();
+		let mut found = Vec::new();
+		filter.iter(|it| {
+			let pos = it.field_dynamic(1);
+			assert_eq!(pos.element_size(), std::mem::size_of::<Position>());
+			for i in 0..it.count() {
+				let bytes = pos.get(i);
+				let x = f32::from_ne_bytes(bytes[0..4].try_into().unwrap());
+				found.push((it.entity(i as i32), x));
+			}
+		});
+		assert_eq!(found, vec![(still, 1.0)]);
+
+		let query = world.query().expr("Position, !Velocity").build();
+		let mut count = 0;
+		query.iter(|it| count += it.count());
+		assert_eq!(count, 1);
+
+		// parse errors don't abort
+		let err = world.filter_builder().expr("Position, !!").try_build().err().unwrap();
+		assert_eq!(err.expr, "Position, !!");
+		assert!(world.query().expr("Position,, Unknown").try_build().is_err());
+	}
+
+    #[test]
+    fn flecs_rules() {
+		struct LocatedIn;
+		struct Likes;
+
+		let mut world = World::new();
+		let located_in = world.component_named::<LocatedIn>("LocatedIn");
+		world.find_entity(located_in).unwrap().add_id(unsafe { EcsTransitive });
+		let likes = world.component_named::<Likes>("Likes");
+
+		let us = world.entity().named("UnitedStates");
+		let california = world.entity().named("California").add_relation_ids(located_in, us);
+		let sf = world.entity().named("SanFrancisco").add_relation_ids(located_in, california);
+		let nl = world.entity().named("Netherlands");
+		let amsterdam = world.entity().named("Amsterdam").add_relation_ids(located_in, nl);
+
+		// transitive, San Francisco is located in the United States through California
+		let rule = world.rule().expr("(LocatedIn, UnitedStates)").build();
+		let mut found = Vec::new();
+		rule.iter(|it| {
+			for i in 0..it.count() {
+				found.push(it.entity(i as i32));
+			}
+		});
+		found.sort_by_key(|e| e.raw());
+		assert_eq!(found, vec![california, sf]);
+
+		// variables are bound per result and can be constrained
+		let rule = world.rule().expr("LocatedIn(SanFrancisco, $place)").build();
+		let mut places = Vec::new();
+		rule.iter(|it| places.push(it.get_var("place")));
+		places.sort_by_key(|e| e.raw());
+		assert_eq!(places, vec![us, california]);
+
+		let rule = world.rule().expr("(LocatedIn, $place)").build();
+
+		let mut found = Vec::new();
+		rule.rule_iter().set_var("place", nl).iter(|it| {
+			for i in 0..it.count() {
+				found.push(it.entity(i as i32));
+			}
+		});
+		assert_eq!(found, vec![amsterdam]);
+
+		// joins, people who like each other
+		let alice = world.entity().named("Alice");
+		let bob = world.entity().named("Bob");
+		let carol = world.entity().named("Carol");
+		alice.add_relation_ids(likes, bob);
+		bob.add_relation_ids(likes, alice);
+		carol.add_relation_ids(likes, alice);
+
+		let rule = world.rule().expr("(Likes, $other), Likes($other, $This)").build();
+		let mut pairs = Vec::new();
+		rule.iter(|it| {
+			for i in 0..it.count() {
+				pairs.push((it.entity(i as i32), it.get_var("other")));
+			}
+		});
+		pairs.sort_by_key(|(e, _)| e.raw());
+		assert_eq!(pairs, vec![(alice, bob), (bob, alice)]);
+
+		assert!(world.rule().expr("(Likes, $other").try_build().is_err());
+	}
+
+    #[test]
+    fn flecs_wildcard_terms() {
+		struct Likes;
+		struct Knows;
+
+		let mut world = World::new();
+		let likes = world.component::<Likes>();
+		let knows = world.component::<Knows>();
+
+		let alice = world.entity().named("Alice");
+		let bob = world.entity().named("Bob");
+		let carol = world.entity()
+			.add_relation_ids(likes, alice)
+			.add_relation_ids(likes, bob)
+			.add_relation_ids(knows, alice);
+		let dave = world.entity().add_relation_ids(knows, bob);
+
+		// carol is returned once per matching pair
+		let filter = world.filter_builder().term_pair(likes, Wildcard).build();
+		let mut found = Vec::new();
+		filter.iter(|it| {
+			let pair = it.pair(1).unwrap();
+			assert_eq!(pair.first(), RawId::from(likes));
+			for i in 0..it.count() {
+				found.push((it.entity(i as i32), pair.second().raw()));
+			}
+		});
+		found.sort_by_key(|(_, target)| *target);
+		assert_eq!(found, vec![(carol, alice.raw()), (carol, bob.raw())]);
+
+		// Any returns carol once
+		let filter = world.filter_builder().term_pair(likes, Any).build();
+		let mut count = 0;
+		filter.iter(|it| count += it.count());
+		assert_eq!(count, 1);
+
+		// wildcard relation
+		let query = world.query().term_pair(Wildcard, alice).build();
+		let mut found = Vec::new();
+		query.iter(|it| {
+			for i in 0..it.count() {
+				found.push((it.entity(i as i32), it.pair(1).unwrap().first().raw()));
+			}
+		});
+		found.sort_by_key(|(_, relation)| *relation);
+		assert_eq!(found, vec![(carol, likes.raw()), (carol, knows.raw())]);
+
+		let query = world.query().term_pair(Wildcard, bob).build();
+		let mut found = Vec::new();
+		query.iter(|it| {
+			for i in 0..it.count() {
+				found.push((it.entity(i as i32), it.pair(1).unwrap().first().raw()));
+			}
+		});
+		found.sort_by_key(|(e, _)| e.raw());
+		assert_eq!(found, vec![(carol, likes.raw()), (dave, knows.raw())]);
+	}
+
+    #[test]
+    fn flecs_up_and_cascade_terms() {
+		#[derive(Default, Debug, PartialEq, Clone, Copy)]
+		struct WorldPosition {
+			x: f32,
+			y: f32,
+		}
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<WorldPosition>();
+
+		// created bottom-up, so the table order doesn't match the hierarchy
+		let grand_child = world.entity()
+			.set(Position { x: 1.0, y: 0.0 })
+			.set(WorldPosition::default());
+		let child = world.entity()
+			.set(Position { x: 10.0, y: 0.0 })
+			.set(WorldPosition::default());
+		let root = world.entity()
+			.set(Position { x: 100.0, y: 1.0 })
+			.set(WorldPosition::default());
+		child.child_of(root);
+		grand_child.child_of(child);
+		let other_root = world.entity()
+			.set(Position { x: 5.0, y: 5.0 })
+			.set(WorldPosition::default());
+
+		let query = world.query()
+			.term::<Position>()
+			.term::<WorldPosition>()
+			.term::<WorldPosition>().cascade().optional()
+			.build();
+		query.iter(|it| {
+			let pos = it.field::<Position>(1);
+			let world_pos = it.field::<WorldPosition>(2);
+			let parent = it.field_opt::<WorldPosition>(3);
+			for i in 0..it.count() {
+				let mut p = WorldPosition { x: pos.get(i).x, y: pos.get(i).y };
+				if let Some(parent) = &parent {
+					assert!(parent.is_shared());
+					p.x += parent.get(0).x;
+					p.y += parent.get(0).y;
+				}
+				*world_pos.get_mut(i) = p;
+			}
+		});
+
+		assert_eq!(root.get::<WorldPosition>(), &WorldPosition { x: 100.0, y: 1.0 });
+		assert_eq!(child.get::<WorldPosition>(), &WorldPosition { x: 110.0, y: 1.0 });
+		assert_eq!(grand_child.get::<WorldPosition>(), &WorldPosition { x: 111.0, y: 1.0 });
+		assert_eq!(other_root.get::<WorldPosition>(), &WorldPosition { x: 5.0, y: 5.0 });
+
+		// up finds the component on the nearest parent that has it
+		world.entity().child_of(grand_child).set(Position::default());
+		let filter = world.filter_builder()
+			.term::<Position>().self_()
+			.term::<WorldPosition>().up()
+			.build();
+		let mut parents = Vec::new();
+		filter.iter(|it| parents.push(it.field::<WorldPosition>(2).get(0).x));
+		parents.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		assert_eq!(parents, vec![100.0, 110.0, 111.0]);
+
+		let filter = world.filter_builder()
+			.term::<Position>()
+			.term::<WorldPosition>().up_rel(unsafe { EcsChildOf })
+			.build();
+		let mut count = 0;
+		filter.iter(|it| count += it.count());
+		assert_eq!(count, 3);
+	}
+
+    #[test]
+    fn flecs_iter_metadata() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let mut expected = Vec::new();
+		for i in 0..6 {
+			let e = world.entity().set(Position::default());
+			if i % 2 == 0 {
+				e.set(Velocity::default());
+			}
+			expected.push(e);
+		}
+
+		let filter = world.filter_builder().term::<Position>().build();
+		let mut found = Vec::new();
+		let mut offset = 0;
+		filter.iter(|it| {
+			assert_eq!(it.world().raw(), world.raw());
+			assert_eq!(it.entities().len(), it.count());
+			assert_eq!(it.frame_offset(), offset);
+			offset += it.count() as i32;
+			for (i, &id) in it.entities().iter().enumerate() {
+				assert_eq!(it.entity(i as i32).raw(), id);
+				found.push(it.entity(i as i32));
+			}
+		});
+		found.sort_by_key(|e| e.raw());
+		assert_eq!(found, expected);
+
+		let query = world.query().term::<Position>().build();
+		query.iter(|it| assert_eq!(it.table_count(), 2));
+
+		world.system().term::<Position>().iter(|it| {
+			assert_eq!(it.delta_time(), 0.5);
+		});
+		world.progress(0.5);
+	}
+
+    #[test]
+    fn flecs_term_iteration() {
+		struct Tag;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		let tag = world.component::<Tag>();
+
+		let a = world.entity().set(Position { x: 1.0, y: 0.0 });
+		let b = world.entity().set(Position { x: 2.0, y: 0.0 }).add_id(tag);
+		let base = world.prefab("Base").set(Position { x: 3.0, y: 0.0 });
+		let c = world.entity().is_a(base);
+
+		let mut found = Vec::new();
+		world.each_term(|e, p: &Position| found.push((e, p.x)));
+		found.sort_by_key(|(e, _)| e.raw());
+		assert_eq!(found, vec![(a, 1.0), (b, 2.0), (c, 3.0)]);
+
+		let mut found = Vec::new();
+		world.each_id(tag, |e| found.push(e));
+		assert_eq!(found, vec![b]);
+
+		// both iterate in deferred mode, removing the iterated id is safe
+		world.each_id(tag, |e| {
+			e.remove_id(tag);
+			assert!(e.has_id(tag));
+		});
+		assert!(!b.has_id(tag));
+		world.each_term(|e, _: &Position| {
+			e.destruct();
+		});
+		assert!(!a.is_alive() && !b.is_alive() && !c.is_alive());
+	}
+
+    #[test]
+    fn flecs_worker_and_page_iteration() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let mut all = Vec::new();
+		for i in 0..10_000 {
+			let e = world.entity().set(Position { x: i as f32, y: 0.0 });
+			if i % 3 == 0 {
+				e.set(Velocity::default());
+			}
+			all.push(e);
+		}
+
+		let query = world.query().term::<Position>().build();
+		let mut visited = Vec::new();
+		for worker in 0..4 {
+			let mut count = 0;
+			query.iter_worker(worker, 4, |it| {
+				let pos = it.field::<Position>(1);
+				for i in 0..it.count() {
+					assert_eq!(pos.get(i).x, it.entity(i as i32).get::<Position>().x);
+					visited.push(it.entity(i as i32));
+				}
+				count += it.count();
+			});
+			assert!((2490..=2510).contains(&count));
+		}
+		visited.sort_by_key(|e| e.raw());
+		assert_eq!(visited, all);
+
+		let page = |offset, limit| {
+			let mut entities = Vec::new();
+			query.iter_page(offset, limit, |it| {
+				let pos = it.field::<Position>(1);
+				for i in 0..it.count() {
+					entities.push((it.entity(i as i32), pos.get(i).x));
+				}
+			});
+			entities
+		};
+		let first = page(0, 100);
+		assert_eq!(first.len(), 100);
+		assert_eq!(page(0, 100), first);
+		let second = page(100, 100);
+		assert_eq!(second.len(), 100);
+		assert!(second.iter().all(|e| !first.contains(e)));
+		assert_eq!(page(9_950, 100).len(), 50);
+	}
+
+    #[test]
+    fn flecs_result_iterators() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let mut expected = Vec::new();
+		for i in 0..10 {
+			let e = world.entity().set(Position { x: i as f32, y: 0.0 });
+			if i % 2 == 0 {
+				e.set(Velocity { x: 1.0, y: i as f32 });
+			}
+			expected.push(e);
+		}
+
+		let filter = world.filter_builder().term::<Position>().build();
+		let mut found = filter.iter_entities().collect::<Vec<_>>();
+		found.sort_by_key(|e| e.raw());
+		assert_eq!(found, expected);
+		assert_eq!(filter.iter_entities().take(3).count(), 3);
+
+		let query = world.query().term::<Position>().term::<Velocity>().build();
+		let (e, (pos, vel)) = query.iter_tuples::<(Position, Velocity)>()
+			.find(|(_, (pos, _))| pos.x == 4.0)
+			.unwrap();
+		assert_eq!(e, expected[4]);
+		assert_eq!(pos, &Position { x: 4.0, y: 0.0 });
+		assert_eq!(vel.y, 4.0);
+
+		// breaking out early finalizes the iterator
+		for _ in 0..100 {
+			for e in query.iter_entities() {
+				if e == expected[0] {
+					break;
+				}
+			}
+		}
+
+		let mut it = filter.iter_entities();
+		it.next();
+		let rest = it.collect::<Vec<_>>();
+		assert_eq!(rest.len(), 9);
+	}
+
+    #[test]
+    fn flecs_find_helpers() {
+		struct PlayerTag;
+		struct EnemyTag;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<PlayerTag>();
+		world.component::<EnemyTag>();
+
+		// zero matches
+		let players = world.filter_builder().term::<PlayerTag>().build();
+		assert!(players.is_empty());
+		assert_eq!(players.count(), 0);
+		assert_eq!(players.first(), None);
+		assert_eq!(world.find_with(|p: &Position| p.x > 0.0), None);
+
+		// one match
+		let player = world.entity().add::<PlayerTag>().set(Position { x: 1.0, y: 0.0 });
+		assert!(!players.is_empty());
+		assert_eq!(players.count(), 1);
+		assert_eq!(players.first(), Some(player));
+		assert_eq!(world.find_with(|p: &Position| p.x > 0.0), Some(player));
+
+		// many matches
+		let enemies: Vec<Entity> = (0..5)
+			.map(|i| world.entity().add::<EnemyTag>().set(Position { x: -(i as f32), y: 0.0 }))
+			.collect();
+		let query = world.query().term::<EnemyTag>().build();
+		assert_eq!(query.count(), 5);
+		assert!(!query.is_empty());
+		assert!(enemies.contains(&query.first().unwrap()));
+		assert_eq!(query.find(|e| e.get::<Position>().x == -3.0), Some(enemies[3]));
+		assert_eq!(query.find(|e| e.get::<Position>().x == -7.0), None);
+		assert_eq!(world.find_with(|p: &Position| p.x == -2.0), Some(enemies[2]));
+		assert_eq!(world.filter_builder().term::<Position>().build().count(), 6);
+	}
+
+    #[test]
+    fn flecs_dynamic_filters() {
+		let mut world = World::new();
+		let health = world.component_dynamic("Health", Layout::from_size_align(8, 4).unwrap());
+		let mana = world.component_dynamic("Mana", Layout::from_size_align(4, 4).unwrap());
+
+		let mut expected = Vec::new();
+		for i in 0..5u32 {
+			let e = world.entity();
+			let mut data = [0u8; 8];
+			data[0..4].copy_from_slice(&i.to_ne_bytes());
+			data[4..8].copy_from_slice(&(i * 10).to_ne_bytes());
+			world.set_component(e.raw(), health, &data);
+			if i % 2 == 0 {
+				world.set_component(e.raw(), mana, &(i * 100).to_ne_bytes());
+				expected.push((e, data, i * 100));
+			}
+		}
+
+		let filter = world.filter_builder().with_ids(&[health, mana]).build();
+		let mut found = Vec::new();
+		filter.iter(|it| {
+			let health = it.field_dynamic(1);
+			let mana = it.field_dynamic(2);
+			assert_eq!((health.element_size(), mana.element_size()), (8, 4));
+			for i in 0..it.count() {
+				let mut data = [0u8; 8];
+				data.copy_from_slice(health.get(i));
+				let mana = u32::from_ne_bytes(mana.get(i).try_into().unwrap());
+				found.push((it.entity(i as i32), data, mana));
+			}
+		});
+		found.sort_by_key(|(e, _, _)| e.raw());
+		assert_eq!(found, expected);
+
+		// bytes read back through the filter match read_component
+		for (e, data, _) in &found {
+			assert_eq!(world.read_component(e.raw(), health), Some(&data[..]));
+		}
+		assert_eq!(world.filter_builder().with_id(health).build().count(), 5);
+	}
+
+    #[test]
+    fn flecs_checked_fields() {
+		struct Mass(f32);
+
+		let mut world = World::new();
+		world.component_named::<Position>("Position");
+		world.component_named::<Velocity>("Velocity");
+		world.component::<Mass>();
+		world.entity().set(Position::default()).set(Velocity::default());
+
+		let query = world.query().term::<Position>().term::<Velocity>().build();
+		query.iter(|it| {
+			assert!(it.try_field::<Position>(1).is_ok());
+			assert!(it.try_field::<Velocity>(2).is_ok());
+			assert_eq!(it.try_field::<Position>(0).err(), Some(FieldError::IndexOutOfRange { index: 0, field_count: 2 }));
+			assert_eq!(it.try_field::<Position>(3).err(), Some(FieldError::IndexOutOfRange { index: 3, field_count: 2 }));
+			match it.try_field::<Position>(2) {
+				Err(FieldError::TypeMismatch { index: 2, found, .. }) => assert_eq!(found, "Velocity"),
+				_ => panic!("expected a type mismatch"),
+			}
+			assert!(matches!(it.try_field::<Mass>(1), Err(FieldError::TypeMismatch { .. })));
+
+			// a type the world never saw is an error too
+			struct Unregistered;
+			assert!(matches!(it.try_field::<Unregistered>(1), Err(FieldError::NotRegistered { index: 1, .. })));
+		});
+	}
+
+    #[test]
+    #[should_panic(expected = "Field 2 is Velocity, not")]
+    fn flecs_field_type_mismatch() {
+		let mut world = World::new();
+		world.component_named::<Position>("Position");
+		world.component_named::<Velocity>("Velocity");
+		world.entity().set(Position::default()).set(Velocity::default());
+
+		let query = world.query().term::<Position>().term::<Velocity>().build();
+		query.iter(|it| {
+			it.field::<Position>(2);
+		});
+	}
+
+    #[test]
+    fn flecs_field_slices() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let entities: Vec<Entity> = (0..10)
+			.map(|i| world.entity().set(Position::default()).set(Velocity { x: i as f32, y: 1.0 }))
+			.collect();
+		let base = world.prefab("Base").set(Velocity { x: 100.0, y: 100.0 });
+		let instance = world.entity().set(Position::default()).is_a(base);
+
+		let query = world.query().term::<Position>().term::<Velocity>().build();
+		query.iter_mut(|it| {
+			let (count, is_self) = (it.count(), it.is_self(2));
+			let velocities: Vec<(f32, f32)> = it.field_slice::<Velocity>(2).iter().map(|v| (v.x, v.y)).collect();
+			let positions = it.field_slice_mut::<Position>(1);
+			assert_eq!(positions.len(), count);
+			if is_self {
+				assert_eq!(velocities.len(), count);
+				for (p, v) in positions.iter_mut().zip(velocities) {
+					p.x += v.0;
+					p.y += v.1;
+				}
+			} else {
+				// shared, a single value for all entities
+				assert_eq!(velocities.len(), 1);
+				for p in positions.iter_mut() {
+					p.x += velocities[0].0;
+					p.y += velocities[0].1;
+				}
+			}
+		});
+
+		for (i, e) in entities.iter().enumerate() {
+			assert_eq!(e.get::<Position>(), &Position { x: i as f32, y: 1.0 });
+		}
+		assert_eq!(instance.get::<Position>(), &Position { x: 100.0, y: 100.0 });
+	}
+
+    #[test]
+    fn flecs_iter_changed() {
+		use std::{cell::Cell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		world.entity().set(Position::default()).set(Velocity { x: 1.0, y: 0.0 });
+
+		// writer only touches Position when asked to, and skips the table otherwise
+		let write = Rc::new(Cell::new(true));
+		let w = write.clone();
+		world.system().term::<Position>().term::<Velocity>().in_().iter_mut(move |it| {
+			if !w.get() {
+				it.skip();
+				return;
+			}
+			let velocities = it.field::<Velocity>(2);
+			for (i, p) in it.field_slice_mut::<Position>(1).iter_mut().enumerate() {
+				p.x += velocities.get(i).x;
+			}
+		});
+
+		let work = Rc::new(Cell::new(0));
+		let wk = work.clone();
+		world.system().term::<Position>().in_().iter(move |it| {
+			if it.changed() {
+				wk.set(wk.get() + 1);
+			}
+		});
+
+		world.progress(0.0);
+		assert_eq!(work.get(), 1);
+		world.progress(0.0);
+		assert_eq!(work.get(), 2);
+
+		write.set(false);
+		world.progress(0.0);
+		world.progress(0.0);
+		assert_eq!(work.get(), 2);
+
+		write.set(true);
+		world.progress(0.0);
+		assert_eq!(work.get(), 3);
+
+		// filters have no change tracking
+		let filter = world.filter_builder().term::<Position>().build();
+		let mut changed = false;
+		filter.iter(|it| changed = it.changed());
+		assert!(changed);
+	}
+
+    #[test]
+    fn flecs_entity_to_json() {
+		#[repr(C)]
+		struct Health { value: i32 }
+		struct Enemy;
+		struct Likes;
+
+		let mut world = World::new();
+		let health = world.component::<Health>().member::<i32>("value");
+		let enemy = world.component::<Enemy>();
+		let likes = world.component::<Likes>();
+
+		let player = world.entity().named("Player");
+		let parent = world.entity().named("Level");
+		let e = world.entity().named("Goblin")
+			.child_of(parent)
+			.add::<Enemy>()
+			.add_relation_ids(likes, player)
+			.set(Health { value: 10 });
+
+		let json = e.to_json(&EntityToJsonOptions::default());
+		assert_eq!(json, r#"{"path":"Level.Goblin", "ids":[["Health"], ["Enemy"], ["Likes", "Player"]]}"#);
+
+		// only the reflected component has a value
+		let json = e.to_json(&EntityToJsonOptions { serialize_values: true, ..Default::default() });
+		assert!(json.ends_with(r#""values":[{"value":10}, 0, 0]}"#), "{}", json);
+
+		assert_eq!(world.to_json_id(health), r#"{"value":["int"]}"#);
+		assert_eq!(world.to_json_id(enemy), "");
+	}
+
+    #[test]
+    fn flecs_entity_from_json() {
+		#[repr(C)]
+		#[derive(Debug, PartialEq)]
+		struct Health { value: i32, max: i32 }
+		struct Enemy;
+		struct Likes;
+
+		let mut world = World::new();
+		world.component::<Health>().member::<i32>("value").member::<i32>("max");
+		world.component::<Enemy>();
+		let likes = world.component::<Likes>();
+
+		let player = world.entity().named("Player");
+		let e = world.entity().named("Goblin")
+			.add::<Enemy>()
+			.add_relation_ids(likes, player)
+			.set(Health { value: 10, max: 20 });
+		let json = e.to_json(&EntityToJsonOptions { serialize_values: true, ..Default::default() });
+
+		let copy = world.entity();
+		copy.from_json(&json).unwrap();
+		assert_eq!(copy.get::<Health>(), &Health { value: 10, max: 20 });
+		assert!(copy.has::<Enemy>());
+		assert!(copy.has_relation(likes, player));
+		assert_eq!(copy.name(), "");
+
+		// patches only touch what they mention
+		copy.from_json(r#"{"ids":[["Health"]], "values":[{"value":5, "max":20}]}"#).unwrap();
+		assert_eq!(copy.get::<Health>(), &Health { value: 5, max: 20 });
+		assert!(copy.has::<Enemy>());
+
+		let err = copy.from_json(r#"{"ids":[["Health"], ["Missing"]]}"#).unwrap_err();
+		assert_eq!(err.offset, 21);
+		assert_eq!(err.to_string(), "Invalid JSON at offset 21: unknown entity 'Missing'");
+		assert!(copy.from_json(r#"{"ids":[["Health"]]"#).is_err());
+
+		// \u escapes, including surrogate pairs
+		world.entity().named("Gobl\u{e9}n \u{1f47a}");
+		copy.from_json(r#"{"ids":[["Gobl\u00e9n \ud83d\udc7a"]]}"#).unwrap();
+		assert!(copy.has_id(world.lookup("Gobl\u{e9}n \u{1f47a}").unwrap()));
+		let err = copy.from_json(r#"{"ids":[["Gobl\ud83d"]]}"#).unwrap_err();
+		assert_eq!(err.reason, "unpaired surrogate in \\u escape");
+		let err = copy.from_json(r#"{"ids":[["Health\u0000"]]}"#).unwrap_err();
+		assert_eq!(err.offset, 9);
+		assert_eq!(err.reason, "unexpected nul character in path");
+	}
+
+    #[test]
+    fn flecs_world_json_round_trip() {
+		#[repr(C)]
+		#[derive(Debug, PartialEq)]
+		struct Health { value: i32 }
+		struct Enemy;
+		struct Likes;
+
+		fn register(world: &mut World) -> (Entity, Entity) {
+			world.component::<Health>().member::<i32>("value");
+			world.component::<Enemy>();
+			(world.component::<Likes>(), world.component::<Enemy>())
+		}
+
+		let mut world = World::new();
+		let (likes, _) = register(&mut world);
+		world.system().term::<Health>().iter(|_| {});
+		let level = world.entity().named("Level");
+		let player = world.entity().named("Player").set(Health { value: 100 });
+		world.entity().named("Goblin").child_of(level).add::<Enemy>().add_relation_ids(likes, player).set(Health { value: 10 });
+		world.entity().child_of(level).add::<Enemy>();
+
+		let json = world.to_json(&WorldToJsonOptions::default());
+		assert!(!json.contains("flecs.core"), "{}", json);
+		assert!(json.contains(r#"{"path":"Level.Goblin", "ids":[["Health"], ["Enemy"], ["Likes", "Player"]], "values":[{"value":10}, 0, 0]}"#), "{}", json);
+
+		// the target world already has a Player, which is updated in place
+		let mut target = World::new();
+		let (likes, enemy) = register(&mut target);
+		let existing_player = target.entity().named("Player");
+		target.from_json(&json).unwrap();
+
+		let goblin = target.lookup("Level::Goblin").unwrap();
+		assert_eq!(goblin.get::<Health>(), &Health { value: 10 });
+		assert!(goblin.has::<Enemy>());
+		assert!(goblin.has_relation(likes, existing_player));
+		assert_eq!(existing_player.get::<Health>(), &Health { value: 100 });
+
+		let level = target.lookup("Level").unwrap();
+		let mut children = Vec::new();
+		target.each_id(ecs_pair(unsafe { EcsChildOf }, level.raw()), |e| children.push(e));
+		assert_eq!(children.len(), 2);
+		assert!(children.iter().any(|e| e.name().is_empty() && e.has_id(enemy)));
+
+		let err = target.from_json(r#"[{"path":"Orc", "ids":[["Missing"]]}]"#).unwrap_err();
+		assert_eq!(err.offset, 24);
+		assert!(target.from_json(r#"[{"ids":[]}]"#).is_err());
+	}
+
+    #[test]
+    fn flecs_world_from_json_numeric_paths() {
+		struct Enemy;
+		struct Likes;
+
+		let mut world = World::new();
+		world.component::<Enemy>();
+		let likes = world.component::<Likes>();
+
+		// unnamed entities are written as their id, which belongs to another entity here
+		let bystander = world.entity();
+		let json = format!(r#"[{{"path":"Hero", "ids":[["Likes", "{0}"]]}}, {{"path":"{0}", "ids":[["Enemy"]]}}]"#, bystander.raw());
+		world.from_json(&json).unwrap();
+		assert!(!bystander.has::<Enemy>());
+
+		let hero = world.lookup("Hero").unwrap();
+		let target = hero.target(likes, 0).unwrap();
+		assert_ne!(target.raw(), bystander.raw());
+		assert!(target.has::<Enemy>() && target.name().is_empty());
+
+		// an entity actually named like an id is found by its name
+		let named = world.entity().named("42");
+		world.from_json(r#"[{"path":"42", "ids":[["Enemy"]]}]"#).unwrap();
+		assert!(named.has::<Enemy>());
+	}
+
+    #[test]
+    fn flecs_filter_to_json() {
+		#[repr(C)]
+		struct Health { value: i32 }
+		struct Enemy;
+
+		let mut world = World::new();
+		world.component::<Health>().member::<i32>("value");
+		world.component::<Enemy>();
+		world.entity().named("Goblin").add::<Enemy>().set(Health { value: 10 });
+		world.entity().named("Orc").add::<Enemy>().set(Health { value: 30 });
+		world.entity().named("Player").set(Health { value: 100 });
+
+		let filter = world.filter_builder().expr("Health, Enemy").build();
+		let json: serde_json::Value = serde_json::from_str(&filter.to_json(&IterToJsonOptions::default())).unwrap();
+		assert_eq!(json["ids"], serde_json::json!(["Health", "Enemy"]));
+		let results = json["results"].as_array().unwrap();
+		assert_eq!(results.len(), 1);
+		assert_eq!(results[0]["entities"], serde_json::json!(["Goblin", "Orc"]));
+		assert_eq!(results[0]["values"], serde_json::json!([[{"value": 10}, {"value": 30}], 0]));
+
+		let query = world.query().expr("Health").build();
+		let opts = IterToJsonOptions { serialize_values: false, serialize_entity_ids: true, ..Default::default() };
+		let json: serde_json::Value = serde_json::from_str(&query.to_json(&opts)).unwrap();
+		let results = json["results"].as_array().unwrap();
+		assert_eq!(results.len(), 2);
+		assert!(results.iter().all(|r| r.get("values").is_none() && r.get("entity_ids").is_some()));
+		let mut entities: Vec<&str> = results.iter()
+			.flat_map(|r| r["entities"].as_array().unwrap().iter().map(|e| e.as_str().unwrap()))
+			.collect();
+		entities.sort_unstable();
+		assert_eq!(entities, vec!["Goblin", "Orc", "Player"]);
+	}
+
+    #[test]
+    fn flecs_run_script() {
+		#[repr(C)]
+		#[derive(Debug, PartialEq)]
+		struct Health { value: i32 }
+
+		let mut world = World::new();
+		world.component::<Health>().member::<i32>("value");
+
+		world.run_script("ships.flecs", r#"
+			Prefab(Ship)
+			Ship = Health{value: 100}
+			Ship {
+				Engine = Health{value: 50}
+			}
+			Falcon : Ship
+		"#).unwrap();
+		let ship = world.lookup("Ship").unwrap();
+		let engine = world.lookup("Ship::Engine").unwrap();
+		let falcon = world.lookup("Falcon").unwrap();
+		assert_eq!(ship.get::<Health>(), &Health { value: 100 });
+		assert_eq!(engine.get::<Health>(), &Health { value: 50 });
+		assert_eq!(falcon.get::<Health>(), &Health { value: 100 });
+
+		assert!(world.lookup("Falcon::Engine").is_some());
+
+		let path = std::env::temp_dir().join("flecs_run_script.flecs");
+		std::fs::write(&path, "Orc = Health{value: 30}\n").unwrap();
+		world.run_script_file(path.to_str().unwrap()).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(world.lookup("Orc").unwrap().get::<Health>(), &Health { value: 30 });
+
+		// syntax errors carry the offending line
+		let err = world.run_script("broken.flecs", "Goblin = Health{value: 10}\n- Troll\n").unwrap_err();
+		assert_eq!(err.name, "broken.flecs");
+		assert!(err.message.contains("\n- Troll\n"), "{}", err);
+
+		let err = world.run_script("broken.flecs", "Troll = Health{hp: 10}").unwrap_err();
+		assert!(err.message.contains("unknown member 'hp' for type 'Health'"), "{}", err);
+		assert!(world.run_script_file("missing.flecs").is_err());
+		assert!(world.run_script("nul.flecs", "Troll\0").unwrap_err().message.contains("nul character at 5"));
+	}
+
+    #[test]
+    fn flecs_rest_options() {
+		let world = World::new();
+		assert_eq!(world.rest_options(), None);
+
+		// find a free port
+		let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+		let options = RestOptions { port, ipaddr: Some("127.0.0.1".to_owned()) };
+		world.enable_rest_with(options.clone()).unwrap();
+		assert_eq!(world.rest_options(), Some(options));
+
+		world.disable_rest();
+		assert_eq!(world.rest_options(), None);
+
+		let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = taken.local_addr().unwrap().port();
+		let err = world.enable_rest_with(RestOptions { port, ipaddr: Some("127.0.0.1".to_owned()) }).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+		assert_eq!(world.rest_options(), None);
+	}
+
+    #[test]
+    fn flecs_app_frames() {
+		use std::{cell::Cell, rc::Rc};
+
+		let frames = Rc::new(Cell::new(0));
+		let initialized = Rc::new(Cell::new(false));
+
+		let mut world = World::new();
+		world.component::<Position>();
+		let f = frames.clone();
+		world.system().term::<Position>().iter(move |_| f.set(f.get() + 1));
+
+		let init = initialized.clone();
+		let result = world.app()
+			.frames(3)
+			.init(move |world| {
+				world.entity().set(Position::default());
+				init.set(true);
+			})
+			.run();
+
+		assert_eq!(result, 0);
+		assert!(initialized.get());
+		assert_eq!(frames.get(), 3);
+
+		// quit ends the loop early
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+		let f = frames.clone();
+		world.system().term::<Position>().no_readonly(true).iter(move |it| {
+			f.set(f.get() + 1);
+			it.world().quit();
+		});
+		assert_eq!(world.app().frames(10).run(), 0);
+		assert_eq!(frames.get(), 4);
+	}
+
+    #[test]
+    fn flecs_import_module() {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		#[derive(Default)]
+		struct RigidBody { mass: f32 }
+		#[derive(Default)]
+		struct Collider { radius: f32 }
+
+		static IMPORTS: AtomicUsize = AtomicUsize::new(0);
+
+		struct PhysicsModule;
+		impl Module for PhysicsModule {
+			const NAME: &'static str = "physics";
+
+			fn module(world: &mut World) {
+				IMPORTS.fetch_add(1, Ordering::SeqCst);
+				world.component::<RigidBody>();
+				world.component::<Collider>();
+				world.system().named("Integrate").term::<RigidBody>().iter(|_| {});
+			}
+		}
+
+		// each world gets its own module and components
+		for _ in 0..2 {
+			let mut world = World::new();
+			let physics = world.import::<PhysicsModule>();
+			assert_eq!(world.import::<PhysicsModule>(), physics);
+			assert_eq!(physics.path(), "physics");
+
+			let rigid_body = world.lookup("physics::RigidBody").unwrap();
+			assert_eq!(world.component::<RigidBody>(), rigid_body);
+			assert!(world.lookup("physics::Collider").is_some());
+			assert!(world.lookup("physics::Integrate").is_some());
+			assert!(world.lookup("RigidBody").is_none());
+
+			// the scope is restored after importing
+			assert_eq!(world.entity().named("Player").path(), "Player");
+		}
+		assert_eq!(IMPORTS.load(Ordering::SeqCst), 2);
+	}
+
+    #[test]
+    #[cfg(feature = "log")]
+    fn flecs_log_forwarding() {
+		use std::sync::Mutex;
+
+		struct CaptureLogger(Mutex<Vec<(::log::Level, String)>>);
+		impl ::log::Log for CaptureLogger {
+			fn enabled(&self, _: &::log::Metadata) -> bool { true }
+			fn log(&self, record: &::log::Record) {
+				if record.target() == "flecs" {
+					self.0.lock().unwrap().push((record.level(), record.args().to_string()));
+				}
+			}
+			fn flush(&self) {}
+		}
+
+		static LOGGER: CaptureLogger = CaptureLogger(Mutex::new(Vec::new()));
+		::log::set_logger(&LOGGER).unwrap();
+		::log::set_max_level(::log::LevelFilter::Trace);
+
+		let world = World::new();
+		log::enable_colors(true);
+		assert!(world.filter_builder().expr("Position(").try_build().is_err());
+
+		let records = LOGGER.0.lock().unwrap();
+		let (level, msg) = records.iter().find(|(_, msg)| msg.contains("Position(")).unwrap();
+		assert_eq!(*level, ::log::Level::Error);
+		assert!(!msg.contains('\x1b') && !msg.ends_with('\n'), "{:?}", msg);
+	}
+
+    #[test]
+    fn flecs_component_from_expr() {
+		#[repr(C)]
+		#[derive(Debug, PartialEq)]
+		struct Vec2 { x: f32, y: f32 }
+		#[repr(C)]
+		#[derive(Debug, PartialEq)]
+		struct Transform { position: Vec2, scale: f32 }
+
+		let mut world = World::new();
+		world.component::<Vec2>().member::<f32>("x").member::<f32>("y");
+		let transform = world.component::<Transform>().member::<Vec2>("position").member::<f32>("scale");
+
+		let mut out = [0u8; std::mem::size_of::<Transform>()];
+		world.parse_component_value(transform.raw(), "{position: {x: 1, y: -2.5}, scale: 3}", &mut out).unwrap();
+		let value = unsafe { &*(out.as_ptr() as *const Transform) };
+		assert_eq!(value, &Transform { position: Vec2 { x: 1.0, y: -2.5 }, scale: 3.0 });
+
+		// unmentioned members keep their current value
+		let e = world.entity().set(Transform { position: Vec2 { x: 5.0, y: 6.0 }, scale: 1.0 });
+		world.set_component_from_expr(e.raw(), transform.raw(), "{scale: 2}").unwrap();
+		assert_eq!(e.get::<Transform>(), &Transform { position: Vec2 { x: 5.0, y: 6.0 }, scale: 2.0 });
+
+		let err = world.set_component_from_expr(e.raw(), transform.raw(), "{scale: 2, size: 1}").unwrap_err();
+		assert_eq!(err.message, "unknown member 'size' for type 'Transform'");
+		let err = world.set_component_from_expr(e.raw(), transform.raw(), "{position: [1, 2]}").unwrap_err();
+		assert!(err.message.starts_with("expected '{'"), "{}", err);
+		assert_eq!(err.offset, Some(12));
+		let err = world.parse_component_value(transform.raw(), "{scale: 2} x", &mut out).unwrap_err();
+		assert_eq!(err.offset, Some(11));
+		assert_eq!(e.get::<Transform>().scale, 2.0);
+	}
+
+    #[test]
+    fn flecs_bulk_init_with_data() {
+		#[derive(Clone, Copy, Debug, PartialEq)]
+		struct Pos(f32, f32);
+		#[derive(Clone, Copy, Debug, PartialEq)]
+		struct Vel(f32, f32);
+		#[derive(Clone, Copy)]
+		struct Unregistered;
+
+		let mut world = World::new();
+		world.component::<Pos>();
+		world.component::<Vel>();
+
+		let positions: Vec<Pos> = (0..100).map(|i| Pos(i as f32, 0.0)).collect();
+		let velocities = vec![Vel(1.0, 2.0); 100];
+		let entities = world.bulk::<(Pos, Vel)>().count(100).column(&velocities).column(&positions).build().unwrap();
+		assert_eq!(entities.len(), 100);
+		for (i, e) in entities.iter().enumerate() {
+			let e = world.find_entity(*e).unwrap();
+			assert_eq!(e.get::<Pos>(), &Pos(i as f32, 0.0));
+			assert_eq!(e.get::<Vel>(), &Vel(1.0, 2.0));
+		}
+		assert_eq!(world.filter_builder().with_components::<(Pos, Vel)>().build().count(), 100);
+
+		let err = world.bulk::<(Pos, Vel)>().count(99).column(&positions).column(&velocities).build().unwrap_err();
+		assert!(matches!(err, BulkError::LengthMismatch { expected: 99, found: 100, .. }));
+		let err = world.bulk::<(Pos, Vel)>().count(100).column(&positions).build().unwrap_err();
+		assert_eq!(err, BulkError::MissingColumn { index: 1 });
+		let err = world.bulk::<(Pos, Vel)>().count(100).column(&positions).column(&positions).build().unwrap_err();
+		assert!(matches!(err, BulkError::NotInGroup { .. }));
+		let err = world.bulk::<(Pos, Unregistered)>().count(1).column(&positions[..1]).build().unwrap_err();
+		assert!(matches!(err, BulkError::NotRegistered { component } if component.ends_with("Unregistered")));
+		assert_eq!(world.filter_builder().with_components::<(Pos, Vel)>().build().count(), 100);
+	}
+
+    #[test]
+    fn flecs_each_mixed_and_optional() {
+		#[derive(Debug, PartialEq)]
+		struct Target(u32);
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		world.component::<Target>();
+
+		let a = world.entity().set(Position::default()).set(Velocity { x: 1.0, y: 2.0 }).set(Target(7));
+		let b = world.entity().set(Position::default()).set(Velocity { x: 3.0, y: 4.0 });
+		world.entity().set(Position::default());
+
+		let mut seen = Vec::new();
+		world.for_each::<(&mut Position, &Velocity, Option<&Target>)>(|e, (p, v, t)| {
+			p.x += v.x;
+			p.y += v.y;
+			seen.push((e, t.map(|t| t.0)));
+		});
+		seen.sort_by_key(|(e, _)| e.raw());
+		assert_eq!(seen, vec![(a, Some(7)), (b, None)]);
+		assert_eq!(b.get::<Position>(), &Position { x: 3.0, y: 4.0 });
+
+		world.for_each::<(&Velocity, Option<&mut Target>)>(|_, (_, t)| {
+			if let Some(t) = t {
+				t.0 += 1;
+			}
+		});
+		assert_eq!(a.get::<Target>(), &Target(8));
+
+		// The terms are annotated with the access of the elements, and added when missing
+		let system = world.system()
+			.for_each::<(&mut Position, &Velocity, Option<&Target>)>(|_, (p, _, t)| p.x += t.map_or(0.0, |t| t.0 as f32));
+		let query = unsafe { ecs_system_get_query(world.raw(), system.entity().raw()) };
+		let expr = unsafe { flecs_to_owned_string(ecs_query_str(query)) };
+		assert_eq!(expr, "[inout] Position, [in] Velocity, [in] ?Target");
+		world.progress(0.0);
+		assert_eq!(a.get::<Position>(), &Position { x: 9.0, y: 2.0 });
+		assert_eq!(b.get::<Position>(), &Position { x: 3.0, y: 4.0 });
+	}
+
+    #[test]
+    fn flecs_each1_cached_filter() {
+		let mut world = World::new();
+		let pos = world.component::<Position>();
+		world.component::<Velocity>();
+		world.component::<Serializable>();
+		world.entity().set(Position { x: 1.0, y: 0.0 });
+		world.entity().set(Position { x: 2.0, y: 0.0 }).set(Velocity::default());
+
+		let filter = cache::WorldInfoCache::get_or_init_filter(world.raw(), &[pos.raw()]);
+		let mut sum = 0.0;
+		world.each1(|_, p: &Position| sum += p.x);
+		assert_eq!(sum, 3.0);
+		assert_eq!(cache::WorldInfoCache::get_or_init_filter(world.raw(), &[pos.raw()]), filter);
+
+		// tables created after the filter are still matched
+		world.entity().set(Position { x: 4.0, y: 0.0 }).add::<Serializable>();
+		let mut sum = 0.0;
+		world.each1(|_, p: &Position| sum += p.x);
+		assert_eq!(sum, 7.0);
+	}
+
+    #[test]
+    fn flecs_pair_component_bytes() {
+		struct Damage(f32);
+
+		let mut world = World::new();
+		let damage = world.component::<Damage>().raw();
+		let pos = world.component::<Position>().raw();
+		let fire = world.entity().raw();
+		let begin = world.entity().raw();
+
+		let pair = world.pair(damage, fire);
+		assert!(RawId::from(pair).is_pair());
+		assert!(!is_pair(damage));
+
+		// serialize the pair of one entity and restore it on another
+		let src = world.entity();
+		world.set_component(src.raw(), pair, &2.5f32.to_ne_bytes());
+		let bytes = world.read_component(src.raw(), pair).unwrap().to_vec();
+		assert_eq!(bytes, 2.5f32.to_ne_bytes());
+
+		let dst = world.entity();
+		world.set_component(dst.raw(), pair, &bytes);
+		assert!(dst.has_id(pair));
+		world.write_component(dst.raw(), pair, |b| b.copy_from_slice(&5.0f32.to_ne_bytes()));
+		assert_eq!(world.read_component(dst.raw(), pair), Some(&5.0f32.to_ne_bytes()[..]));
+		assert_eq!(world.read_component(src.raw(), pair), Some(&2.5f32.to_ne_bytes()[..]));
+
+		// a pair with a tag relation has the data type of its target
+		let begin_pos = world.pair(begin, pos);
+		let data = [1.0f32.to_ne_bytes(), 2.0f32.to_ne_bytes()].concat();
+		world.set_component(dst.raw(), begin_pos, &data);
+		assert_eq!(world.read_component(dst.raw(), begin_pos), Some(&data[..]));
+		assert_eq!(world.read_component(dst.raw(), world.pair(begin, damage)), None);
+	}
+
+    #[test]
+    fn flecs_lookup_symbol_and_alias() {
+		let mut world1 = World::new();
+		let pos1 = world1.component::<Position>();
+		world1.component::<Velocity>();
+
+		// registered in a different order, the ids differ but the symbols don't
+		let mut world2 = World::new();
+		let vel2 = world2.component::<Velocity>();
+		let pos2 = world2.component::<Position>();
+
+		assert_eq!(world1.lookup_symbol("Position"), Some(pos1));
+		assert_eq!(world2.lookup_symbol("Position"), Some(pos2));
+		assert_eq!(world2.lookup_symbol("Velocity"), Some(vel2));
+
+		// a miss doesn't create an entity
+		assert_eq!(world2.lookup_symbol("Mass"), None);
+		assert_eq!(world2.lookup("Mass"), None);
+
+		let e = world1.entity().named("player_controller");
+		world1.set_alias(e, "Player");
+		assert_eq!(world1.lookup("Player"), Some(e));
+		assert_eq!(world1.lookup("player_controller"), Some(e));
+		assert_eq!(world2.lookup("Player"), None);
+	}
+
+    #[test]
+    fn flecs_entity_rename() {
+		let world = World::new();
+		let slot = world.entity().named("slot_1");
+		slot.set_name("Castle").unwrap();
+		assert_eq!(slot.name(), "Castle");
+		assert_eq!(world.lookup("Castle"), Some(slot));
+		assert_eq!(world.lookup("slot_1"), None);
+
+		// renaming to its own name is fine, a sibling's name is not
+		slot.set_name("Castle").unwrap();
+		let other = world.entity().named("Forest");
+		let err = other.set_name("Castle").unwrap_err();
+		assert_eq!(err, NameError { name: "Castle".to_owned(), existing: slot });
+		assert_eq!(other.name(), "Forest");
+
+		// the same name is allowed under another parent
+		let child = world.entity_child_of(other).named("child");
+		child.set_name("Castle").unwrap();
+		assert_eq!(world.lookup("Forest::Castle"), Some(child));
+		assert_eq!(world.lookup("Castle"), Some(slot));
+
+		slot.remove_name();
+		assert_eq!(slot.name(), "");
+		assert_eq!(world.lookup("Castle"), None);
+		other.set_name("Castle").unwrap();
+		assert_eq!(world.lookup("Castle"), Some(other));
+	}
+
+    #[test]
+    fn flecs_typed_ids() {
+		assert_eq!(std::mem::size_of::<Option<RawId>>(), 8);
+		assert_eq!(std::mem::size_of::<Option<PairId>>(), 8);
+
+		let mut world = World::new();
+		let pos = world.component::<Position>();
+		let likes = world.entity();
+		let bob = world.entity();
+
+		assert_eq!(RawId::new(0), None);
+		assert!(RawId::try_from(0u64).is_err());
+		let id = RawId::from(pos);
+		assert_eq!(u64::from(id), pos.raw());
+		assert!(!id.is_pair());
+		assert_eq!(id.first(), None);
+		assert_eq!(PairId::try_from(id), Err(id));
+
+		let pair = PairId::new(likes, bob);
+		let id = RawId::from(pair);
+		assert!(id.is_pair());
+		assert!(id.has_flag(unsafe { ECS_PAIR }));
+		assert!(!id.has_flag(unsafe { ECS_OVERRIDE }));
+		assert_eq!(id.first(), Some(RawId::from(likes)));
+		assert_eq!(id.second(), Some(RawId::from(bob)));
+		assert_eq!(PairId::try_from(id), Ok(pair));
+
+		// typed ids are accepted wherever an id is
+		let e = world.entity().add_id(pair).set(Position::default());
+		assert!(e.has_id(id));
+		assert!(e.has_id(RawId::from(pos)));
+		assert_eq!(world.pair(likes, bob), pair);
+		assert_eq!(world.component_raw_id::<Position>(), RawId::from(pos));
+		assert_eq!(e.ids(), vec![RawId::from(pos), id]);
+		assert_eq!(world.read_component(e, pos), Some(&[0u8; 8][..]));
+
+		// the deprecated u64 versions keep working
+		#[allow(deprecated)]
+		{
+			assert_eq!(world.pair_id(likes.raw(), bob.raw()), pair.raw());
+			assert_eq!(world.component_id::<Position>(), pos.raw());
+			assert_eq!(e.type_id_list(), vec![pos.raw(), pair.raw()]);
+		}
+	}
+
+    #[test]
+    fn flecs_world_minimal_and_args() {
+		let mut world = World::new_minimal();
+		assert!(world.lookup("flecs::core").is_some());
+		assert!(world.lookup("flecs::pipeline").is_none());
+		assert!(world.lookup("flecs::system").is_none());
+
+		world.component::<Position>();
+		world.component::<Velocity>();
+		let e = world.entity().set(Position { x: 1.0, y: 2.0 }).set(Velocity { x: 3.0, y: 4.0 });
+		world.entity().set(Position::default());
+		world.for_each::<(&mut Position, &Velocity)>(|_, (p, v)| {
+			p.x += v.x;
+			p.y += v.y;
+		});
+		assert_eq!(e.get::<Position>(), &Position { x: 4.0, y: 6.0 });
+		let query = world.query().term::<Position>().build();
+		assert_eq!(query.iter_entities().count(), 2);
+
+		let world = World::new_with_args(&["/usr/local/bin/game", "--unused"]);
+		assert!(world.lookup("flecs::pipeline").is_some());
+		let world_e = world.lookup("flecs::core::World").unwrap();
+		assert_eq!(world_e.doc_name().as_deref(), Some("game"));
+	}
+
+    #[test]
+    fn flecs_world_destroy_and_post_frame_hooks() {
+		use std::{cell::Cell, rc::Rc};
+
+		struct DropCounter(Rc<Cell<u32>>);
+		impl Drop for DropCounter {
+			fn drop(&mut self) {
+				self.0.set(self.0.get() + 1);
+			}
+		}
+
+		let runs = Rc::new(Cell::new(0));
+		let drops = Rc::new(Cell::new(0));
+		{
+			let mut world = World::new();
+			world.component::<Position>();
+			let e = world.entity().set(Position::default());
+
+			let (r, d) = (runs.clone(), DropCounter(drops.clone()));
+			world.run_post_frame(move |w| {
+				let _d = d;
+				assert!(w.is_alive(e.raw()));
+				r.set(r.get() + 1);
+			});
+			assert_eq!(runs.get(), 0);
+			world.progress(0.0);
+			assert_eq!((runs.get(), drops.get()), (1, 1));
+			world.progress(0.0);
+			assert_eq!(runs.get(), 1);
+
+			// still pending when the world is dropped
+			let (r, d) = (runs.clone(), DropCounter(drops.clone()));
+			world.run_post_frame(move |_| {
+				let _d = d;
+				r.set(r.get() + 10);
+			});
+			let (r, d) = (runs.clone(), DropCounter(drops.clone()));
+			world.on_destroy(move |_| {
+				let _d = d;
+				r.set(r.get() + 100);
+			});
+			assert_eq!(runs.get(), 1);
+		}
+		assert_eq!((runs.get(), drops.get()), (111, 3));
+	}
+
+    #[test]
+    fn flecs_filter_with_prefabs() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let prefab = world.prefab("Base").set(Position::default());
+		let instance = world.entity().is_a(prefab);
+		let moving = world.entity().set(Position::default()).set(Velocity::default());
+
+		let query = world.query().term::<Position>().build();
+		let found: Vec<Entity> = query.iter_entities().collect();
+		assert!(!found.contains(&prefab));
+		assert!(found.contains(&instance));
+
+		let query = world.query().term::<Position>().with_prefabs().build();
+		let found: Vec<Entity> = query.iter_entities().collect();
+		assert!(found.contains(&prefab) && found.contains(&instance));
+
+		// flecs ignores the flags of queries, they match through optional terms instead
+		moving.disable();
+		assert_eq!(world.query().term::<Velocity>().build().iter_entities().count(), 0);
+		let query = world.query().term::<Velocity>().with_disabled().build();
+		assert_eq!(query.to_string(), "Velocity, [none] ?Disabled");
+		assert_eq!(query.iter_entities().collect::<Vec<_>>(), vec![moving]);
+		moving.enable();
+
+		let filter = world.filter_builder().term::<Position>().query_flags(EcsFilterMatchPrefab).without::<Velocity>().build();
+		let found: Vec<Entity> = filter.iter_entities().collect();
+		assert!(found.contains(&prefab) && found.contains(&instance));
+		assert!(!found.contains(&moving));
+	}
+
+    #[test]
+    fn flecs_iter_table_columns() {
+		struct Tag;
+		struct Mass(f32);
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		world.component::<Tag>();
+		world.component::<Mass>();
+
+		let entities: Vec<Entity> = (0..4).map(|i| world.entity()
+			.set(Position { x: i as f32, y: 0.0 })
+			.set(Velocity { x: 1.0, y: 2.0 })
+			.add::<Tag>()).collect();
+		world.entity().set(Position::default());
+
+		let query = world.query().term::<Velocity>().build();
+		query.iter_mut(|it| {
+			let entities = it.entities().to_vec();
+			let mut table = it.table();
+			assert_eq!(table.count(), 4);
+			assert_eq!(table.entities(), &entities[..]);
+			assert_eq!(table.column::<Tag>().map(|tags| tags.len()), Some(4));
+			assert!(table.column::<Mass>().is_none());
+
+			let velocities: Vec<(f32, f32)> = table.column::<Velocity>().unwrap().iter().map(|v| (v.x, v.y)).collect();
+			let positions = table.column_mut::<Position>().unwrap();
+			for (p, v) in positions.iter_mut().zip(velocities) {
+				p.x += v.0;
+				p.y += v.1;
+			}
+		});
+
+		for (i, e) in entities.iter().enumerate() {
+			let p = e.get::<Position>();
+			assert_eq!((p.x, p.y), (i as f32 + 1.0, 2.0));
+		}
+	}
+
+    #[test]
+    fn flecs_deferred_deletes_during_iteration() {
+		use std::{cell::Cell, rc::Rc};
+
+		struct Dead;
+		struct Spawned;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Dead>();
+		world.component::<Spawned>();
+
+		let entities: Vec<Entity> = (0..6).map(|i| {
+			let e = world.entity().set(Position { x: i as f32, y: 0.0 });
+			if i % 2 == 0 {
+				e.add::<Dead>();
+			}
+			e
+		}).collect();
+
+		let visited = Rc::new(Cell::new(0));
+		let v = visited.clone();
+		world.system().term::<Position>().term::<Dead>().for_each::<&Position>(move |e, _| {
+			e.destruct();
+			// the deletion is deferred, so the entity is still alive while iterating
+			assert!(e.is_alive());
+			v.set(v.get() + 1);
+		});
+		world.system().term::<Position>().without::<Dead>().iter(|it| {
+			for _ in 0..it.count() {
+				it.world().entity().set(Position { x: -1.0, y: 0.0 }).add::<Spawned>();
+			}
+		});
+
+		world.progress(0.0);
+		assert_eq!(visited.get(), 3);
+		for (i, e) in entities.iter().enumerate() {
+			assert_eq!(e.is_alive(), i % 2 == 1);
+		}
+		let mut spawned = world.filter_builder().term::<Position>().term::<Spawned>().build();
+		assert_eq!(spawned.count(), 3);
+		spawned.for_each::<&Position>(|_, p| assert_eq!(p.x, -1.0));
+
+		// filters iterate in deferred mode too
+		let filter = world.filter_builder().term::<Spawned>().build();
+		filter.iter(|it| {
+			for i in 0..it.count() {
+				it.entity(i as i32).destruct();
+			}
+		});
+		assert_eq!(filter.count(), 0);
+	}
+
+    #[test]
+    fn flecs_world_components() {
+		struct Tag;
+
+		let mut world = World::new();
+		let pos = world.component::<Position>();
+		let vel = world.component::<Velocity>();
+		world.component::<Tag>();
+		let dynamic = world.component_dynamic_named("dynamic::Health", "Health", Layout::from_size_align(16, 8).unwrap());
+
+		let components: Vec<ComponentInfo> = world.components(false).collect();
+		assert_eq!(components.len(), 3);
+		let find = |id: EntityId| components.iter().find(|c| c.entity.id() == id).unwrap();
+		assert_eq!((find(pos.id()).name.as_str(), find(pos.id()).size, find(pos.id()).alignment), ("Position", 8, 4));
+		assert_eq!(find(vel.id()).size, std::mem::size_of::<Velocity>());
+		let health = find(dynamic);
+		assert_eq!((health.name.as_str(), health.symbol.as_str()), ("Health", "dynamic::Health"));
+		assert_eq!((health.size, health.alignment), (16, 8));
+
+		let all: Vec<ComponentInfo> = world.components(true).collect();
+		assert!(all.len() > components.len());
+		assert!(all.iter().any(|c| c.name == "Identifier" && c.size == std::mem::size_of::<EcsIdentifier>()));
+	}
+
+    #[test]
+    fn flecs_entity_type_comparison() {
+		struct Tag;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		world.component::<Tag>();
+
+		let a = world.entity().set(Position::default()).set(Velocity::default());
+		let b = world.entity().set(Velocity::default()).set(Position::default());
+		assert!(a.same_type_as(b));
+		assert_eq!(a.ids(), b.ids());
+		assert_eq!(a.type_hash(), b.type_hash());
+		assert!(a.ids().windows(2).all(|ids| ids[0] < ids[1]));
+
+		b.add::<Tag>();
+		assert!(!a.same_type_as(b));
+		assert_ne!(a.type_hash(), b.type_hash());
+
+		// entities of another world fall back to comparing id lists
+		let mut other = World::new();
+		other.component::<Position>();
+		other.component::<Velocity>();
+		let c = other.entity().set(Position::default()).set(Velocity::default());
+		assert!(a.same_type_as(c));
+		assert_eq!(a.type_hash(), c.type_hash());
+		assert!(!b.same_type_as(c));
+	}
+
+    #[test]
+    fn flecs_world_frame_hooks() {
+		use std::{cell::RefCell, rc::Rc};
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+
+		let log = Rc::new(RefCell::new(Vec::new()));
+		let l = log.clone();
+		world.on_frame_end(move |_| l.borrow_mut().push("end"));
+		let l = log.clone();
+		world.system().term::<Position>().iter(move |_| l.borrow_mut().push("update"));
+		let l = log.clone();
+		let begin = world.on_frame_begin(move |_| l.borrow_mut().push("begin 1"));
+		let l = log.clone();
+		world.on_frame_begin(move |_| l.borrow_mut().push("begin 2"));
+
+		world.progress(0.0);
+		assert_eq!(*log.borrow(), vec!["begin 1", "begin 2", "update", "end"]);
+
+		log.borrow_mut().clear();
+		begin.destruct();
+		unsafe { ecs_progress(world.raw(), 0.0) };
+		assert_eq!(*log.borrow(), vec!["begin 2", "update", "end"]);
+	}
+
+    #[test]
+    fn flecs_exclusive_state_relation() {
+		use std::{cell::Cell, rc::Rc};
+
+		struct State;
+
+		let mut world = World::new();
+		let state = world.component_exclusive::<State>();
+		let idle = world.entity().named("Idle");
+		let walking = world.entity().named("Walking");
+		let running = world.entity().named("Running");
+
+		let transitions = Rc::new(Cell::new(0));
+		let t = transitions.clone();
+		world.observer()
+			.term_id(world.pair(state, Wildcard))
+			.event_on_add()
+			.iter(move |it| t.set(t.get() + it.count()));
+
+		let e = world.entity();
+		assert_eq!(e.state::<State>(), None);
+		for target in [idle, walking, running] {
+			e.set_state::<State>(target);
+			assert_eq!(e.state::<State>(), Some(target));
+		}
+		assert_eq!(e.targets(state).collect::<Vec<_>>(), vec![running]);
+		assert!(!e.has_pair_second::<State>(idle) && !e.has_pair_second::<State>(walking));
+		assert_eq!(transitions.get(), 3);
+	}
+
+    #[test]
+    fn flecs_relation_cleanup_policies() {
+		struct OwnedBy;
+		struct Likes;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		let owned_by = world.component::<OwnedBy>().on_delete_target_delete();
+		let likes = world.component::<Likes>().on_delete_target(CleanupPolicy::Remove);
+		assert!(owned_by.has_relation(unsafe { EcsOnDeleteTarget }, CleanupPolicy::Delete.id()));
+
+		let owner = world.entity();
+		let items: Vec<Entity> = (0..3).map(|_| world.entity().add_pair_second::<OwnedBy>(owner)).collect();
+		let nested = world.entity().add_pair_second::<OwnedBy>(items[0]);
+		owner.destruct();
+		assert!(items.iter().all(|e| !e.is_alive()));
+		assert!(!nested.is_alive());
+
+		let friend = world.entity();
+		let fans: Vec<Entity> = (0..3).map(|_| world.entity().add_pair_second::<Likes>(friend).set(Position::default())).collect();
+		friend.destruct();
+		for fan in fans {
+			assert!(fan.is_alive() && fan.has::<Position>());
+			assert!(!fan.has_relation_wildcard(likes));
+		}
+	}
+
+    #[test]
+    fn flecs_filter_each_while_and_indexed() {
+		struct Tag;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		world.component::<Tag>();
+		for i in 0..10 {
+			let e = world.entity().set(Position { x: i as f32, y: 0.0 });
+			if i >= 5 {
+				e.add::<Tag>();
+			}
+		}
+
+		let mut filter = world.filter_builder().term::<Position>().build();
+		let mut xs = Vec::new();
+		filter.each_while::<&Position>(|_, p| {
+			xs.push(p.x);
+			xs.len() < 7
+		});
+		assert_eq!(xs, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+		let mut buffer = vec![-1.0; filter.count()];
+		filter.each_indexed::<&Position>(|i, _, p| buffer[i] = p.x);
+		assert_eq!(buffer, (0..10).map(|i| i as f32).collect::<Vec<_>>());
+	}
+
+    #[test]
+    fn flecs_each_shared_fields() {
+		#[derive(Default)]
+		struct Gravity { value: f32 }
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.set_singleton(Gravity { value: 2.0 });
+		for i in 0..10 {
+			world.entity().set(Position { x: i as f32, y: 0.0 });
+		}
+
+		// the singleton field has one value for the whole table
+		let mut filter = world.filter_builder().term::<Position>().term_singleton::<Gravity>().build();
+		let mut visited = 0;
+		filter.for_each::<(&mut Position, &Gravity)>(|_, (p, g)| {
+			p.y -= g.value;
+			visited += 1;
+		});
+		assert_eq!(visited, 10);
+		filter.for_each::<&Position>(|_, p| assert_eq!(p.y, -2.0));
+	}
+
+    #[test]
+    #[should_panic(expected = "a mutably accessed component can only be in a group once")]
+    fn flecs_each_rejects_aliasing_elements() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.entity().set(Position::default());
+		world.for_each::<(&mut Position, &Position)>(|_, _| {});
+	}
+
+    #[test]
+    #[should_panic(expected = "a mutably accessed component can only be in a group once")]
+    fn flecs_filter_each_rejects_aliasing_elements() {
+		let mut world = World::new();
+		world.component::<Position>();
+		let mut filter = world.filter_builder().term::<Position>().term::<Position>().build();
+		filter.for_each::<(&mut Position, &mut Position)>(|_, _| {});
+	}
+
+    #[test]
+    #[should_panic(expected = "doesn't match the id of term 2")]
+    fn flecs_filter_each_rejects_mismatched_elements() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		let mut filter = world.filter_builder().term::<Position>().term::<Velocity>().build();
+		filter.for_each::<(&mut Position, &Position)>(|_, _| {});
+	}
+
+    #[test]
+    #[should_panic(expected = "doesn't match the id of term 1")]
+    fn flecs_iter_tuples_rejects_mismatched_components() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+		let query = world.query().term::<Position>().term::<Velocity>().build();
+		let _ = query.iter_tuples::<(Velocity, Position)>();
+	}
+
+    #[test]
+    fn flecs_world_systems_info() {
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Velocity>();
+
+		let movement = world.system().named("Move").term::<Position>().term::<Velocity>().iter(|_| {});
+		let render = world.system().named("Render").on_store().expr("[in] Position").iter(|_| {});
+		render.disable();
+		world.system().named("Manual").kind(0).iter(|_| {});
+
+		let systems: Vec<SystemInfo> = world.systems().collect();
+		assert_eq!(systems.len(), 3);
+		let find = |name: &str| systems.iter().find(|s| s.name == name).unwrap().clone();
+
+		let info = find("Move");
+		assert_eq!(info.entity, movement.entity());
+		assert_eq!(info.phase.map(|p| p.id()), Some(unsafe { EcsOnUpdate }));
+		assert_eq!(info.query_str, "Position, Velocity");
+		assert!(info.enabled);
+
+		let info = find("Render");
+		assert_eq!(info.phase.map(|p| p.id()), Some(unsafe { EcsOnStore }));
+		assert_eq!(info.query_str, "[in] Position");
+		assert!(!info.enabled);
+
+		assert_eq!(find("Manual").phase, None);
+	}
+
+    #[test]
+    fn flecs_entity_in_stage() {
+		let mut world = World::new();
+		world.component::<Position>();
+		assert!(!world.is_stage() && !world.is_deferred());
+
+		let stage = world.async_stage();
+		assert!(stage.is_stage() && stage.is_deferred());
+
+		let entities: Vec<Entity> = (0..1000).map(|_| world.entity()).collect();
+		for (i, e) in entities.iter().enumerate() {
+			e.in_stage(&stage).set(Position { x: i as f32, y: 0.0 });
+		}
+		assert!(entities.iter().all(|e| !e.has::<Position>()));
+
+		stage.merge();
+		for (i, e) in entities.iter().enumerate() {
+			assert_eq!(e.get::<Position>().x, i as f32);
+		}
+	}
+
+    #[test]
+    fn flecs_async_stage_merge() {
+		struct Loaded;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Loaded>();
+
+		let stage = world.async_stage();
+		let created: Vec<Entity> = (0..10).map(|i| stage.entity().set(Position { x: i as f32, y: 1.0 }).add::<Loaded>()).collect();
+		let existing = world.entity().set(Position::default());
+		existing.in_stage(&stage).set(Position { x: 5.0, y: 5.0 });
+
+		let loaded = world.filter_builder().term::<Loaded>().build();
+		assert_eq!(loaded.count(), 0);
+		assert_eq!(existing.get::<Position>().x, 0.0);
+
+		stage.merge();
+		assert_eq!(loaded.count(), 10);
+		for (i, e) in created.iter().enumerate() {
+			assert_eq!(world.find_entity(e.id()).unwrap().get::<Position>().x, i as f32);
+		}
+		assert_eq!(existing.get::<Position>().x, 5.0);
+
+		// the stage keeps recording after a merge
+		stage.entity().add::<Loaded>();
+		assert_eq!(loaded.count(), 10);
+		stage.merge();
+		assert_eq!(loaded.count(), 11);
+	}
+
+    #[test]
+    fn flecs_async_stage_on_thread() {
+		let mut world = World::new();
+		world.component::<Position>();
+
+		let stage = world.async_stage();
+		let (stage, ids) = std::thread::spawn(move || {
+			let ids: Vec<EntityId> = (0..1000).map(|i| stage.entity().set(Position { x: i as f32, y: 0.0 }).id()).collect();
+			(stage, ids)
+		}).join().unwrap();
+
+		let positions = world.filter_builder().term::<Position>().build();
+		assert_eq!(positions.count(), 0);
+
+		stage.merge();
+		assert_eq!(positions.count(), 1000);
+		for (i, id) in ids.into_iter().enumerate() {
+			assert_eq!(world.find_entity(id).unwrap().get::<Position>().x, i as f32);
+		}
+	}
+
+    #[test]
+    fn flecs_each_typed_pairs() {
+		#[derive(Component)]
+		struct Requires { amount: u32 }
+		#[derive(Component)]
+		#[flecs(tag)]
+		struct Gold;
+		#[derive(Component)]
+		#[flecs(tag)]
+		struct Wood;
+		#[derive(Component)]
+		#[flecs(tag)]
+		struct Begin;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Requires>();
+		world.component::<Gold>();
+		world.component::<Wood>();
+		world.component::<Begin>();
+
+		let a = world.entity().set(Position { x: 1.0, y: 0.0 }).set_pair::<Requires, Gold>(Requires { amount: 10 });
+		let b = world.entity().set(Position { x: 2.0, y: 0.0 }).set_pair::<Requires, Gold>(Requires { amount: 20 });
+		let c = world.entity().set(Position { x: 3.0, y: 0.0 }).set_pair::<Requires, Wood>(Requires { amount: 30 });
+		b.add::<Wood>();
+
+		let mut found = Vec::new();
+		world.for_each::<(&Position, Pair<Requires, Gold>)>(|e, (p, req)| found.push((e, p.x, req.amount)));
+		assert_eq!(found, vec![(a, 1.0, 10), (b, 2.0, 20)]);
+
+		world.for_each::<PairMut<Requires, Gold>>(|_, req| req.amount += 1);
+		assert_eq!(a.get_pair::<Requires, Gold>().unwrap().amount, 11);
+
+		// a tag relation takes the data from the target
+		c.set_pair::<Begin, Position>(Position { x: 5.0, y: 6.0 });
+		let mut found = Vec::new();
+		world.for_each::<Pair<Begin, Position>>(|e, p| found.push((e, p.y)));
+		assert_eq!(found, vec![(c, 6.0)]);
+
+		// pairs in component groups are registered like components
+		world.component_pair::<Requires, Gold>();
+		let mut found = Vec::new();
+		world.filter::<(Position, Pair<Requires, Gold>)>().each(|e, (p, req)| found.push((e, p.x, req.amount)));
+		assert_eq!(found, vec![(a, 1.0, 11), (b, 2.0, 21)]);
+	}
+
+    #[test]
+    fn flecs_each_pair_target() {
+		struct Requires { amount: u32 }
+		struct Gold;
+		struct Wood;
+
+		let mut world = World::new();
+		world.component::<Position>();
+		world.component::<Requires>();
+		world.component::<Gold>();
+		world.component::<Wood>();
+
+		let a = world.entity().set(Position::default()).set_pair::<Requires, Gold>(Requires { amount: 10 });
+		let b = world.entity().set(Position::default()).set_pair::<Requires, Wood>(Requires { amount: 20 });
+		b.set_pair::<Requires, Gold>(Requires { amount: 30 });
+
+		// one result for each matched pair
+		let gold = world.id::<Gold>().unwrap();
+		let wood = world.id::<Wood>().unwrap();
+		let mut found = Vec::new();
+		world.for_each::<(PairTarget<Requires>, &Position)>(|e, ((target, req), _)| found.push((e, target, req.amount)));
+		found.sort_by_key(|&(e, target, _)| (e.id(), target.id()));
+		assert_eq!(found, vec![(a, gold, 10), (b, gold, 30), (b, wood, 20)]);
+	}
+
+    #[test]
+    #[should_panic(expected = "Alignment 32 is above the maximum of 16 for dynamic components")]
+    fn flecs_dynamic_component_over_aligned() {
+		let mut world = World::new();
+		world.component_dynamic("script.OverAligned", Layout::from_size_align(64, 32).unwrap());
+	}
+
     #[test]
     fn flecs_raw_binding_calls() {
 		let world = unsafe { ecs_init() };
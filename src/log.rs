@@ -0,0 +1,125 @@
+use crate::*;
+use std::cell::RefCell;
+
+// Logging
+//
+// Flecs logs through ecs_os_api.log_, and reports parse errors through the log instead of
+// returning them. The hook is installed once and, unless the calling thread is capturing
+// errors, forwards messages to the log or tracing crate (with the features of the same
+// name), or to the previous log function.
+
+static INSTALL_HOOK: std::sync::Once = std::sync::Once::new();
+static mut PREV_LOG: ecs_os_api_log_t = None;
+
+thread_local! {
+	static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Levels below -2 are errors (-3) and fatal errors (-4), -2 are warnings, 0 is tracing
+// and debug messages go from 1 up
+const LOG_ERROR: i32 = -3;
+const LOG_WARNING: i32 = -2;
+
+/// Enables or disables color codes in flecs messages, returns the previous setting
+pub fn enable_colors(enabled: bool) -> bool {
+	unsafe { ecs_log_enable_colors(enabled) }
+}
+
+// Removes the color codes flecs embeds in messages (ESC [ ... m)
+fn strip_colors(msg: &str) -> String {
+	let mut stripped = String::with_capacity(msg.len());
+	let mut chars = msg.chars();
+	while let Some(ch) = chars.next() {
+		if ch == '\x1b' {
+			for ch in chars.by_ref() {
+				if ch == 'm' {
+					break;
+				}
+			}
+		} else {
+			stripped.push(ch);
+		}
+	}
+	stripped
+}
+
+#[cfg(feature = "log")]
+fn forward(level: i32, file: Option<&str>, line: i32, msg: &str) {
+	let level = match level {
+		i32::MIN..=LOG_ERROR => ::log::Level::Error,
+		LOG_WARNING => ::log::Level::Warn,
+		-1..=0 => ::log::Level::Info,
+		1 => ::log::Level::Debug,
+		_ => ::log::Level::Trace,
+	};
+	::log::logger().log(&::log::Record::builder()
+		.args(format_args!("{}", msg))
+		.level(level)
+		.target("flecs")
+		.file(file)
+		.line(if line > 0 { Some(line as u32) } else { None })
+		.build());
+}
+
+#[cfg(all(feature = "tracing", not(feature = "log")))]
+fn forward(level: i32, file: Option<&str>, line: i32, msg: &str) {
+	let file = file.unwrap_or("");
+	match level {
+		i32::MIN..=LOG_ERROR => ::tracing::error!(target: "flecs", file, line, "{}", msg),
+		LOG_WARNING => ::tracing::warn!(target: "flecs", file, line, "{}", msg),
+		-1..=0 => ::tracing::info!(target: "flecs", file, line, "{}", msg),
+		1 => ::tracing::debug!(target: "flecs", file, line, "{}", msg),
+		_ => ::tracing::trace!(target: "flecs", file, line, "{}", msg),
+	}
+}
+
+unsafe extern "C" fn log_hook(level: i32, file: *const ::std::os::raw::c_char, line: i32, msg: *const ::std::os::raw::c_char) {
+	let captured = CAPTURED.with(|captured| {
+		// only errors are captured, other messages are logged as usual
+		match captured.borrow_mut().as_mut() {
+			Some(errors) if level <= LOG_ERROR => {
+				if !errors.is_empty() {
+					errors.push('\n');
+				}
+				// color codes are written into the message itself
+				errors.push_str(&strip_colors(flecs_to_rust_str(msg)));
+				true
+			}
+			_ => false,
+		}
+	});
+	if captured {
+		return;
+	}
+
+	#[cfg(any(feature = "log", feature = "tracing"))]
+	{
+		let file = if file.is_null() { None } else { Some(flecs_to_rust_str(file)) };
+		let msg = strip_colors(flecs_to_rust_str(msg));
+		forward(level, file, line, msg.trim_end());
+	}
+
+	#[cfg(not(any(feature = "log", feature = "tracing")))]
+	if let Some(prev) = PREV_LOG {
+		prev(level, file, line, msg);
+	}
+}
+
+// Installs the hook, the os api must be initialized (i.e. a world exists) or it would
+// overwrite the hook
+pub(crate) fn install_hook() {
+	INSTALL_HOOK.call_once(|| unsafe {
+		PREV_LOG = ecs_os_api.log_;
+		ecs_os_api.log_ = Some(log_hook);
+	});
+}
+
+// Runs f while collecting the errors flecs logs on this thread, instead of printing them
+pub(crate) fn capture_errors<R>(f: impl FnOnce() -> R) -> (R, String) {
+	install_hook();
+
+	let prev = CAPTURED.with(|captured| captured.borrow_mut().replace(String::new()));
+	let result = f();
+	let errors = CAPTURED.with(|captured| std::mem::replace(&mut *captured.borrow_mut(), prev));
+	(result, errors.unwrap_or_default())
+}
@@ -0,0 +1,155 @@
+use crate::*;
+use crate::cache::WorldInfoCache;
+
+// Meta addon support
+//
+// Reflection data lets flecs (and the REST explorer) understand the layout of a
+// component, so values can be serialized to JSON instead of showing as opaque bytes.
+
+// Maps a Rust type to the flecs meta type entity that describes it. Primitives map
+// to the builtin flecs types, anything else must be a registered or derived component.
+pub(crate) fn meta_type_id<T: Component>(world: *mut ecs_world_t) -> EntityId {
+	let type_id = TypeId::of::<T>();
+	let primitive = unsafe {
+		if type_id == TypeId::of::<bool>() { FLECS__Eecs_bool_t }
+		else if type_id == TypeId::of::<u8>() { FLECS__Eecs_u8_t }
+		else if type_id == TypeId::of::<u16>() { FLECS__Eecs_u16_t }
+		else if type_id == TypeId::of::<u32>() { FLECS__Eecs_u32_t }
+		else if type_id == TypeId::of::<u64>() { FLECS__Eecs_u64_t }
+		else if type_id == TypeId::of::<usize>() { FLECS__Eecs_uptr_t }
+		else if type_id == TypeId::of::<i8>() { FLECS__Eecs_i8_t }
+		else if type_id == TypeId::of::<i16>() { FLECS__Eecs_i16_t }
+		else if type_id == TypeId::of::<i32>() { FLECS__Eecs_i32_t }
+		else if type_id == TypeId::of::<i64>() { FLECS__Eecs_i64_t }
+		else if type_id == TypeId::of::<isize>() { FLECS__Eecs_iptr_t }
+		else if type_id == TypeId::of::<f32>() { FLECS__Eecs_f32_t }
+		else if type_id == TypeId::of::<f64>() { FLECS__Eecs_f64_t }
+		else { 0 }
+	};
+
+	if primitive != 0 {
+		return primitive;
+	}
+
+	WorldInfoCache::get_component_id_for_type::<T>(world)
+		.or_else(|| register_recorded_component_type::<T>(world))
+		.expect("Member type is not a primitive or registered component!")
+}
+
+impl Entity {
+	/// Adds a member to the reflection data of this component. Members are laid out
+	/// with C rules in the order they are added, so the component must be #[repr(C)].
+	/// 
+	/// The member type can be a primitive or another component with reflection data.
+	pub fn member<T: Component>(self, name: &str) -> Self {
+		self.member_array::<T>(name, 1)
+	}
+
+	/// Adds a fixed size array member, i.e. [T; count]
+	pub fn member_array<T: Component>(self, name: &str, count: i32) -> Self {
+		let type_id = meta_type_id::<T>(self.world());
+		self.member_id(name, type_id, count)
+	}
+
+	/// Adds a member annotated with a unit from the units addon, which shows up in
+	/// the explorer and in JSON type info. Requires World::import_units().
+	pub fn member_with_unit<T: Component>(self, name: &str, unit: impl units::Unit) -> Self {
+		let type_id = meta_type_id::<T>(self.world());
+		self.member_id_with_unit(name, type_id, 1, unit.id())
+	}
+
+	pub fn member_id(self, name: &str, type_id: impl AsEcsId, count: i32) -> Self {
+		self.member_id_with_unit(name, type_id, count, 0)
+	}
+
+	pub fn member_id_with_unit(self, name: &str, type_id: impl AsEcsId, count: i32, unit: impl AsEcsId) -> Self {
+		let (type_id, unit) = (type_id.id(), unit.id());
+		let world = self.world();
+		let member = Entity::new(world, unsafe { ecs_new_id(world) })
+			.child_of(self)
+			.named(name);
+
+		let value = EcsMember { type_: type_id, count, unit, offset: 0 };
+		unsafe {
+			ecs_set_id(world, 
+				member.raw(), 
+				FLECS__EEcsMember, 
+				std::mem::size_of::<EcsMember>() as size_t, 
+				&value as *const EcsMember as *const ::std::os::raw::c_void)
+		};
+		self
+	}
+}
+
+/// Error returned when an expression can't be parsed into a component value. offset is
+/// the position in the expression where parsing failed, which flecs only reports for
+/// syntax errors (not for e.g. unknown members).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+	pub offset: Option<usize>,
+	pub message: String,
+}
+
+impl std::fmt::Display for ExprError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.message)
+	}
+}
+
+impl std::error::Error for ExprError {}
+
+// Parse errors end with the expression and a line with a caret under the failing column
+fn caret_offset(message: &str) -> Option<usize> {
+	let line = message.lines().last()?;
+	if line.trim() != "^" {
+		return None;
+	}
+	line.find('^')
+}
+
+impl World {
+	/// Parses a flecs expression (e.g. "{x: 1, y: 2}") into the value of a component with
+	/// reflection data. out must be the size of the component, members which are not in
+	/// the expression keep their value.
+	pub fn parse_component_value(&self, comp: impl AsEcsId, expr: &str, out: &mut [u8]) -> Result<(), ExprError> {
+		let comp = comp.id();
+		let info = get_component_info(self.raw(), comp).expect("Component type not registered!");
+		assert_eq!(out.len(), info.size as usize, "Buffer size doesn't match the component size!");
+
+		let c_expr = std::ffi::CString::new(expr).unwrap();
+		let mut desc: ecs_parse_expr_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.expr = c_expr.as_ptr();	// Lets syntax errors point at the column
+		let (end, errors) = log::capture_errors(|| unsafe {
+			ecs_parse_expr(self.raw(), c_expr.as_ptr(), comp, out.as_mut_ptr() as *mut ::std::os::raw::c_void, &desc)
+		});
+		if end.is_null() {
+			return Err(ExprError { offset: caret_offset(&errors), message: errors });
+		}
+
+		let end = unsafe { end.offset_from(c_expr.as_ptr()) } as usize;
+		let rest = expr[end..].trim_start();
+		if !rest.is_empty() {
+			let offset = expr.len() - rest.len();
+			return Err(ExprError { offset: Some(offset), message: format!("unexpected characters after the value\n{}\n{:>2$}", expr, "^", offset + 1) });
+		}
+		Ok(())
+	}
+
+	/// Sets a component from a flecs expression, see parse_component_value. Members which
+	/// are not in the expression keep their current value, or are zero for a new component.
+	pub fn set_component_from_expr(&self, entity: impl AsEcsId, comp: impl AsEcsId, expr: &str) -> Result<(), ExprError> {
+		let (entity, comp) = (entity.id(), comp.id());
+		let size = get_component_info(self.raw(), comp).expect("Component type not registered!").size as usize;
+
+		// u64 storage keeps members aligned
+		let mut storage = vec![0u64; size / 8 + 1];
+		let value = unsafe { std::slice::from_raw_parts_mut(storage.as_mut_ptr() as *mut u8, size) };
+		if let Some(current) = self.read_component(entity, comp) {
+			value.copy_from_slice(current);
+		}
+
+		self.parse_component_value(comp, expr, value)?;
+		unsafe { ecs_set_id(self.raw(), entity, comp, size as size_t, value.as_ptr() as *const ::std::os::raw::c_void) };
+		Ok(())
+	}
+}
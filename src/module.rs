@@ -0,0 +1,35 @@
+use crate::*;
+
+// Module support
+//
+// A module groups components, systems and other entities under a module entity. Everything
+// created while the module is imported is scoped to it, e.g. "physics::RigidBody".
+
+/// Implemented by types that can be imported into a world with World::import
+pub trait Module: 'static {
+	/// Path of the module entity, nested modules are separated by '.' (e.g. "game.physics")
+	const NAME: &'static str;
+
+	/// Registers the contents of the module, called once per world
+	fn module(world: &mut World);
+}
+
+// Called by ecs_import when the module doesn't exist yet, ecs_import restores the scope afterwards
+unsafe extern "C" fn import_module<M: Module>(world: *mut ecs_world_t) {
+	let name = std::ffi::CString::new(M::NAME).unwrap();
+	let desc: ecs_component_desc_t = MaybeUninit::zeroed().assume_init();
+	let module = ecs_module_init(world, name.as_ptr(), &desc);
+	ecs_set_scope(world, module);
+
+	let mut world = World::new_from(world);
+	M::module(&mut world);
+}
+
+impl World {
+	/// Imports a module, or returns the module entity if it was already imported
+	pub fn import<M: Module>(&mut self) -> Entity {
+		let name = std::ffi::CString::new(M::NAME).unwrap();
+		let module = unsafe { ecs_import(self.raw(), Some(import_module::<M>), name.as_ptr()) };
+		Entity::new(self.raw(), module)
+	}
+}
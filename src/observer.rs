@@ -0,0 +1,252 @@
+use std::ffi::c_void;
+
+use crate::*;
+
+// Custom events
+//
+// Event types are registered like components, the event entity is the component
+// entity of the type. The payload is passed along as the iterator param.
+
+impl Entity {
+	/// Emits event E with payload to the observers of this entity.
+	/// The entity must have at least one component, since events are sent to a table.
+	pub fn emit<E: Component>(&self, payload: E) {
+		let world = self.world();
+		let event = register_component_typed::<E>(world, None);
+
+		let table = unsafe { ecs_get_table(world, self.raw()) };
+		assert!(!table.is_null(), "Cannot emit events to an entity without components!");
+		let record = unsafe { ecs_record_find(world, self.raw()).as_ref().unwrap() };
+
+		let mut ids = [event];
+		let ids_type = ecs_type_t { array: ids.as_mut_ptr(), count: 1 };
+
+		let mut desc: ecs_event_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.event = event;
+		desc.ids = &ids_type;
+		desc.table = table;
+		desc.offset = (record.row & ECS_ROW_MASK) as i32;
+		desc.count = 1;
+		desc.param = &payload as *const E as *const c_void;
+		desc.observable = world as *mut ecs_poly_t;
+		unsafe { ecs_emit(world, &mut desc) };
+	}
+
+	/// Calls cb each time event E is emitted to this entity. The observer lives as
+	/// long as the returned entity or the world.
+	pub fn observe<E: Component>(&self, mut cb: impl FnMut(Entity, &E) + 'static) -> Entity {
+		let world = self.world();
+		let event = register_component_typed::<E>(world, None);
+		let entity = *self;
+
+		let closure = move |it: *mut ecs_iter_t| {
+			let payload = unsafe { ((*it).param as *const E).as_ref().unwrap() };
+			cb(entity, payload);
+		};
+
+		let mut desc: ecs_observer_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.filter.terms[0].id = event;
+		desc.filter.terms[0].src.id = self.raw();
+		desc.events[0] = event;
+		set_observer_callback(&mut desc, closure);
+		Entity::new(world, unsafe { ecs_observer_init(world, &desc) })
+	}
+}
+
+// Observers
+//
+// Observers run a callback when an event (OnAdd, OnSet, OnRemove or a custom one) matches
+// their terms. Unlike systems they run immediately, as part of the operation causing the event.
+
+pub struct ObserverBuilder<'w> {
+	world: &'w World,
+	desc: ecs_observer_desc_t,
+	name_temp: String,
+	next_term_index: usize,
+	next_event_index: usize,
+}
+
+impl<'w> TermBuilder for ObserverBuilder<'w> {
+    fn world(&mut self) -> *mut ecs_world_t {
+        self.world.raw()
+    }
+
+	fn filter_desc(&mut self) -> &mut ecs_filter_desc_t {
+        &mut self.desc.filter
+	}
+
+    fn current_term(&mut self) -> &mut ecs_term_t {
+        &mut self.desc.filter.terms[self.next_term_index]
+    }
+
+    fn last_term(&mut self) -> &mut ecs_term_t {
+        assert!(self.next_term_index > 0, "No term to modify, add one first!");
+        &mut self.desc.filter.terms[self.next_term_index - 1]
+    }
+
+    fn next_term(&mut self) {
+        self.next_term_index += 1;
+    }
+}
+
+impl<'w> ObserverBuilder<'w> {
+	pub fn new(world: &'w World) -> Self {
+		Self {
+			world,
+			desc: unsafe { MaybeUninit::zeroed().assume_init() },
+			name_temp: "".to_owned(),
+			next_term_index: 0,
+			next_event_index: 0,
+		}
+	}
+
+	pub fn named(mut self, name: &str) -> Self {
+		self.name_temp = name.to_owned();
+		self
+	}
+
+	/// Adds an event the observer listens to, can be called multiple times
+	pub fn event<T: AsEcsId>(mut self, event: T) -> Self {
+		assert!(self.next_event_index < self.desc.events.len(), "Too many observer events!");
+		self.desc.events[self.next_event_index] = event.id();
+		self.next_event_index += 1;
+		self
+	}
+
+	pub fn event_on_add(self) -> Self {
+		self.event(unsafe { EcsOnAdd })
+	}
+
+	/// OnSet fires after a component value is set, e.g. with Entity::set or Entity::modified
+	pub fn event_on_set(self) -> Self {
+		self.event(unsafe { EcsOnSet })
+	}
+
+	/// OnRemove fires before the component is removed, so its value can still be read
+	pub fn event_on_remove(self) -> Self {
+		self.event(unsafe { EcsOnRemove })
+	}
+
+	/// Notifies when an entity starts matching all terms (Iter::event is EcsOnAdd) and when it
+	/// stops matching them (EcsOnRemove). Must be the only event of the observer.
+	pub fn event_monitor(self) -> Self {
+		self.event(unsafe { EcsMonitor })
+	}
+
+	/// Runs the observer for the entities that already match when it is created, as if
+	/// its events had just happened (only OnAdd and OnSet can be yielded)
+	pub fn yield_existing(mut self, yield_existing: bool) -> Self {
+		self.desc.yield_existing = yield_existing;
+		self
+	}
+
+	pub fn each<G: ComponentGroup<'w>>(self, mut cb: impl FnMut(Entity, G::RefTuple) + 'static) -> Entity {
+		self.build(move |it: *mut ecs_iter_t| {
+			unsafe {
+				let it = &(*it);
+				each_ref_tuple::<G>(it, &mut cb);
+			}
+		})
+	}
+
+	/// Calls cb per entity with the elements of G, which apply to the first terms like
+	/// for SystemBuilder::for_each
+	pub fn for_each<G: EachGroup<'w>>(mut self, mut cb: impl FnMut(Entity, G::Item) + 'static) -> Entity {
+		G::apply_terms(self.world.raw(), &mut self.desc.filter.terms[..G::COUNT]);
+		self.next_term_index = self.next_term_index.max(G::COUNT);
+		self.build(move |it: *mut ecs_iter_t| {
+			unsafe {
+				let it = &(*it);
+				each_group::<G>(it, &mut cb);
+			}
+		})
+	}
+
+	#[deprecated(note = "use for_each with &mut elements, e.g. for_each::<(&mut A, &mut B)>")]
+	pub fn each_mut<G: ComponentGroup<'w>>(self, mut cb: impl FnMut(Entity, G::MutRefTuple) + 'static) -> Entity {
+		self.build(move |it: *mut ecs_iter_t| {
+			unsafe {
+				let it = &(*it);
+				each_mut_tuple::<G>(it, &mut cb);
+			}
+		})
+	}
+
+	/// Iter::event returns the event which triggered the observer
+	pub fn iter<F: FnMut(&Iter) + 'static>(self, mut func: F) -> Entity {
+		self.build(move |it: *mut ecs_iter_t| {
+			let iter = Iter::new(it);
+			func(&iter);
+		})
+	}
+
+	fn build<F: FnMut(*mut ecs_iter_t)>(mut self, closure: F) -> Entity {
+		assert!(self.next_event_index > 0, "Observer needs at least one event!");
+		let world = self.world.raw();
+
+		let name_c_str = std::ffi::CString::new(self.name_temp.as_str()).unwrap();
+		if !self.name_temp.is_empty() {
+			let mut entity_desc: ecs_entity_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+			entity_desc.name = name_c_str.as_ptr();
+			self.desc.entity = unsafe { ecs_entity_init(world, &entity_desc) };
+		}
+
+		// flecs 3.0 drops the existing matches of observers with multiple terms, those are
+		// yielded here instead with a filter over the same terms
+		let yield_existing = self.desc.yield_existing && self.next_term_index > 1;
+		if yield_existing {
+			self.desc.yield_existing = false;
+		}
+
+		set_observer_callback(&mut self.desc, closure);
+		let observer = unsafe { ecs_observer_init(world, &self.desc) };
+
+		if yield_existing {
+			unsafe {
+				let filter = ecs_filter_init(world, &self.desc.filter);
+				let events = self.desc.events.iter().filter(|&&e| e == EcsOnAdd || e == EcsOnSet);
+				for &event in events {
+					let mut it = ecs_filter_iter(world, filter);
+					while ecs_filter_next(&mut it) {
+						it.event = event;
+						it.system = observer;
+						it.binding_ctx = self.desc.binding_ctx;
+						observer_callback::<F>(&mut it);
+					}
+				}
+				ecs_filter_fini(filter);
+			}
+		}
+
+		Entity::new(world, observer)
+	}
+}
+
+unsafe extern "C" fn observer_callback<F>(it: *mut ecs_iter_t)
+where
+	F: FnMut(*mut ecs_iter_t),
+{
+	if it.is_null() || (*it).binding_ctx.is_null() {
+		return;
+	}
+
+	let func = &mut *((*it).binding_ctx as *mut F);
+	func(it);
+}
+
+// The closure is boxed and owned by the observer, flecs frees it with the observer
+fn set_observer_callback<F>(desc: &mut ecs_observer_desc_t, closure: F)
+where
+	F: FnMut(*mut ecs_iter_t),
+{
+	desc.callback = Some(observer_callback::<F>);
+	desc.binding_ctx = Box::into_raw(Box::new(closure)) as *mut c_void;
+	desc.binding_ctx_free = Some(free_observer_closure::<F>);
+}
+
+unsafe extern "C" fn free_observer_closure<F>(ptr: *mut c_void)
+where
+	F: FnMut(*mut ecs_iter_t),
+{
+	drop(Box::from_raw(ptr as *mut F));
+}
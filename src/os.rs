@@ -0,0 +1,210 @@
+//! OS api overrides
+//!
+//! Flecs calls into the os api for memory, threads and time. Overrides must be installed
+//! before the first world is created, afterwards flecs may already hold memory or threads
+//! from the previous implementation.
+
+use crate::*;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+static WORLD_CREATED: AtomicBool = AtomicBool::new(false);
+static ALLOCATOR_SET: AtomicBool = AtomicBool::new(false);
+static THREADS_SET: AtomicBool = AtomicBool::new(false);
+
+/// Error returned when an os api override can't be installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsApiError {
+	/// A world was already created, overrides must be installed before that
+	WorldExists,
+	/// The override was already installed
+	AlreadySet,
+}
+
+impl std::fmt::Display for OsApiError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			OsApiError::WorldExists => write!(f, "The os api can't be changed after a world was created"),
+			OsApiError::AlreadySet => write!(f, "The os api override was already installed"),
+		}
+	}
+}
+
+impl std::error::Error for OsApiError {}
+
+pub(crate) fn on_world_created() {
+	WORLD_CREATED.store(true, Ordering::SeqCst);
+}
+
+// Applies an override on top of the flecs defaults, at most once per flag
+fn install(flag: &AtomicBool, apply: impl FnOnce(&mut ecs_os_api_t)) -> Result<(), OsApiError> {
+	if WORLD_CREATED.load(Ordering::SeqCst) {
+		return Err(OsApiError::WorldExists);
+	}
+	if flag.swap(true, Ordering::SeqCst) {
+		return Err(OsApiError::AlreadySet);
+	}
+
+	unsafe {
+		// No-op once initialized, which also keeps ecs_init from resetting the overrides
+		ecs_os_set_api_defaults();
+		let mut api = ecs_os_api;
+		apply(&mut api);
+		ecs_os_api = api;
+		ecs_os_set_api(&mut api);
+	}
+	Ok(())
+}
+
+pub type MallocFn = unsafe extern "C" fn(size: ecs_size_t) -> *mut ::std::os::raw::c_void;
+pub type ReallocFn = unsafe extern "C" fn(ptr: *mut ::std::os::raw::c_void, size: ecs_size_t) -> *mut ::std::os::raw::c_void;
+pub type CallocFn = unsafe extern "C" fn(size: ecs_size_t) -> *mut ::std::os::raw::c_void;
+pub type FreeFn = unsafe extern "C" fn(ptr: *mut ::std::os::raw::c_void);
+
+/// Routes all flecs allocations through the given functions
+pub fn set_allocator(malloc: MallocFn, realloc: ReallocFn, calloc: CallocFn, free: FreeFn) -> Result<(), OsApiError> {
+	install(&ALLOCATOR_SET, |api| {
+		api.malloc_ = Some(malloc);
+		api.realloc_ = Some(realloc);
+		api.calloc_ = Some(calloc);
+		api.free_ = Some(free);
+	})
+}
+
+/// Routes all flecs allocations through std::alloc, i.e. the global allocator
+pub fn use_rust_allocator() -> Result<(), OsApiError> {
+	set_allocator(rust_malloc, rust_realloc, rust_calloc, rust_free)
+}
+
+// std::alloc needs the size on free, so it is stored in front of each allocation
+const HEADER: usize = 16;
+
+fn layout(size: usize) -> std::alloc::Layout {
+	std::alloc::Layout::from_size_align(size + HEADER, HEADER).unwrap()
+}
+
+unsafe fn finish_alloc(base: *mut u8, size: usize) -> *mut ::std::os::raw::c_void {
+	if base.is_null() {
+		std::alloc::handle_alloc_error(layout(size));
+	}
+	*(base as *mut usize) = size;
+	base.add(HEADER) as *mut ::std::os::raw::c_void
+}
+
+unsafe extern "C" fn rust_malloc(size: ecs_size_t) -> *mut ::std::os::raw::c_void {
+	finish_alloc(std::alloc::alloc(layout(size as usize)), size as usize)
+}
+
+unsafe extern "C" fn rust_calloc(size: ecs_size_t) -> *mut ::std::os::raw::c_void {
+	finish_alloc(std::alloc::alloc_zeroed(layout(size as usize)), size as usize)
+}
+
+unsafe extern "C" fn rust_realloc(ptr: *mut ::std::os::raw::c_void, size: ecs_size_t) -> *mut ::std::os::raw::c_void {
+	if ptr.is_null() {
+		return rust_malloc(size);
+	}
+	let base = (ptr as *mut u8).sub(HEADER);
+	let old_size = *(base as *mut usize);
+	finish_alloc(std::alloc::realloc(base, layout(old_size), size as usize + HEADER), size as usize)
+}
+
+unsafe extern "C" fn rust_free(ptr: *mut ::std::os::raw::c_void) {
+	if ptr.is_null() {
+		return;
+	}
+	let base = (ptr as *mut u8).sub(HEADER);
+	std::alloc::dealloc(base, layout(*(base as *mut usize)));
+}
+
+/// Implements the flecs threading primitives with std::thread and parking_lot, for
+/// platforms without the default (posix/windows) implementation
+pub fn use_std_threads() -> Result<(), OsApiError> {
+	install(&THREADS_SET, |api| {
+		api.thread_new_ = Some(thread_new);
+		api.thread_join_ = Some(thread_join);
+		api.ainc_ = Some(ainc);
+		api.adec_ = Some(adec);
+		api.mutex_new_ = Some(mutex_new);
+		api.mutex_free_ = Some(mutex_free);
+		api.mutex_lock_ = Some(mutex_lock);
+		api.mutex_unlock_ = Some(mutex_unlock);
+		api.cond_new_ = Some(cond_new);
+		api.cond_free_ = Some(cond_free);
+		api.cond_signal_ = Some(cond_signal);
+		api.cond_broadcast_ = Some(cond_broadcast);
+		api.cond_wait_ = Some(cond_wait);
+		api.sleep_ = Some(sleep);
+	})
+}
+
+// Handles are boxed Rust objects cast to the integer handle type
+type Thread = std::thread::JoinHandle<usize>;
+type Mutex = parking_lot::Mutex<()>;
+type Cond = parking_lot::Condvar;
+
+unsafe fn handle<T>(handle: uintptr_t) -> &'static T {
+	&*(handle as *const T)
+}
+
+unsafe extern "C" fn thread_new(callback: ecs_os_thread_callback_t, param: *mut ::std::os::raw::c_void) -> ecs_os_thread_t {
+	let callback = callback.unwrap();
+	let param = param as usize;
+	let thread: Thread = std::thread::spawn(move || callback(param as *mut ::std::os::raw::c_void) as usize);
+	Box::into_raw(Box::new(thread)) as ecs_os_thread_t
+}
+
+unsafe extern "C" fn thread_join(thread: ecs_os_thread_t) -> *mut ::std::os::raw::c_void {
+	let thread = Box::from_raw(thread as *mut Thread);
+	thread.join().unwrap_or(0) as *mut ::std::os::raw::c_void
+}
+
+unsafe extern "C" fn ainc(value: *mut i32) -> i32 {
+	(*(value as *const AtomicI32)).fetch_add(1, Ordering::SeqCst) + 1
+}
+
+unsafe extern "C" fn adec(value: *mut i32) -> i32 {
+	(*(value as *const AtomicI32)).fetch_sub(1, Ordering::SeqCst) - 1
+}
+
+unsafe extern "C" fn mutex_new() -> ecs_os_mutex_t {
+	Box::into_raw(Box::new(Mutex::new(()))) as ecs_os_mutex_t
+}
+
+unsafe extern "C" fn mutex_free(mutex: ecs_os_mutex_t) {
+	drop(Box::from_raw(mutex as *mut Mutex));
+}
+
+unsafe extern "C" fn mutex_lock(mutex: ecs_os_mutex_t) {
+	// The guard is dropped by mutex_unlock
+	std::mem::forget(handle::<Mutex>(mutex).lock());
+}
+
+unsafe extern "C" fn mutex_unlock(mutex: ecs_os_mutex_t) {
+	handle::<Mutex>(mutex).force_unlock();
+}
+
+unsafe extern "C" fn cond_new() -> ecs_os_cond_t {
+	Box::into_raw(Box::new(Cond::new())) as ecs_os_cond_t
+}
+
+unsafe extern "C" fn cond_free(cond: ecs_os_cond_t) {
+	drop(Box::from_raw(cond as *mut Cond));
+}
+
+unsafe extern "C" fn cond_signal(cond: ecs_os_cond_t) {
+	handle::<Cond>(cond).notify_one();
+}
+
+unsafe extern "C" fn cond_broadcast(cond: ecs_os_cond_t) {
+	handle::<Cond>(cond).notify_all();
+}
+
+unsafe extern "C" fn cond_wait(cond: ecs_os_cond_t, mutex: ecs_os_mutex_t) {
+	// The mutex is locked by the caller, and stays locked after waiting
+	let mut guard = handle::<Mutex>(mutex).make_guard_unchecked();
+	handle::<Cond>(cond).wait(&mut guard);
+	std::mem::forget(guard);
+}
+
+unsafe extern "C" fn sleep(sec: i32, nanosec: i32) {
+	std::thread::sleep(std::time::Duration::new(sec as u64, nanosec as u32));
+}
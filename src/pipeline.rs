@@ -0,0 +1,60 @@
+use crate::*;
+
+// Custom pipelines
+//
+// A pipeline is a query over systems, the first term always matches EcsSystem. Systems are
+// usually assigned to a pipeline by giving them a phase tag with SystemBuilder::kind, which
+// the pipeline query then matches on.
+
+pub struct PipelineBuilder<'w> {
+	world: &'w World,
+	desc: ecs_pipeline_desc_t,
+	next_term_index: usize,
+}
+
+impl<'w> TermBuilder for PipelineBuilder<'w> {
+    fn world(&mut self) -> *mut ecs_world_t {
+        self.world.raw()
+    }
+
+	fn filter_desc(&mut self) -> &mut ecs_filter_desc_t {
+        &mut self.desc.query.filter
+	}
+
+    fn current_term(&mut self) -> &mut ecs_term_t {
+        &mut self.desc.query.filter.terms[self.next_term_index]
+    }
+
+    fn last_term(&mut self) -> &mut ecs_term_t {
+        assert!(self.next_term_index > 0, "No term to modify, add one first!");
+        &mut self.desc.query.filter.terms[self.next_term_index - 1]
+    }
+
+    fn next_term(&mut self) {
+        self.next_term_index += 1;
+    }
+}
+
+impl<'w> PipelineBuilder<'w> {
+	pub fn new(world: &'w World) -> Self {
+		let mut desc: ecs_pipeline_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.query.filter.terms[0].id = unsafe { EcsSystem };
+		Self { 
+			world,
+			desc,
+			next_term_index: 1
+		}
+	}
+
+	pub fn named(mut self, name: &str) -> Self {
+		self.desc.entity = self.world.entity().named(name).raw();
+		self
+	}
+
+	/// Creates the pipeline entity, systems in it run in declaration order
+	pub fn build(self) -> Entity {
+		let pipeline = unsafe { ecs_pipeline_init(self.world.raw(), &self.desc) };
+		assert!(pipeline != 0, "Invalid pipeline query!");
+		Entity::new(self.world.raw(), pipeline)
+	}
+}
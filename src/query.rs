@@ -1,59 +1,201 @@
+use std::ffi::c_void;
+
 use crate::*;
+use crate::cache::WorldInfoCache;
 
 pub struct Query {
 	world: *mut ecs_world_t,
 	query: *mut ecs_query_t,	
+	// owned by the query, freed by flecs with the query
+	group_by: *const GroupByFn,
 }
 
+// Group functions get the type (component ids) of a table and return its group id
+type GroupByFn = Box<dyn Fn(&[EntityId]) -> u64>;
+
 impl Query {
 	pub fn each<'w, G: ComponentGroup<'w>>(&'w self, mut cb: impl FnMut(Entity, G::RefTuple)) {
 		unsafe {
-			let mut it = ecs_query_iter(self.world, self.query);
-			while ecs_query_next(&mut it) {
-				// Iterate all entities for the type
-				for i in 0..it.count {
-                    let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(self.world, *eid);
-					let rt = G::iter_as_ref_tuple(&it, i as isize);
-					cb(e, rt);
+			check_filter_components::<G>(self.world, ecs_query_get_filter(self.query));
+			defer_iter(self.world, || {
+				let mut it = ecs_query_iter(self.world, self.query);
+				while ecs_query_next(&mut it) {
+					each_ref_tuple::<G>(&it, &mut cb);
 				}
-			}
+			});
+		}				
+	}
+
+	/// Calls cb for every matched entity with the elements of G, which map to the first
+	/// terms. Option elements are None when their (optional) term isn't set. Takes the
+	/// query mutably, so the &mut elements can't alias those of a nested for_each.
+	pub fn for_each<'w, G: EachGroup<'w>>(&'w mut self, mut cb: impl FnMut(Entity, G::Item)) {
+		unsafe {
+			check_filter_terms::<G>(self.world, ecs_query_get_filter(self.query));
+			defer_iter(self.world, || {
+				let mut it = ecs_query_iter(self.world, self.query);
+				while ecs_query_next(&mut it) {
+					each_group::<G>(&it, &mut cb);
+				}
+			});
 		}				
 	}
 
+	#[deprecated(note = "use for_each with &mut elements, e.g. for_each::<(&mut A, &mut B)>")]
 	pub fn each_mut<'w, G: ComponentGroup<'w>>(&mut self, mut cb: impl FnMut(Entity, G::MutRefTuple)) {
 		unsafe {
-			let mut it = ecs_query_iter(self.world, self.query);
-			while ecs_query_next(&mut it) {
-				// Iterate all entities for the type
-				for i in 0..it.count {
-                    let eid = it.entities.offset(i as isize).as_ref().unwrap();
-                    let e = Entity::new(self.world, *eid);
-
-					// TODO - performance is poor here due to looking up terms for each tuple entry * each entity
-					// we need to rework this to take slices of components, determined outside the loop
-					// so optimal iteration can occur
-					let rt = G::iter_as_mut_tuple(&it, i as isize);
-					cb(e, rt);
+			check_filter_components::<G>(self.world, ecs_query_get_filter(self.query));
+			defer_iter(self.world, || {
+				let mut it = ecs_query_iter(self.world, self.query);
+				while ecs_query_next(&mut it) {
+					each_mut_tuple::<G>(&it, &mut cb);
 				}
-			}
+			});
 		}				
 	}
 
 	pub fn iter<F: FnMut(&Iter)>(&self, mut func: F) {
 		unsafe {
-			let mut it = ecs_query_iter(self.world, self.query);
-			while ecs_query_next(&mut it) {
-				let iter = Iter::new(&mut it);
-				func(&iter);
-			}
+			defer_iter(self.world, || {
+				let mut it = ecs_query_iter(self.world, self.query);
+				while ecs_query_next(&mut it) {
+					let iter = Iter::new(&mut it);
+					func(&iter);
+				}
+			});
 		}				
 	}
+
+	/// Same as iter, with a mutable Iter for the accessors which hand out mutable
+	/// references, such as Iter::field_slice_mut
+	pub fn iter_mut<F: FnMut(&mut Iter)>(&self, mut func: F) {
+		unsafe {
+			defer_iter(self.world, || {
+				let mut it = ecs_query_iter(self.world, self.query);
+				while ecs_query_next(&mut it) {
+					let mut iter = Iter::new(&mut it);
+					func(&mut iter);
+				}
+			});
+		}
+	}
+
+	/// Returns an iterator over the matched entities
+	pub fn iter_entities(&self) -> EntityIter<'_> {
+		EntityIter::new(unsafe { ecs_query_iter(self.world, self.query) })
+	}
+
+	/// Returns an iterator over the matched entities and their components G
+	pub fn iter_tuples<'w, G: ComponentGroup<'w>>(&'w self) -> TupleIter<'w, G> {
+		unsafe { check_filter_components::<G>(self.world, ecs_query_get_filter(self.query)) };
+		TupleIter::new(self.iter_entities())
+	}
+
+	/// Returns the first matched entity
+	pub fn first(&self) -> Option<Entity> {
+		self.iter_entities().next()
+	}
+
+	/// Returns the first matched entity for which pred returns true, iteration stops there
+	pub fn find(&self, pred: impl FnMut(&Entity) -> bool) -> Option<Entity> {
+		self.iter_entities().find(pred)
+	}
+
+	/// Returns the number of matched entities, without accessing component data
+	pub fn count(&self) -> usize {
+		unsafe {
+			let mut it = ecs_query_iter(self.world, self.query);
+			ecs_iter_count(&mut it) as usize
+		}
+	}
+
+	/// Serializes all matched results to a single JSON document, component values
+	/// are only written for components with reflection data
+	pub fn to_json(&self, opts: &IterToJsonOptions) -> String {
+		unsafe {
+			let mut it = ecs_query_iter(self.world, self.query);
+			opts.iter_to_json(self.world, &mut it)
+		}
+	}
+
+	/// Returns true if no entities are matched, without accessing component data
+	pub fn is_empty(&self) -> bool {
+		unsafe {
+			let mut it = ecs_query_iter(self.world, self.query);
+			!ecs_iter_is_true(&mut it)
+		}
+	}
+
+	/// Iterates the index-th of count slices of the matched entities, each table is
+	/// divided over the slices. Meant for spreading one iteration over several jobs.
+	pub fn iter_worker<F: FnMut(&Iter)>(&self, index: i32, count: i32, mut func: F) {
+		assert!(index >= 0 && index < count, "Worker index out of range!");
+		unsafe {
+			defer_iter(self.world, || {
+				// flecs advances the query iterator through the chained one
+				let mut it = ecs_query_iter(self.world, self.query);
+				let mut worker_it = ecs_worker_iter(&mut it as *mut ecs_iter_t, index, count);
+				while ecs_worker_next(&mut worker_it) {
+					let iter = Iter::new(&mut worker_it);
+					func(&iter);
+				}
+			});
+		}
+	}
+
+	/// Iterates at most limit entities, skipping the first offset matched entities
+	pub fn iter_page<F: FnMut(&Iter)>(&self, offset: i32, limit: i32, mut func: F) {
+		unsafe {
+			defer_iter(self.world, || {
+				// flecs advances the query iterator through the chained one
+				let mut it = ecs_query_iter(self.world, self.query);
+				let mut page_it = ecs_page_iter(&mut it as *mut ecs_iter_t, offset, limit);
+				while ecs_page_next(&mut page_it) {
+					let iter = Iter::new(&mut page_it);
+					func(&iter);
+				}
+			});
+		}
+	}
+
+	/// Returns true if a matched table was modified or tables were matched/unmatched
+	/// since the last iteration of the query. Iterating a query with write access
+	/// marks its tables as modified, so this is mostly useful for read only queries.
+	pub fn changed(&self) -> bool {
+		unsafe { ecs_query_changed(self.query, std::ptr::null()) }
+	}
+
+	/// Only iterates the tables in group, the query must have been built with group_by
+	pub fn iter_group<F: FnMut(&Iter)>(&self, group: u64, mut func: F) {
+		assert!(!self.group_by.is_null(), "Query is not grouped!");
+		let group_by = unsafe { &*self.group_by };
+		unsafe {
+			defer_iter(self.world, || {
+				let mut it = ecs_query_iter(self.world, self.query);
+				while ecs_query_next(&mut it) {
+					if group_by(table_type(it.table)) != group {
+						continue;
+					}
+					let iter = Iter::new(&mut it);
+					func(&iter);
+				}
+			});
+		}
+	}
+}
+
+// Shows the terms of the query in the query language, e.g. "[in] Position, !Frozen"
+impl std::fmt::Display for Query {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let expr = unsafe { flecs_to_owned_string(ecs_query_str(self.query)) };
+		write!(f, "{}", expr)
+	}
 }
 
 pub struct QueryBuilder<'w> {
 	world: &'w World,
 	desc: ecs_query_desc_t,
+	expr_temp: String,
 	next_term_index: usize,
 }
 
@@ -70,6 +212,11 @@ impl<'w> TermBuilder for QueryBuilder<'w> {
         &mut self.desc.filter.terms[self.next_term_index]
     }
 
+    fn last_term(&mut self) -> &mut ecs_term_t {
+        assert!(self.next_term_index > 0, "No term to modify, add one first!");
+        &mut self.desc.filter.terms[self.next_term_index - 1]
+    }
+
     fn next_term(&mut self) {
         self.next_term_index += 1;
     }
@@ -80,15 +227,158 @@ impl<'w> QueryBuilder<'w> {
 		Self { 
 			world,
 			desc: unsafe { MaybeUninit::zeroed().assume_init() },
+			expr_temp: "".to_owned(),
 			next_term_index: 0
 		}
 	}
 
+	/// Adds the terms of a query language expression, e.g. "Position, !Velocity". The
+	/// fields of these terms are accessed with Iter::field_dynamic.
+	pub fn expr(mut self, expr: &str) -> Self {
+		self.expr_temp = expr.to_owned();
+		self
+	}
+
+	/// Sorts the query results by component T. flecs only sorts again when tables were
+	/// changed, e.g. after entities were added or T was modified.
+	///
+	/// flecs doesn't pass a context to comparators, so cmp must be a function item or a
+	/// closure that captures nothing. Anything else, including fn pointers, fails to compile:
+	///
+	/// ```compile_fail
+	/// use flecs::*;
+	///
+	/// struct Depth { value: i32 }
+	///
+	/// let mut world = World::new();
+	/// world.component::<Depth>();
+	/// let sign = -1;
+	/// world.query().term::<Depth>().order_by(move |_, a: &Depth, _, b: &Depth| sign * (a.value - b.value));
+	/// ```
+	pub fn order_by<T, F>(mut self, _cmp: F) -> Self
+	where
+		T: Component,
+		F: Fn(EntityId, &T, EntityId, &T) -> i32 + Copy + 'static,
+	{
+		let () = Stateless::<F>::ASSERT;
+		let world_raw = self.world.raw();
+		self.desc.order_by_component = WorldInfoCache::get_component_id_for_type::<T>(world_raw)
+			.expect("Component type not registered!");
+		self.desc.order_by = Some(order_by_trampoline::<T, F>);
+		self
+	}
+
+	/// Sorts the query results by entity id only, without a component. Like order_by,
+	/// cmp must be a function item or a closure that captures nothing.
+	pub fn order_by_entity<F>(mut self, _cmp: F) -> Self
+	where
+		F: Fn(EntityId, EntityId) -> i32 + Copy + 'static,
+	{
+		let () = Stateless::<F>::ASSERT;
+		self.desc.order_by_component = 0;
+		self.desc.order_by = Some(order_by_entity_trampoline::<F>);
+		self
+	}
+
+	/// Iterates the query results in ascending entity id order, which is the creation
+	/// order for entities whose ids weren't recycled.
+	pub fn ordered(self) -> Self {
+		self.order_by_entity(|e1: EntityId, e2: EntityId| (e1 > e2) as i32 - (e1 < e2) as i32)
+	}
+
+	/// Groups the query results by the target of relation, e.g. (InChunk, *).
+	/// Tables without the relation end up in group 0.
+	pub fn group_by<R: AsEcsId>(self, relation: R) -> Self {
+		let relation = relation.id();
+		self.group_by_fn(move |ids| {
+			ids.iter()
+				.find(|&&id| is_pair(id) && pair_first(id) == relation)
+				.map_or(0, |&id| pair_second(id))
+		})
+	}
+
+	/// Groups the query results by the group id the function returns for the
+	/// type (the component ids) of each table. Use Query::iter_group to iterate a group.
+	pub fn group_by_fn(mut self, group_by: impl Fn(&[EntityId]) -> u64 + 'static) -> Self {
+		if let Some(ctx_free) = self.desc.group_by_ctx_free {
+			unsafe { ctx_free(self.desc.group_by_ctx) };
+		}
+		let group_by: GroupByFn = Box::new(group_by);
+		self.desc.group_by = Some(group_by_trampoline);
+		self.desc.group_by_ctx = Box::into_raw(Box::new(group_by)) as *mut c_void;
+		self.desc.group_by_ctx_free = Some(free_group_by);
+		self
+	}
+
+	/// Panics if the query is invalid, see try_build
 	pub fn build(self) -> Query {
+		self.try_build().unwrap()
+	}
+
+	pub fn try_build(mut self) -> Result<Query, FilterError> {
+		add_match_flag_terms(&mut self.desc.filter, self.next_term_index, &mut self.expr_temp);
+		let expr_c_str = std::ffi::CString::new(self.expr_temp.as_str()).unwrap();
+		if !self.expr_temp.is_empty() {
+			self.desc.filter.expr = expr_c_str.as_ptr();
+		}
 		let query = unsafe { ecs_query_init(self.world.raw(), &self.desc) };
-		Query { 
+		if query.is_null() {
+			if let Some(ctx_free) = self.desc.group_by_ctx_free {
+				unsafe { ctx_free(self.desc.group_by_ctx) };
+			}
+			return Err(FilterError { expr: self.expr_temp });
+		}
+		// creates the change monitors, so changed() tracks changes from the start
+		unsafe { ecs_query_changed(query, std::ptr::null()) };
+		Ok(Query { 
 			world: self.world.raw(), 
-			query 
+			query,
+			group_by: self.desc.group_by_ctx as *const GroupByFn,
+		})
+	}
+}
+
+// Comparators are called without a context, so only zero sized function types can be
+// recreated in the trampoline. Evaluating ASSERT fails the build for any other type.
+struct Stateless<F>(std::marker::PhantomData<F>);
+
+impl<F> Stateless<F> {
+	const ASSERT: () = assert!(std::mem::size_of::<F>() == 0, "order_by comparators must be function items or closures that capture nothing");
+}
+
+unsafe extern "C" fn order_by_trampoline<T, F>(e1: ecs_entity_t, ptr1: *const c_void, e2: ecs_entity_t, ptr2: *const c_void) -> i32
+where
+	T: Component,
+	F: Fn(EntityId, &T, EntityId, &T) -> i32,
+{
+	// F is zero sized (see Stateless), so any aligned pointer holds a valid value
+	let cmp = std::ptr::NonNull::<F>::dangling().as_ptr().read();
+	cmp(e1, &*(ptr1 as *const T), e2, &*(ptr2 as *const T))
+}
+
+unsafe extern "C" fn order_by_entity_trampoline<F>(e1: ecs_entity_t, _ptr1: *const c_void, e2: ecs_entity_t, _ptr2: *const c_void) -> i32
+where
+	F: Fn(EntityId, EntityId) -> i32,
+{
+	let cmp = std::ptr::NonNull::<F>::dangling().as_ptr().read();
+	cmp(e1, e2)
+}
+
+fn table_type<'a>(table: *const ecs_table_t) -> &'a [EntityId] {
+	unsafe {
+		let ids = ecs_table_get_type(table);
+		if ids.is_null() || (*ids).count == 0 {
+			return &[];
 		}
+		std::slice::from_raw_parts((*ids).array, (*ids).count as usize)
 	}
 }
+
+unsafe extern "C" fn group_by_trampoline(_world: *mut ecs_world_t, table: *mut ecs_table_t, _id: ecs_id_t, ctx: *mut c_void) -> u64 {
+	let group_by = &*(ctx as *const GroupByFn);
+	group_by(table_type(table))
+}
+
+unsafe extern "C" fn free_group_by(ctx: *mut c_void) {
+	drop(Box::from_raw(ctx as *mut GroupByFn));
+}
@@ -0,0 +1,155 @@
+use std::ffi::CString;
+
+use crate::*;
+
+// Rules
+//
+// Rules are uncached queries that support variables, e.g. "(Likes, $person)". A variable
+// is bound to an entity per result and can be constrained before iterating, which makes
+// joins possible that filters and queries can't express. $This is the iterated entity.
+
+pub struct Rule {
+	world: *mut ecs_world_t,
+	rule: *mut ecs_rule_t,
+}
+
+impl Rule {
+	pub fn iter<F: FnMut(&Iter)>(&self, func: F) {
+		self.rule_iter().iter(func)
+	}
+
+	/// Starts an iteration whose variables can be constrained with RuleIter::set_var
+	pub fn rule_iter(&self) -> RuleIter<'_> {
+		RuleIter {
+			rule: self,
+			it: unsafe { ecs_rule_iter(self.world, self.rule) },
+			done: false,
+		}
+	}
+
+	/// Returns the id of a variable, e.g. "person" for $person
+	pub fn find_var(&self, name: &str) -> Option<i32> {
+		let name_c_str = CString::new(name).unwrap();
+		let var = unsafe { ecs_rule_find_var(self.rule, name_c_str.as_ptr()) };
+		if var < 0 {
+			return None;
+		}
+		Some(var)
+	}
+}
+
+// Shows the rule in the query language
+impl std::fmt::Display for Rule {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let expr = unsafe { flecs_to_owned_string(ecs_rule_str(self.rule)) };
+		write!(f, "{}", expr)
+	}
+}
+
+// Rules must be dropped before their world
+impl Drop for Rule {
+	fn drop(&mut self) {
+		unsafe { ecs_rule_fini(self.rule) };
+	}
+}
+
+pub struct RuleIter<'r> {
+	rule: &'r Rule,
+	it: ecs_iter_t,
+	done: bool,
+}
+
+impl<'r> RuleIter<'r> {
+	/// Constrains a variable to an entity, only results with that binding are returned
+	pub fn set_var<T: AsEcsId>(mut self, name: &str, entity: T) -> Self {
+		let var = self.rule.find_var(name).unwrap_or_else(|| panic!("Rule has no variable ${}", name));
+		unsafe { ecs_iter_set_var(&mut self.it, var, entity.id()) };
+		self
+	}
+
+	pub fn iter<F: FnMut(&Iter)>(mut self, mut func: F) {
+		// flecs cleans up the iterator once it returns false
+		self.done = true;
+		unsafe {
+			while ecs_rule_next(&mut self.it) {
+				let iter = Iter::new(&mut self.it);
+				func(&iter);
+			}
+		}
+	}
+}
+
+impl<'r> Drop for RuleIter<'r> {
+	fn drop(&mut self) {
+		if !self.done {
+			unsafe { ecs_iter_fini(&mut self.it) };
+		}
+	}
+}
+
+pub struct RuleBuilder<'w> {
+	world: &'w World,
+	desc: ecs_filter_desc_t,
+	expr_temp: String,
+	next_term_index: usize,
+}
+
+impl<'w> TermBuilder for RuleBuilder<'w> {
+    fn world(&mut self) -> *mut ecs_world_t {
+        self.world.raw()
+    }
+
+	fn filter_desc(&mut self) -> &mut ecs_filter_desc_t {
+        &mut self.desc
+	}
+
+    fn current_term(&mut self) -> &mut ecs_term_t {
+        &mut self.desc.terms[self.next_term_index]
+    }
+
+    fn last_term(&mut self) -> &mut ecs_term_t {
+        assert!(self.next_term_index > 0, "No term to modify, add one first!");
+        &mut self.desc.terms[self.next_term_index - 1]
+    }
+
+    fn next_term(&mut self) {
+        self.next_term_index += 1;
+    }
+}
+
+impl<'w> RuleBuilder<'w> {
+	pub fn new(world: &'w World) -> Self {
+		Self { 
+			world,
+			desc: unsafe { MaybeUninit::zeroed().assume_init() },
+			expr_temp: "".to_owned(),
+			next_term_index: 0
+		}
+	}
+
+	/// Adds the terms of a query language expression, variables are written as $name
+	pub fn expr(mut self, expr: &str) -> Self {
+		self.expr_temp = expr.to_owned();
+		self
+	}
+
+	/// Panics if the rule is invalid, see try_build
+	pub fn build(self) -> Rule {
+		self.try_build().unwrap()
+	}
+
+	pub fn try_build(mut self) -> Result<Rule, FilterError> {
+		let expr_c_str = CString::new(self.expr_temp.as_str()).unwrap();
+		if !self.expr_temp.is_empty() {
+			self.desc.expr = expr_c_str.as_ptr();
+		}
+		let rule = unsafe { ecs_rule_init(self.world.raw(), &self.desc) };
+		if rule.is_null() {
+			return Err(FilterError { expr: self.expr_temp });
+		}
+		Ok(Rule { 
+			world: self.world.raw(), 
+			rule 
+		})
+	}
+}
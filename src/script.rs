@@ -0,0 +1,55 @@
+use crate::*;
+
+// Plecs addon support
+//
+// Scripts create entities, prefabs and hierarchies in the flecs text format. Component
+// values are parsed with the reflection data of the component, see meta.rs.
+
+/// Error returned when a script fails to load, message holds the errors logged by the
+/// parser, which include the offending line of the script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+	pub name: String,
+	pub message: String,
+}
+
+impl std::fmt::Display for ScriptError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Failed to run script '{}': {}", self.name, self.message)
+	}
+}
+
+impl std::error::Error for ScriptError {}
+
+impl ScriptError {
+	fn nul(name: &str, err: &std::ffi::NulError) -> Self {
+		Self { name: name.to_owned(), message: format!("unexpected nul character at {}", err.nul_position()) }
+	}
+}
+
+impl World {
+	/// Runs a plecs script, name identifies the script in errors
+	pub fn run_script(&self, name: &str, code: &str) -> Result<(), ScriptError> {
+		let c_name = std::ffi::CString::new(name).map_err(|e| ScriptError::nul(name, &e))?;
+		let c_code = std::ffi::CString::new(code).map_err(|e| ScriptError::nul(name, &e))?;
+		let (result, errors) = log::capture_errors(|| unsafe {
+			ecs_plecs_from_str(self.raw(), c_name.as_ptr(), c_code.as_ptr())
+		});
+		if result != 0 {
+			return Err(ScriptError { name: name.to_owned(), message: errors });
+		}
+		Ok(())
+	}
+
+	/// Same as run_script, loading the script from a file
+	pub fn run_script_file(&self, path: &str) -> Result<(), ScriptError> {
+		let c_path = std::ffi::CString::new(path).map_err(|e| ScriptError::nul(path, &e))?;
+		let (result, errors) = log::capture_errors(|| unsafe {
+			ecs_plecs_from_file(self.raw(), c_path.as_ptr())
+		});
+		if result != 0 {
+			return Err(ScriptError { name: path.to_owned(), message: errors });
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,148 @@
+use crate::*;
+
+// Stats addon support
+//
+// Flecs keeps a ring buffer of ECS_STAT_WINDOW measurements for every metric. These wrappers
+// only expose the latest measurement: the running total for counters, the last value for gauges.
+
+fn gauge(metric: &ecs_metric_t, t: i32) -> f32 {
+	unsafe { metric.gauge.avg[t as usize] }
+}
+
+fn counter(metric: &ecs_metric_t, t: i32) -> f32 {
+	unsafe { metric.counter.value[t as usize] }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorldStats {
+	pub entity_count: u32,
+	pub component_count: u32,
+	pub table_count: u32,
+	pub query_count: u32,
+	pub observer_count: u32,
+	pub system_count: u32,
+	pub frame_count: u32,
+	pub fps: f32,
+	pub delta_time: f32,
+	pub world_time_total: f32,
+	pub frame_time_total: f32,
+	pub system_time_total: f32,
+	pub merge_time_total: f32,
+}
+
+impl WorldStats {
+	fn new(stats: &ecs_world_stats_t) -> Self {
+		let t = stats.t;
+		WorldStats {
+			entity_count: gauge(&stats.entity_count, t) as u32,
+			component_count: gauge(&stats.component_count, t) as u32,
+			table_count: gauge(&stats.table_count, t) as u32,
+			query_count: gauge(&stats.query_count, t) as u32,
+			observer_count: gauge(&stats.observer_count, t) as u32,
+			system_count: gauge(&stats.system_count, t) as u32,
+			frame_count: counter(&stats.frame_count_total, t) as u32,
+			fps: gauge(&stats.fps, t),
+			delta_time: gauge(&stats.delta_time, t),
+			world_time_total: counter(&stats.world_time_total, t),
+			frame_time_total: counter(&stats.frame_time_total, t),
+			system_time_total: counter(&stats.system_time_total, t),
+			merge_time_total: counter(&stats.merge_time_total, t),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SystemStats {
+	pub system: Entity,
+	/// Total time spent in the system, only measured after World::import_stats_module
+	pub time_spent: f32,
+	pub invoke_count: u32,
+	pub matched_entity_count: u32,
+	pub matched_table_count: u32,
+	pub active: bool,
+	pub enabled: bool,
+}
+
+impl SystemStats {
+	fn new(world: *mut ecs_world_t, system: ecs_entity_t, stats: &ecs_system_stats_t) -> Self {
+		let t = stats.query.t;
+		SystemStats {
+			system: Entity::new(world, system),
+			time_spent: counter(&stats.time_spent, t),
+			invoke_count: counter(&stats.invoke_count, t) as u32,
+			matched_entity_count: gauge(&stats.query.matched_entity_count, t) as u32,
+			matched_table_count: gauge(&stats.query.matched_table_count, t) as u32,
+			active: gauge(&stats.active, t) != 0.0,
+			enabled: gauge(&stats.enabled, t) != 0.0,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PipelineStats {
+	pub system_count: u32,
+	/// Systems which matched any entities
+	pub active_system_count: u32,
+	/// Stats of all systems in the pipeline, ordered by system id
+	pub systems: Vec<SystemStats>,
+}
+
+impl World {
+	/// Imports the flecs.monitor module, which enables measuring frame & system time and
+	/// periodically collects stats for the REST explorer.
+	pub fn import_stats_module(&self) {
+		let module_name = std::ffi::CString::new("FlecsMonitor").unwrap();
+		unsafe { ecs_import_c(self.raw(), Some(FlecsMonitorImport), module_name.as_ptr()) };
+	}
+
+	pub fn world_stats(&self) -> WorldStats {
+		unsafe {
+			let mut stats: ecs_world_stats_t = MaybeUninit::zeroed().assume_init();
+			ecs_world_stats_get(self.raw(), &mut stats);
+			WorldStats::new(&stats)
+		}
+	}
+
+	/// Returns None if system is not a system
+	pub fn system_stats(&self, system: Entity) -> Option<SystemStats> {
+		unsafe {
+			let mut stats: ecs_system_stats_t = MaybeUninit::zeroed().assume_init();
+			if !ecs_system_stats_get(self.raw(), system.raw(), &mut stats) {
+				return None;
+			}
+			Some(SystemStats::new(self.raw(), system.raw(), &stats))
+		}
+	}
+
+	/// Returns None if pipeline is not a pipeline or doesn't have any systems
+	pub fn pipeline_stats(&self, pipeline: Entity) -> Option<PipelineStats> {
+		unsafe {
+			let mut stats: ecs_pipeline_stats_t = MaybeUninit::zeroed().assume_init();
+			if !ecs_pipeline_stats_get(self.raw(), pipeline.raw(), &mut stats) {
+				ecs_pipeline_stats_fini(&mut stats);
+				return None;
+			}
+
+			let mut systems = Vec::new();
+			let mut it = ecs_map_iter(&stats.system_stats);
+			let mut system: ecs_map_key_t = 0;
+			let elem_size = std::mem::size_of::<ecs_system_stats_t>() as ecs_size_t;
+			loop {
+				let system_stats = _ecs_map_next(&mut it, elem_size, &mut system) as *const ecs_system_stats_t;
+				if system_stats.is_null() {
+					break;
+				}
+				systems.push(SystemStats::new(self.raw(), system, &*system_stats));
+			}
+			systems.sort_by_key(|s| s.system.raw());
+
+			let result = PipelineStats {
+				system_count: systems.len() as u32,
+				active_system_count: systems.iter().filter(|s| s.active).count() as u32,
+				systems,
+			};
+			ecs_pipeline_stats_fini(&mut stats);
+			Some(result)
+		}
+	}
+}
@@ -1,4 +1,4 @@
-use std::{ffi::c_void};
+use std::{any::TypeId, ffi::c_void};
 
 use crate::*;
 use crate::cache::WorldInfoCache;
@@ -21,9 +21,23 @@ impl System {
 	}
 
     pub fn interval(&self, interval: f32) {
+        self.set_interval(interval);
+    }
+
+	/// Changes how often (in seconds) the system runs. An interval of 0 runs it every frame.
+    pub fn set_interval(&self, interval: f32) {
         unsafe { ecs_set_interval(self.world, self.id, interval) };
     }
 
+    pub fn get_interval(&self) -> f32 {
+        unsafe { ecs_get_interval(self.world, self.id) }
+    }
+
+	/// Changes the system to run once every `rate` frames.
+    pub fn set_rate(&self, rate: i32) {
+        unsafe { ecs_set_rate(self.world, self.id, rate, 0) };
+    }
+
     pub fn enable(&self) {
         unsafe { ecs_enable(self.world, self.id, true) };
     }
@@ -32,6 +46,16 @@ impl System {
         unsafe { ecs_enable(self.world, self.id, false) };
     }
 
+	/// Systems are disabled by adding the EcsDisabled tag, which the pipeline skips
+    pub fn is_enabled(&self) -> bool {
+        unsafe { !ecs_has_id(self.world, self.id, EcsDisabled) }
+    }
+
+	/// Deletes the system entity, which also drops the closure the system was built with
+    pub fn destruct(self) {
+        unsafe { ecs_delete(self.world, self.id) };
+    }
+
 	pub fn run(&self, delta_time: f32) {
 		let param: *mut ::std::os::raw::c_void = std::ptr::null_mut();
 
@@ -46,6 +70,54 @@ impl System {
 	}
 }
 
+/// Describes a system of the world, see World::systems
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemInfo {
+	pub entity: Entity,
+	pub name: String,
+	/// None for systems that only run when called manually
+	pub phase: Option<Entity>,
+	/// The terms in the query language, e.g. "Position, [in] Velocity"
+	pub query_str: String,
+	pub enabled: bool,
+}
+
+impl World {
+	/// Returns the systems of the application, disabled ones included. Systems of the
+	/// flecs modules (e.g. the timer module) are skipped. Use World::system_stats for
+	/// how long the systems took.
+	pub fn systems(&self) -> impl Iterator<Item = SystemInfo> {
+		let world = self.raw();
+		let mut desc: ecs_filter_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		desc.terms[0].id = unsafe { EcsSystem };
+		desc.flags = EcsFilterMatchDisabled;
+
+		let mut systems = Vec::new();
+		unsafe {
+			let filter = ecs_filter_init(world, &desc);
+			let mut it = ecs_filter_iter(world, filter);
+			while ecs_filter_next(&mut it) {
+				for i in 0..it.count as isize {
+					let entity = Entity::new(world, *it.entities.offset(i));
+					if is_flecs_builtin(world, entity.raw()) {
+						continue;
+					}
+					let query = ecs_system_get_query(world, entity.raw());
+					systems.push(SystemInfo {
+						entity,
+						name: entity.name().to_owned(),
+						phase: entity.target(EcsDependsOn, 0),
+						query_str: flecs_to_owned_string(ecs_query_str(query)),
+						enabled: !entity.has_id(EcsDisabled),
+					});
+				}
+			}
+			ecs_filter_fini(filter);
+		}
+		systems.into_iter()
+	}
+}
+
 pub struct SystemBuilder<'w> {
 	world: &'w World,
 	desc: ecs_system_desc_t,
@@ -55,6 +127,7 @@ pub struct SystemBuilder<'w> {
 	expr_temp: String,	
 
 	next_term_index: usize,
+	phase: ecs_entity_t,
 }
 
 impl<'w> TermBuilder for SystemBuilder<'w> {
@@ -70,6 +143,11 @@ impl<'w> TermBuilder for SystemBuilder<'w> {
         &mut self.desc.query.filter.terms[self.next_term_index]
     }
 
+    fn last_term(&mut self) -> &mut ecs_term_t {
+        assert!(self.next_term_index > 0, "No term to modify, add one first!");
+        &mut self.desc.query.filter.terms[self.next_term_index - 1]
+    }
+
     fn next_term(&mut self) {
         self.next_term_index += 1;
     }
@@ -86,6 +164,7 @@ impl<'w> SystemBuilder<'w> {
 			name_temp: "".to_owned(),
 			expr_temp: "".to_owned(),
 			next_term_index: 0,
+			phase: unsafe { EcsOnUpdate },
 		}
 	}
 
@@ -99,11 +178,52 @@ impl<'w> SystemBuilder<'w> {
         self
     }
 
+	/// Runs the system every `interval` seconds. The time accumulated since the
+	/// system last ran is available through `Iter::delta_system_time`.
     pub fn interval(mut self, interval: f32) -> Self {
         self.desc.interval = interval;
 		self
     }
 
+	/// Runs the system once every `rate` frames.
+    pub fn rate(mut self, rate: i32) -> Self {
+        self.desc.rate = rate;
+		self
+    }
+
+	/// Only runs the system on the frames where the tick source (e.g. a Timer) fires
+    pub fn tick_source<T: AsEcsId>(mut self, tick_source: T) -> Self {
+        self.desc.tick_source = tick_source.id();
+		self
+    }
+
+	/// Runs the system outside of the readonly (staged) part of the frame, so entities it creates
+	/// or deletes are visible right away. The pipeline merges all commands queued by earlier
+	/// systems before it runs, and systems after it in the frame see its changes directly.
+	/// Such a system cannot run on multiple threads.
+    pub fn no_readonly(mut self, no_readonly: bool) -> Self {
+        self.desc.no_staging = no_readonly;
+		self
+    }
+
+	/// Sets the pipeline phase the system runs in, EcsOnUpdate by default.
+	/// A phase of 0 creates a system which only runs when called manually.
+	/// Systems within a phase run in declaration order, World::create_phase can be
+	/// used to insert extra phases when that isn't enough.
+    pub fn kind<T: AsEcsId>(mut self, phase: T) -> Self {
+        self.phase = phase.id();
+        self
+    }
+
+    pub fn on_load(self) -> Self { self.kind(unsafe { EcsOnLoad }) }
+    pub fn post_load(self) -> Self { self.kind(unsafe { EcsPostLoad }) }
+    pub fn pre_update(self) -> Self { self.kind(unsafe { EcsPreUpdate }) }
+    pub fn on_update(self) -> Self { self.kind(unsafe { EcsOnUpdate }) }
+    pub fn on_validate(self) -> Self { self.kind(unsafe { EcsOnValidate }) }
+    pub fn post_update(self) -> Self { self.kind(unsafe { EcsPostUpdate }) }
+    pub fn pre_store(self) -> Self { self.kind(unsafe { EcsPreStore }) }
+    pub fn on_store(self) -> Self { self.kind(unsafe { EcsOnStore }) }
+
 	/** Associate system with entity */
 	// TODO - Don't create an entity then in this case (v3.0 change)
 	// pub fn entity(mut self, entity: Entity) -> Self {
@@ -111,12 +231,34 @@ impl<'w> SystemBuilder<'w> {
 	// 	self
 	// }
 	
-    /** Set system context */
-    pub(crate) fn ctx(mut self, ctx: *mut ::std::os::raw::c_void) -> Self {
-        self.desc.ctx = ctx;
+	/// Gives the system ownership of a value, accessible from an iter_mut callback through
+	/// Iter::ctx. The value is dropped together with the system.
+    pub fn ctx<C: 'static>(mut self, value: C) -> Self {
+		if let Some(ctx_free) = self.desc.ctx_free {
+			unsafe { ctx_free(self.desc.ctx) };
+		}
+		let ctx = SystemCtx { type_id: TypeId::of::<C>(), value };
+        self.desc.ctx = Box::into_raw(Box::new(ctx)) as *mut c_void;
+		self.desc.ctx_free = Some(free_ctx::<C>);
         self
     }	
 
+	// The closure is boxed and owned by the system, flecs frees it with the system. It is
+	// called long after the builder is gone, hence it can't borrow from the stack.
+	fn set_callback<F: FnMut(*mut ecs_iter_t) + 'static>(&mut self, closure: F) {
+		self.desc.callback = Some(get_trampoline(&closure));
+		self.desc.binding_ctx = Box::into_raw(Box::new(closure)) as *mut c_void;
+		self.desc.binding_ctx_free = Some(free_closure::<F>);
+	}
+
+	// Same as set_callback, for a closure the worker threads call at the same time
+	fn set_callback_mt<F: Fn(*mut ecs_iter_t) + Send + Sync + 'static>(&mut self, closure: F) {
+		self.desc.multi_threaded = true;
+		self.desc.callback = Some(trampoline_mt::<F>);
+		self.desc.binding_ctx = Box::into_raw(Box::new(closure)) as *mut c_void;
+		self.desc.binding_ctx_free = Some(free_closure::<F>);
+	}
+
 	// Build APIs, the 2 variants call the internal build()
 	fn build(&mut self) -> ecs_entity_t {
 		let world = self.world.raw();
@@ -133,12 +275,16 @@ impl<'w> SystemBuilder<'w> {
 			entity_desc.name = std::ptr::null()
 		}
 
-		// We have to add this pair so that the system is part of standard progress stage
-		entity_desc.add[0] = unsafe { ecs_pair(EcsDependsOn, EcsOnUpdate) };
+		// The DependsOn pair makes the builtin pipeline schedule the system in its phase
+		if self.phase != 0 {
+			entity_desc.add[0] = unsafe { ecs_pair(EcsDependsOn, self.phase) };
+			entity_desc.add[1] = self.phase;
+		}
 
 		// create a system entity
 		self.desc.entity = unsafe { ecs_entity_init(world, &entity_desc) };
 
+		add_match_flag_terms(&mut self.desc.query.filter, self.next_term_index, &mut self.expr_temp);
 		let expr_c_str = std::ffi::CString::new(self.expr_temp.as_str()).unwrap();
 		if self.expr_temp.len() > 0 {
 			self.desc.query.filter.expr = expr_c_str.as_ptr() as *const i8;
@@ -188,59 +334,118 @@ impl<'w> SystemBuilder<'w> {
         e
 	}
 
-	pub fn each<G: ComponentGroup<'w>>(mut self, mut cb: impl FnMut(Entity, G::RefTuple)) -> System {
-		let mut closure = |it: *mut ecs_iter_t| {
+	/// Builds a system which gets called per entity with references to the components of G,
+	/// the first terms are marked as read only unless annotated otherwise
+	pub fn each<G: ComponentGroup<'w>>(mut self, mut cb: impl FnMut(Entity, G::RefTuple) + 'static) -> System {
+		set_default_inout(&mut self.desc.query.filter.terms[..G::COUNT], ecs_inout_kind_t_EcsIn);
+		let closure = move |it: *mut ecs_iter_t| {
 			unsafe {
 				let it = &(*it);
-				for i in 0..it.count {
-					let eid = it.entities.offset(i as isize).as_ref().unwrap();
-					let e = Entity::new(it.world, *eid);
-					let rt = G::iter_as_ref_tuple(&it, i as isize);
-					cb(e, rt);
-				}
+				each_ref_tuple::<G>(it, &mut cb);
 			}
 		};
-		let trampoline = get_trampoline(&closure);
+		self.set_callback(closure);
+
+		let e = Self::build(&mut self);
+		System::new(self.world.raw(), e)		
+	}
 
-		self.desc.callback = Some(trampoline);
-		self.desc.binding_ctx = &mut closure as *mut _ as *mut c_void;
+	/// Builds a system which gets called per entity with the elements of G, e.g.
+	/// (&mut Position, &Velocity, Option<&Target>). The elements apply to the first terms,
+	/// which are added when missing: Option makes a term optional, and terms are marked
+	/// as In for & and InOut for &mut unless annotated otherwise. The terms of an expr are
+	/// left as they are.
+	pub fn for_each<G: EachGroup<'w>>(mut self, mut cb: impl FnMut(Entity, G::Item) + 'static) -> System {
+		self.apply_each_terms::<G>();
+		let closure = move |it: *mut ecs_iter_t| {
+			unsafe {
+				let it = &(*it);
+				each_group::<G>(it, &mut cb);
+			}
+		};
+		self.set_callback(closure);
 
 		let e = Self::build(&mut self);
 		System::new(self.world.raw(), e)		
 	}
 
-	pub fn each_mut<G: ComponentGroup<'w>>(mut self, mut cb: impl FnMut(Entity, G::MutRefTuple)) -> System {
-		let mut closure = |it: *mut ecs_iter_t| {
+	/// Same as for_each, for a system whose matched entities are split over the worker threads,
+	/// see World::set_threads. The workers call cb at the same time, hence it must be Fn
+	/// and thread safe.
+	pub fn each_mt<G: EachGroup<'w>>(mut self, cb: impl Fn(Entity, G::Item) + Send + Sync + 'static) -> System {
+		self.apply_each_terms::<G>();
+		let closure = move |it: *mut ecs_iter_t| {
 			unsafe {
 				let it = &(*it);
-				for i in 0..it.count {
-					let eid = it.entities.offset(i as isize).as_ref().unwrap();
-					let e = Entity::new(it.world, *eid);
-					let rt = G::iter_as_mut_tuple(&it, i as isize);
-					cb(e, rt);
-				}
+				each_group::<G>(it, &mut |e, item| cb(e, item));
 			}
 		};
-		let trampoline = get_trampoline(&closure);
+		self.set_callback_mt(closure);
+
+		let e = Self::build(&mut self);
+		System::new(self.world.raw(), e)		
+	}
 
-		self.desc.callback = Some(trampoline);
-		self.desc.binding_ctx = &mut closure as *mut _ as *mut c_void;
+	fn apply_each_terms<G: EachGroup<'w>>(&mut self) {
+		if self.expr_temp.is_empty() {
+			G::apply_terms(self.world.raw(), &mut self.desc.query.filter.terms[..G::COUNT]);
+			self.next_term_index = self.next_term_index.max(G::COUNT);
+		}
+	}
+
+	/// Like each, with mutable references. The first terms are marked as read & write.
+	#[deprecated(note = "use for_each with &mut elements, e.g. for_each::<(&mut A, &mut B)>")]
+	pub fn each_mut<G: ComponentGroup<'w>>(mut self, mut cb: impl FnMut(Entity, G::MutRefTuple) + 'static) -> System {
+		set_default_inout(&mut self.desc.query.filter.terms[..G::COUNT], ecs_inout_kind_t_EcsInOut);
+		let closure = move |it: *mut ecs_iter_t| {
+			unsafe {
+				let it = &(*it);
+				each_mut_tuple::<G>(it, &mut cb);
+			}
+		};
+		self.set_callback(closure);
 
 		let e = Self::build(&mut self);
 		System::new(self.world.raw(), e)		
 	}
 
-	pub fn iter<F: FnMut(&Iter)>(mut self, mut func: F) -> System {
+	/// Builds a system which gets called once per matched table with the whole Iter, fields
+	/// are read as columns with Iter::field. Faster than each for large amounts of entities.
+	pub fn iter<F: FnMut(&Iter) + 'static>(mut self, mut func: F) -> System {
 		// we have to wrap the passed in function in a trampoline
 		// so that we can access it again within the C callback handler
-		let mut closure = |it: *mut ecs_iter_t| {
+		let closure = move |it: *mut ecs_iter_t| {
 			let iter = Iter::new(it);
 			func(&iter);
 		};
-		let trampoline = get_trampoline(&closure);
+		self.set_callback(closure);
 
-		self.desc.callback = Some(trampoline);
-		self.desc.binding_ctx = &mut closure as *mut _ as *mut c_void;
+		let e = Self::build(&mut self);
+		System::new(self.world.raw(), e)
+	}
+
+	/// Same as iter, with a mutable Iter for the accessors which hand out mutable
+	/// references, such as Iter::field_slice_mut and Iter::ctx
+	pub fn iter_mut<F: FnMut(&mut Iter) + 'static>(mut self, mut func: F) -> System {
+		let closure = move |it: *mut ecs_iter_t| {
+			let mut iter = Iter::new(it);
+			func(&mut iter);
+		};
+		self.set_callback(closure);
+
+		let e = Self::build(&mut self);
+		System::new(self.world.raw(), e)
+	}
+
+	/// Same as iter_mut, for a system whose matched entities are split over the worker threads,
+	/// see World::set_threads. The workers call func at the same time, hence it must be Fn
+	/// and thread safe.
+	pub fn iter_mt<F: Fn(&mut Iter) + Send + Sync + 'static>(mut self, func: F) -> System {
+		let closure = move |it: *mut ecs_iter_t| {
+			let mut iter = Iter::new(it);
+			func(&mut iter);
+		};
+		self.set_callback_mt(closure);
 
 		let e = Self::build(&mut self);
 		System::new(self.world.raw(), e)
@@ -281,6 +486,71 @@ impl Iter {
 		unsafe { (*self.it).count as usize }
 	}
 
+	/// Returns the context passed to SystemBuilder::ctx, or None if it isn't of type C
+	pub fn ctx<C: 'static>(&mut self) -> Option<&mut C> {
+		unsafe {
+			let ctx = (*self.it).ctx as *mut SystemCtx<C>;
+			if ctx.is_null() || (*ctx).type_id != TypeId::of::<C>() {
+				return None;
+			}
+			Some(&mut (*ctx).value)
+		}
+	}
+
+	/// The ids of the iterated entities, the entity of element i is at index i
+	pub fn entities(&self) -> &[EntityId] {
+		unsafe {
+			let entities = (*self.it).entities;
+			if entities.is_null() {
+				return &[];
+			}
+			std::slice::from_raw_parts(entities, self.count())
+		}
+	}
+
+	/// The table of the current result, for direct access to its columns. See Table
+	/// for when the column slices are invalidated.
+	pub fn table(&mut self) -> Table<'_> {
+		unsafe {
+			let it = &*self.it;
+			let entities = if it.entities.is_null() { it.entities } else { it.entities.offset(-(it.offset as isize)) };
+			Table::new(it.real_world, it.table, entities)
+		}
+	}
+
+	/// The number of tables a query matches. Only set when iterating a query.
+	pub fn table_count(&self) -> i32 {
+		unsafe { (*self.it).table_count }
+	}
+
+	/// The index of the first entity of the current result, counted from the start
+	/// of the iteration
+	pub fn frame_offset(&self) -> i32 {
+		unsafe { (*self.it).frame_offset }
+	}
+
+	/// Returns the entity a rule variable is bound to in the current result,
+	/// e.g. "person" for $person
+	pub fn get_var(&self, name: &str) -> Entity {
+		unsafe {
+			let names = (*self.it).variable_names;
+			let var = (0..(*self.it).variable_count)
+				.find(|&i| flecs_to_rust_str(*names.offset(i as isize)) == name)
+				.unwrap_or_else(|| panic!("Iterator has no variable ${}", name));
+			Entity::new((*self.it).world, ecs_iter_get_var(self.it, var))
+		}
+	}
+
+	/// The event that triggered an observer
+	pub fn event_id(&self) -> RawId {
+		RawId::new(unsafe { (*self.it).event }).expect("Iterator has no event!")
+	}
+
+	#[deprecated(note = "use Iter::event_id, which returns a RawId")]
+	pub fn event(&self) -> EntityId {
+		unsafe { (*self.it).event }
+	}
+
 	pub fn delta_time(&self) -> f32 {
 		unsafe { (*self.it).delta_time }
 	}
@@ -298,26 +568,140 @@ impl Iter {
 		Entity::new(unsafe { (*self.it).world }, *entity)
     }
 
+	/// Returns the singleton T matched by a term_singleton term
+	pub fn singleton<T: Component>(&self) -> Option<&T> {
+		self.singleton_field::<T>().map(|index| unsafe {
+			&*(ecs_field_w_size(self.it, std::mem::size_of::<T>() as size_t, index) as *const T)
+		})
+	}
+
+	/// Returns the singleton T matched by a term_singleton_mut term
+	pub fn singleton_mut<T: Component>(&mut self) -> Option<&mut T> {
+		self.singleton_field::<T>().map(|index| unsafe {
+			assert!(!ecs_field_is_readonly(self.it, index), "singleton term is readonly, use term_singleton_mut");
+			&mut *(ecs_field_w_size(self.it, std::mem::size_of::<T>() as size_t, index) as *mut T)
+		})
+	}
+
+	fn singleton_field<T: Component>(&self) -> Option<i32> {
+		let world = unsafe { (*self.it).real_world };
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(world)?;
+		let field_count = unsafe { (*self.it).term_count };
+		(1..=field_count).find(|&index| unsafe {
+			ecs_field_id(self.it, index) == comp_id && ecs_field_src(self.it, index) == comp_id
+		})
+	}
+
+	/// Returns false if the field is shared (e.g. inherited from a prefab) rather than
+	/// owned by the iterated entities, in which case it holds a single value
+	pub fn is_self(&self, index: i32) -> bool {
+		unsafe { ecs_field_is_self(self.it, index) }
+	}
+
+	/// Marks the current table as not modified by this iteration, even though the
+	/// query has write access to it. Only valid when iterating a query.
+	pub fn skip(&self) {
+		unsafe { ecs_query_skip(self.it) }
+	}
+
+	/// Returns true if the current table was modified since the query last iterated it,
+	/// so work on unchanged tables can be skipped. Only read terms are tracked, pair it
+	/// with skip() in the writing system. Always true when not iterating a query (filters, rules).
+	pub fn changed(&self) -> bool {
+		unsafe {
+			// Page and worker iterators wrap the query iterator
+			let query_next: unsafe extern "C" fn(*mut ecs_iter_t) -> bool = ecs_query_next;
+			let mut it = self.it as *const ecs_iter_t;
+			while !it.is_null() && (*it).next.map(|f| f as usize) != Some(query_next as usize) {
+				it = (*it).chain_it;
+			}
+			if it.is_null() {
+				return true;
+			}
+			ecs_query_changed(std::ptr::null_mut(), it)
+		}
+	}
+
     pub fn field<A: Component>(&self, index: i32) -> Column<A> {
         Self::get_field::<A>(self, index)
     }
 
+	/// Returns the id matched by a field, e.g. which component of an Or chain matched
+	pub fn id(&self, index: i32) -> RawId {
+		RawId::new(unsafe { ecs_field_id(self.it, index) }).expect("Field has no id!")
+	}
+
+	/// Returns the concrete pair a wildcard field matched for the current result, e.g.
+	/// (Likes, Alice) for (Likes, *), or None if the field is not a pair.
+	pub fn pair(&self, index: i32) -> Option<PairId> {
+		self.id(index).as_pair()
+	}
+
+	#[deprecated(note = "use Iter::id, which returns a RawId")]
+	pub fn field_id(&self, index: i32) -> EntityId {
+		unsafe { ecs_field_id(self.it, index) }
+	}
+
+	#[deprecated(note = "use Iter::pair, which returns a PairId")]
+	pub fn pair_id(&self, index: i32) -> EntityId {
+		unsafe { ecs_field_id(self.it, index) }
+	}
+
+	/// Returns the field of an optional term, or None if the current table doesn't have it
+	pub fn field_opt<A: Component>(&self, index: i32) -> Option<Column<A>> {
+		if !unsafe { ecs_field_is_set(self.it, index) } {
+			return None;
+		}
+		Some(Self::get_field::<A>(self, index))
+	}
+
     fn get_field<T: Component>(&self, index: i32) -> Column<T> {
-			// validate that types match. could avoid this in Release builds perhaps to get max perf
+		self.try_field::<T>(index).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+	/// Returns the field as a slice of it.count() components. A shared field (see is_self)
+	/// holds a single value which applies to all entities, its slice has length 1.
+	pub fn field_slice<T: Component>(&self, index: i32) -> &[T] {
+		let column = self.get_field::<T>(index);
+		unsafe { std::slice::from_raw_parts(column.as_ptr(), column.count) }
+	}
+
+	/// Same as field_slice, mutable. Fields read alongside can be accessed with field,
+	/// which doesn't borrow the Iter.
+	pub fn field_slice_mut<T: Component>(&mut self, index: i32) -> &mut [T] {
+		let column = self.get_field::<T>(index);
+		unsafe { std::slice::from_raw_parts_mut(column.as_ptr(), column.count) }
+	}
+
+	/// Same as field, returns an error instead of panicking if the index is out of range,
+	/// the field has no data, or its type isn't T
+	pub fn try_field<T: Component>(&self, index: i32) -> Result<Column<T>, FieldError> {
+			let field_count = unsafe { (*self.it).term_count };
+			if index < 1 || index > field_count {
+				return Err(FieldError::IndexOutOfRange { index, field_count });
+			}
+			if !unsafe { ecs_field_is_set(self.it, index) } {
+				return Err(FieldError::NoData { index });
+			}
+
+			// the field may also be a pair with T as data type, e.g. (T, Target)
 			let field_id = unsafe { ecs_field_id(self.it, index) };
 			let world = unsafe { (*self.it).real_world };	// must use real to get component infos
-			let comp_id = WorldInfoCache::get_component_id_for_type::<T>(world).expect("Component type not registered!");
-			// println!("Term: {}, Comp: {}", term_id, comp_id);
-			assert!(field_id == comp_id);
-
-			/* TODO - validate that the types actually match!!!!
-	#ifndef NDEBUG
-					ecs_assert(term_id & ECS_PAIR || term_id & ECS_SWITCH || 
-							term_id & ECS_CASE ||
-							term_id == _::cpp_type<T>::id(m_iter->world), 
-							ECS_COLUMN_TYPE_MISMATCH, NULL);
-	#endif
-			*/
+			let comp_id = WorldInfoCache::get_component_id_for_type::<T>(world)
+				.ok_or(FieldError::NotRegistered { index, expected: std::any::type_name::<T>() })?;
+			if field_id != comp_id && unsafe { ecs_get_typeid(world, field_id) } != comp_id {
+				return Err(FieldError::TypeMismatch {
+					index,
+					expected: std::any::type_name::<T>(),
+					found: unsafe { flecs_to_owned_string(ecs_id_str(world, field_id)) },
+				});
+			}
+
+			let size = std::mem::size_of::<T>();
+			let field_size = unsafe { ecs_field_size(self.it, index) } as usize;
+			if field_size != size {
+				return Err(FieldError::SizeMismatch { index, expected: size, found: field_size });
+			}
 
 			let mut count = self.count();
 
@@ -329,12 +713,13 @@ impl Iter {
 			if is_shared {
 					count = 1;
 			}
-			// println!("Term: {}, is_shared: {}, count: {}", term_id, is_shared, count);
 
-			let size = std::mem::size_of::<T>();
 			let array = unsafe { ecs_field_w_size(self.it, size as size_t, index) as *mut T };
+			if array.is_null() && size != 0 {
+				return Err(FieldError::NoData { index });
+			}
 
-			Column::new(array, count, is_shared)
+			Ok(Column::new(array, count, is_shared))
     }
 
     pub fn field_dynamic(&self, index: i32) -> ColumnDynamic {
@@ -360,6 +745,38 @@ impl Iter {
     }	
 }
 
+/// Returned by Iter::try_field when a field can't be accessed as the requested type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+	/// Field indices start at 1 and go up to the number of fields
+	IndexOutOfRange { index: i32, field_count: i32 },
+	/// Not, optional (when not matched) and inout_none terms have no data
+	NoData { index: i32 },
+	/// T was never registered with the world, so it can't be the type of the field
+	NotRegistered { index: i32, expected: &'static str },
+	TypeMismatch { index: i32, expected: &'static str, found: String },
+	SizeMismatch { index: i32, expected: usize, found: usize },
+}
+
+impl std::fmt::Display for FieldError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FieldError::IndexOutOfRange { index, field_count } =>
+				write!(f, "Field {} is out of range, the iterator has {} fields (starting at 1)", index, field_count),
+			FieldError::NoData { index } =>
+				write!(f, "Field {} has no data, it is a Not, optional (see field_opt) or inout_none term", index),
+			FieldError::NotRegistered { index, expected } =>
+				write!(f, "Field {} can't be {}, which is not a registered component", index, expected),
+			FieldError::TypeMismatch { index, expected, found } =>
+				write!(f, "Field {} is {}, not {}", index, found, expected),
+			FieldError::SizeMismatch { index, expected, found } =>
+				write!(f, "Field {} has size {}, expected {}", index, found, expected),
+		}
+	}
+}
+
+impl std::error::Error for FieldError {}
+
 pub type SystemCallback = unsafe extern "C" fn(*mut ecs_iter_t);
 
 unsafe extern "C" fn trampoline<F>(it: *mut ecs_iter_t)
@@ -379,6 +796,39 @@ where
     func(it);
 }
 
+// Only ever borrows the closure shared, as the worker threads call it at the same time
+unsafe extern "C" fn trampoline_mt<F>(it: *mut ecs_iter_t)
+where
+    F: Fn(*mut ecs_iter_t) + Sync,
+{
+	if it.is_null() {
+		return;
+	}
+
+	let func_ptr = (*it).binding_ctx;
+	if func_ptr.is_null() {
+		return;
+	}
+
+    let func = &*(func_ptr as *const F);
+    func(it);
+}
+
+unsafe extern "C" fn free_closure<F>(ptr: *mut c_void) {
+	drop(Box::from_raw(ptr as *mut F));
+}
+
+// The type id lives in front of the value so that Iter::ctx can check it before casting
+#[repr(C)]
+struct SystemCtx<C> {
+	type_id: TypeId,
+	value: C,
+}
+
+unsafe extern "C" fn free_ctx<C>(ptr: *mut c_void) {
+	drop(Box::from_raw(ptr as *mut SystemCtx<C>));
+}
+
 // we have to wrap system callback functions in a trampoline
 // so that we can access it again within the C callback handler
 fn get_trampoline<F>(_closure: &F) -> SystemCallback
@@ -423,6 +873,19 @@ impl<T: Component> Column<T> {
 			value.as_mut().unwrap()
 		}
 	}
+
+	/// A shared column only holds a single value, which applies to all iterated entities
+	pub fn is_shared(&self) -> bool {
+		self.is_shared
+	}
+
+	// Tags (zero sized components) have no storage, slices need a non null pointer
+	fn as_ptr(&self) -> *mut T {
+		if self.array.is_null() {
+			return std::ptr::NonNull::dangling().as_ptr();
+		}
+		self.array
+	}
 }
 
 pub struct ColumnDynamic {
@@ -0,0 +1,93 @@
+use crate::*;
+use crate::cache::WorldInfoCache;
+use std::marker::PhantomData;
+
+// Raw table access
+//
+// A table stores all entities with the same set of components, each component in a
+// contiguous column. Exposing the columns as slices lets integrations (e.g. physics)
+// hand the memory to other libraries without copies.
+
+/// The table of an iterator result, see Iter::table. Covers all entities of the table,
+/// which may be more than the current result, e.g. with a paged or worker iterator.
+///
+/// The slices point into the table storage and are only valid while the table doesn't
+/// change structurally: adding or removing entities, or components of its entities,
+/// moves the data and invalidates them. This can't happen during iteration, as flecs
+/// defers these operations until the iteration ends.
+pub struct Table<'a> {
+	world: *mut ecs_world_t,
+	table: *mut ecs_table_t,
+	entities: *const ecs_entity_t,
+	_phantom: PhantomData<&'a mut Iter>,
+}
+
+impl<'a> Table<'a> {
+	pub(crate) fn new(world: *mut ecs_world_t, table: *mut ecs_table_t, entities: *const ecs_entity_t) -> Self {
+		Self { world, table, entities, _phantom: PhantomData }
+	}
+
+	pub fn raw(&self) -> *mut ecs_table_t {
+		self.table
+	}
+
+	/// The number of entities in the table
+	pub fn count(&self) -> usize {
+		if self.table.is_null() {
+			return 0;
+		}
+		unsafe { ecs_table_count(self.table) as usize }
+	}
+
+	/// The ids of the entities in the table, the entity of row i is at index i
+	pub fn entities(&self) -> &[EntityId] {
+		if self.entities.is_null() {
+			return &[];
+		}
+		unsafe { std::slice::from_raw_parts(self.entities, self.count()) }
+	}
+
+	/// The component ids of the table
+	pub fn type_ids(&self) -> &[EntityId] {
+		if self.table.is_null() {
+			return &[];
+		}
+		unsafe {
+			let ids = ecs_table_get_type(self.table);
+			if ids.is_null() || (*ids).count == 0 {
+				return &[];
+			}
+			std::slice::from_raw_parts((*ids).array, (*ids).count as usize)
+		}
+	}
+
+	/// Returns the column of component T with a value per entity, or None if the table
+	/// doesn't have T
+	pub fn column<T: Component>(&self) -> Option<&[T]> {
+		self.column_ptr::<T>().map(|column| unsafe { std::slice::from_raw_parts(column, self.count()) })
+	}
+
+	/// Same as column, mutable
+	pub fn column_mut<T: Component>(&mut self) -> Option<&mut [T]> {
+		self.column_ptr::<T>().map(|column| unsafe { std::slice::from_raw_parts_mut(column, self.count()) })
+	}
+
+	fn column_ptr<T: Component>(&self) -> Option<*mut T> {
+		if self.table.is_null() {
+			return None;
+		}
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world)?;
+		let index = unsafe { ecs_search(self.world, self.table, comp_id, std::ptr::null_mut()) };
+		if index == -1 {
+			return None;
+		}
+		if std::mem::size_of::<T>() == 0 {
+			// tags have no storage
+			return Some(std::ptr::NonNull::dangling().as_ptr());
+		}
+
+		let column = unsafe { ecs_table_get_column(self.table, index) } as *mut T;
+		assert!(!column.is_null());
+		Some(column)
+	}
+}
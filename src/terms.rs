@@ -8,6 +8,7 @@ pub trait TermBuilder : Sized {
 	fn world(&mut self) -> *mut ecs_world_t;
 	fn filter_desc(&mut self) -> &mut ecs_filter_desc_t;
 	fn current_term(&mut self) -> &mut ecs_term_t;
+	fn last_term(&mut self) -> &mut ecs_term_t;
 	fn next_term(&mut self);
 
 	// This is not working right
@@ -43,6 +44,89 @@ pub trait TermBuilder : Sized {
 		self
 	}
 
+	/// Marks the last term as read only, which lets the scheduler run the system
+	/// alongside other systems that read the component
+	fn in_(mut self) -> Self {
+		self.last_term().inout = ecs_inout_kind_t_EcsIn;
+		self
+	}
+
+	/// Marks the last term as write only
+	fn out(mut self) -> Self {
+		self.last_term().inout = ecs_inout_kind_t_EcsOut;
+		self
+	}
+
+	/// Marks the last term as read & write
+	fn inout(mut self) -> Self {
+		self.last_term().inout = ecs_inout_kind_t_EcsInOut;
+		self
+	}
+
+	/// The last term only filters, its data is not accessed and Iter::field panics for it
+	fn inout_none(mut self) -> Self {
+		self.last_term().inout = ecs_inout_kind_t_EcsInOutNone;
+		self
+	}
+
+	/// Negates the last term, entities with the component are not matched. Like without(),
+	/// a Not term has no data, so Iter::field panics for it.
+	fn not(mut self) -> Self {
+		self.last_term().oper = ecs_oper_kind_t_EcsNot;
+		self
+	}
+
+	/// Chains the last term and the next one with Or, e.g. term::<Dog>().or().term::<Cat>()
+	/// matches entities with Dog or Cat. A chain of Or terms makes up a single field,
+	/// Iter::field_id tells which of the terms matched.
+	fn or(mut self) -> Self {
+		self.last_term().oper = ecs_oper_kind_t_EcsOr;
+		self.current_term().oper = ecs_oper_kind_t_EcsOr;
+		self
+	}
+
+	/// Makes the last term optional, entities without the component are still matched.
+	/// Use Iter::field_opt to access the field.
+	fn optional(mut self) -> Self {
+		self.last_term().oper = ecs_oper_kind_t_EcsOptional;
+		self
+	}
+
+	/// Matches the singleton A (the component set on its own entity) as a readonly term,
+	/// accessible through Iter::singleton.
+	///
+	/// This makes the whole filter instanced (see instanced), so other shared fields, e.g.
+	/// inherited from a prefab, then hold a single value for all entities of a result.
+	fn term_singleton<A: Component>(self) -> Self {
+		self.singleton_term::<A>(ecs_inout_kind_t_EcsIn)
+	}
+
+	/// Matches the singleton A as a writable term, accessible through Iter::singleton_mut.
+	/// Makes the filter instanced, see term_singleton.
+	fn term_singleton_mut<A: Component>(self) -> Self {
+		self.singleton_term::<A>(ecs_inout_kind_t_EcsInOut)
+	}
+
+	#[doc(hidden)]
+	fn singleton_term<A: Component>(mut self, inout: ecs_inout_kind_t) -> Self {
+		let world_raw = self.world();
+		let term = self.current_term();
+
+		let comp_id = WorldInfoCache::get_component_id_for_type::<A>(world_raw)
+			.expect("Component type not registered!");
+		term.id = comp_id;
+		term.src.id = comp_id;
+		term.inout = inout;
+
+		// Without instancing flecs would split every table into single entity iterations,
+		// since the singleton field is shared. Documented on term_singleton, as this changes
+		// how other shared fields are returned.
+		self.filter_desc().instanced = true;
+
+		self.next_term();
+		self
+	}
+
 	fn term_dynamic(mut self, comp_id: EntityId) -> Self {
 		// TODO - validate that the comp_id passed is valid
 		let term = self.current_term();
@@ -51,9 +135,253 @@ pub trait TermBuilder : Sized {
 		self
 	}
 
+	fn term_id<T: AsEcsId>(mut self, id: T) -> Self {
+		let term = self.current_term();
+		term.id = id.id();
+		self.next_term();
+		self
+	}
+
+	fn term_pair<R: AsEcsId, T: AsEcsId>(self, relation: R, target: T) -> Self {
+		let pair = unsafe { ecs_make_pair(relation.id(), target.id()) };
+		self.term_id(pair)
+	}
+
+	// Modifiers below apply to the last added term, e.g. .term::<Transform>().parent()
+
+	/// Matches the last term on a fixed entity instead of on the iterated entities
+	fn src<T: AsEcsId>(mut self, entity: T) -> Self {
+		let term = self.last_term();
+		term.src.id = entity.id();
+		term.src.flags |= EcsIsEntity;
+		self
+	}
+
+	/// Same as src, with the entity looked up by its path
+	fn src_name(mut self, name: &str) -> Self {
+		let world = self.world();
+		let c_str = std::ffi::CString::new(name).unwrap();
+		let sep = NAME_SEP.as_ptr();
+		let entity = unsafe { ecs_lookup_path_w_sep(world, 0, c_str.as_ptr(), sep, sep, true) };
+		assert!(entity != 0, "No entity found for term source: {name}");
+		self.src(entity)
+	}
+
+	/// Matches the last term on the parent (ChildOf target) of the iterated entities
+	fn parent(mut self) -> Self {
+		let term = self.last_term();
+		term.src.flags |= EcsParent;
+		self
+	}
+
+	/// Matches the last term on the iterated entities themselves. Combined with up()
+	/// the entity is checked first, then its parents.
+	fn self_(mut self) -> Self {
+		let term = self.last_term();
+		term.src.flags |= EcsSelf;
+		self
+	}
+
+	/// Matches the last term on the parents of the iterated entities, walking up the
+	/// ChildOf hierarchy until an entity with the component is found. The field is shared.
+	fn up(self) -> Self {
+		self.up_rel(unsafe { EcsChildOf })
+	}
+
+	/// Same as up, walking up the given (acyclic) relation instead of ChildOf
+	fn up_rel<T: AsEcsId>(mut self, relation: T) -> Self {
+		let term = self.last_term();
+		term.src.flags |= EcsUp;
+		term.src.trav = relation.id();
+		self
+	}
+
+	/// Same as up(), and orders the results breadth-first so that parents are iterated
+	/// before their children. Only supported by queries.
+	fn cascade(mut self) -> Self {
+		let term = self.last_term();
+		term.src.flags |= EcsCascade;
+		if term.src.trav == 0 {
+			term.src.trav = unsafe { EcsChildOf };
+		}
+		self
+	}
+
+	/// Sets the relation of the last term, turning it into a pair with second
+	fn first<T: AsEcsId>(mut self, first: T) -> Self {
+		let term = self.last_term();
+		split_term_id(term, false);
+		term.first.id = first.id();
+		self
+	}
+
+	/// Sets the target of the last term, turning it into a pair with the term id as relation
+	fn second<T: AsEcsId>(mut self, second: T) -> Self {
+		let term = self.last_term();
+		split_term_id(term, true);
+		term.second.id = second.id();
+		self
+	}
+
+	/// Adds a term for A and returns a Term to set its modifiers on, e.g.
+	/// .with::<Transform>().parent().end()
+	fn with<A: Component>(self) -> Term<Self> {
+		Term { builder: self.term::<A>() }
+	}
+
+	/// Same as with, for an id
+	fn with_term<T: AsEcsId>(self, id: T) -> Term<Self> {
+		Term { builder: self.term_id(id) }
+	}
+
+	/// Same as with, for the pair (relation, target)
+	fn with_pair<R: AsEcsId, T: AsEcsId>(self, relation: R, target: T) -> Term<Self> {
+		Term { builder: self.term_pair(relation, target) }
+	}
+
 	fn with_components<'c, G: ComponentGroup<'c>>(mut self) -> Self {
 		G::populate(&mut self);
 		self
 	}
 
+	/// Adds a term for a runtime id, e.g. a component registered with component_dynamic.
+	/// Its field is read with Iter::field_dynamic.
+	fn with_id(self, id: EntityId) -> Self {
+		self.term_id(id)
+	}
+
+	/// Adds a term for each of the runtime ids, in order
+	fn with_ids(mut self, ids: &[EntityId]) -> Self {
+		for &id in ids {
+			self = self.term_id(id);
+		}
+		self
+	}
+
+	/// By default flecs iterates entities one at a time when a field is shared (e.g. inherited
+	/// from a prefab), so that every field can be indexed the same way. Instanced iteration
+	/// keeps tables whole, shared fields then hold a single value, see Iter::is_self.
+	fn instanced(mut self, instanced: bool) -> Self {
+		self.filter_desc().instanced = instanced;
+		self
+	}
+
+	/// Also match entities that were disabled with Entity::disable()
+	fn with_disabled(mut self) -> Self {
+		self.filter_desc().flags |= EcsFilterMatchDisabled;
+		self
+	}
+
+	/// Also match prefabs, e.g. the ones created with World::prefab()
+	fn with_prefabs(mut self) -> Self {
+		self.filter_desc().flags |= EcsFilterMatchPrefab;
+		self
+	}
+
+	/// Adds EcsFilter* flags to the filter, e.g. EcsFilterMatchEmptyTables
+	fn query_flags(mut self, flags: u32) -> Self {
+		self.filter_desc().flags |= flags;
+		self
+	}
+
+}
+
+/// A term being built, returned by TermBuilder::with. The TermBuilder modifiers (src,
+/// parent, not, ...) apply to this term, end() returns the builder.
+pub struct Term<B: TermBuilder> {
+	builder: B,
+}
+
+impl<B: TermBuilder> Term<B> {
+	/// Returns the builder, to add other terms or build
+	pub fn end(self) -> B {
+		self.builder
+	}
+
+	/// Ends this term and adds the next one, see TermBuilder::with
+	pub fn with<A: Component>(self) -> Term<B> {
+		self.builder.with::<A>()
+	}
+
+	/// Ends this term and adds the next one, see TermBuilder::with_term
+	pub fn with_term<T: AsEcsId>(self, id: T) -> Term<B> {
+		self.builder.with_term(id)
+	}
+
+	/// Ends this term and adds the next one, see TermBuilder::with_pair
+	pub fn with_pair<R: AsEcsId, T: AsEcsId>(self, relation: R, target: T) -> Term<B> {
+		self.builder.with_pair(relation, target)
+	}
+}
+
+// The modifiers of the trait apply to the last term, which is this one
+impl<B: TermBuilder> TermBuilder for Term<B> {
+	fn world(&mut self) -> *mut ecs_world_t {
+		self.builder.world()
+	}
+
+	fn filter_desc(&mut self) -> &mut ecs_filter_desc_t {
+		self.builder.filter_desc()
+	}
+
+	fn current_term(&mut self) -> &mut ecs_term_t {
+		self.builder.current_term()
+	}
+
+	fn last_term(&mut self) -> &mut ecs_term_t {
+		self.builder.last_term()
+	}
+
+	fn next_term(&mut self) {
+		self.builder.next_term()
+	}
+}
+
+// Moves the id of a term into its first & second members, so that either can be replaced.
+// A plain id becomes the first member if is_first is set, else the second one.
+fn split_term_id(term: &mut ecs_term_t, is_first: bool) {
+	if term.id == 0 {
+		return;
+	}
+	if is_pair(term.id) {
+		term.first.id = pair_first(term.id);
+		term.second.id = pair_second(term.id);
+	} else if is_first {
+		term.first.id = term.id;
+	} else {
+		term.second.id = term.id;
+	}
+	term.id = 0;
+}
+
+// flecs 3.0 queries ignore the flags of their filter descriptor, an optional Prefab or
+// Disabled term makes them match those tables instead. The terms go after the others (or
+// at the end of the expr) so that the field indices don't change.
+pub(crate) fn add_match_flag_terms(desc: &mut ecs_filter_desc_t, mut next_term_index: usize, expr: &mut String) {
+	let tags = unsafe { [(EcsFilterMatchPrefab, EcsPrefab, "Prefab"), (EcsFilterMatchDisabled, EcsDisabled, "Disabled")] };
+	for (flag, tag, name) in tags {
+		if desc.flags & flag == 0 {
+			continue;
+		}
+		if !expr.is_empty() {
+			expr.push_str(", ?");
+			expr.push_str(name);
+			continue;
+		}
+		let term = &mut desc.terms[next_term_index];
+		term.id = tag;
+		term.oper = ecs_oper_kind_t_EcsOptional;
+		term.inout = ecs_inout_kind_t_EcsInOutNone;
+		next_term_index += 1;
+	}
+}
+
+// Annotates the terms a ComponentGroup populated with the access of each/each_mut,
+// unless they were annotated already
+pub(crate) fn set_default_inout(terms: &mut [ecs_term_t], inout: ecs_inout_kind_t) {
+	for term in terms {
+		if term.inout == ecs_inout_kind_t_EcsInOutDefault {
+			term.inout = inout;
+		}
+	}
 }
@@ -0,0 +1,59 @@
+use crate::*;
+
+// Timer addon support
+//
+// A timer is an entity with the EcsTimer & EcsTickSource components. Systems which use it as
+// their tick source only run on the frames where the timer fires, so several systems can share
+// a single timer and be paused together by stopping it.
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Timer {
+	world: *mut ecs_world_t,
+	id: ecs_entity_t,
+}
+
+impl AsEcsId for Timer {
+	fn id(&self) -> ecs_id_t {
+		self.id
+	}
+}
+
+impl Timer {
+	pub(crate) fn new(world: *mut ecs_world_t, id: ecs_entity_t) -> Self {
+		Timer { world, id }
+	}
+
+	pub fn entity(&self) -> Entity {
+		Entity::new(self.world, self.id)
+	}
+
+	/// Fires the timer every `interval` seconds
+	pub fn interval(self, interval: f32) -> Self {
+		unsafe { ecs_set_interval(self.world, self.id, interval) };
+		self
+	}
+
+	pub fn get_interval(&self) -> f32 {
+		unsafe { ecs_get_interval(self.world, self.id) }
+	}
+
+	/// Fires the timer once after `timeout` seconds
+	pub fn timeout(self, timeout: f32) -> Self {
+		unsafe { ecs_set_timeout(self.world, self.id, timeout) };
+		self
+	}
+
+	pub fn get_timeout(&self) -> f32 {
+		unsafe { ecs_get_timeout(self.world, self.id) }
+	}
+
+	/// Resumes the timer, restarting the count towards the next tick
+	pub fn start(&self) {
+		unsafe { ecs_start_timer(self.world, self.id) };
+	}
+
+	/// Pauses the timer. Systems driven by it stop running but stay enabled.
+	pub fn stop(&self) {
+		unsafe { ecs_stop_timer(self.world, self.id) };
+	}
+}
@@ -0,0 +1,114 @@
+use crate::*;
+
+// Units addon support
+//
+// Units are entities from the flecs.units module. They are only valid once the
+// module has been imported with World::import_units().
+
+/// A unit entity from the flecs.units module that can annotate a member
+pub trait Unit {
+	fn id(&self) -> EntityId;
+}
+
+macro_rules! units {
+	($($name:ident => $ecs:ident),* $(,)?) => {
+		$(
+			#[derive(Debug, Clone, Copy)]
+			pub struct $name;
+
+			impl Unit for $name {
+				fn id(&self) -> EntityId {
+					let id = unsafe { $ecs };
+					assert!(id != 0, "Units module is not imported, call World::import_units() first!");
+					id
+				}
+			}
+		)*
+	};
+}
+
+units! {
+	// Duration
+	PicoSeconds => EcsPicoSeconds,
+	NanoSeconds => EcsNanoSeconds,
+	MicroSeconds => EcsMicroSeconds,
+	MilliSeconds => EcsMilliSeconds,
+	Seconds => EcsSeconds,
+	Minutes => EcsMinutes,
+	Hours => EcsHours,
+	Days => EcsDays,
+
+	// Time
+	Date => EcsDate,
+
+	// Mass
+	Grams => EcsGrams,
+	KiloGrams => EcsKiloGrams,
+
+	// Electric current
+	Ampere => EcsAmpere,
+
+	// Amount
+	Mole => EcsMole,
+
+	// Luminous intensity
+	Candela => EcsCandela,
+
+	// Force
+	Newton => EcsNewton,
+
+	// Length
+	Meters => EcsMeters,
+	PicoMeters => EcsPicoMeters,
+	NanoMeters => EcsNanoMeters,
+	MicroMeters => EcsMicroMeters,
+	MilliMeters => EcsMilliMeters,
+	CentiMeters => EcsCentiMeters,
+	KiloMeters => EcsKiloMeters,
+	Miles => EcsMiles,
+
+	// Pressure
+	Pascal => EcsPascal,
+	Bar => EcsBar,
+
+	// Speed
+	MetersPerSecond => EcsMetersPerSecond,
+	KiloMetersPerSecond => EcsKiloMetersPerSecond,
+	KiloMetersPerHour => EcsKiloMetersPerHour,
+	MilesPerHour => EcsMilesPerHour,
+
+	// Temperature
+	Kelvin => EcsKelvin,
+	Celsius => EcsCelsius,
+	Fahrenheit => EcsFahrenheit,
+
+	// Data
+	Bits => EcsBits,
+	KiloBits => EcsKiloBits,
+	MegaBits => EcsMegaBits,
+	GigaBits => EcsGigaBits,
+	Bytes => EcsBytes,
+	KiloBytes => EcsKiloBytes,
+	MegaBytes => EcsMegaBytes,
+	GigaBytes => EcsGigaBytes,
+	KibiBytes => EcsKibiBytes,
+	MebiBytes => EcsMebiBytes,
+	GibiBytes => EcsGibiBytes,
+
+	// Data rate
+	BitsPerSecond => EcsBitsPerSecond,
+	KiloBitsPerSecond => EcsKiloBitsPerSecond,
+	MegaBitsPerSecond => EcsMegaBitsPerSecond,
+	GigaBitsPerSecond => EcsGigaBitsPerSecond,
+	BytesPerSecond => EcsBytesPerSecond,
+	KiloBytesPerSecond => EcsKiloBytesPerSecond,
+	MegaBytesPerSecond => EcsMegaBytesPerSecond,
+	GigaBytesPerSecond => EcsGigaBytesPerSecond,
+
+	// Other
+	Percentage => EcsPercentage,
+	Radians => EcsRadians,
+	Degrees => EcsDegrees,
+	Bel => EcsBel,
+	DeciBel => EcsDeciBel,
+}
@@ -11,8 +11,33 @@ pub struct World {
 impl World {
 	/// Creates a new Flecs World instance
 	pub fn new() -> Self {
-		let world = unsafe { ecs_init() };
+		os::on_world_created();
+		Self::init(unsafe { ecs_init() })
+	}
+
+	/// Creates a world without the builtin addon modules (systems, pipelines, timers,
+	/// meta, ...), which starts faster and uses less memory. Components, filters and
+	/// queries work as usual, the modules that are needed must be imported. Note that
+	/// progress() requires the pipeline module.
+	pub fn new_minimal() -> Self {
+		os::on_world_created();
+		Self::init(unsafe { ecs_mini() })
+	}
+
+	/// Creates a world with the command line arguments of the application. In this
+	/// version of flecs, the only argument used is the application name (args[0]), which
+	/// becomes the doc name of the flecs.core.World entity.
+	pub fn new_with_args(args: &[&str]) -> Self {
+		os::on_world_created();
+		let args_c: Vec<std::ffi::CString> = args.iter().map(|a| std::ffi::CString::new(*a).unwrap()).collect();
+		let mut argv: Vec<*mut ::std::os::raw::c_char> = args_c.iter().map(|a| a.as_ptr() as *mut _).collect();
+		Self::init(unsafe { ecs_init_w_args(argv.len() as i32, argv.as_mut_ptr()) })
+	}
+
+	fn init(world: *mut ecs_world_t) -> Self {
 		WorldInfoCache::insert(world);
+		#[cfg(any(feature = "log", feature = "tracing"))]
+		log::install_hook();
 		//init_builtin_components();
 		Self {
 			world,
@@ -31,20 +56,80 @@ impl World {
 		self.world
 	}
 
+	/// Creates an async stage, which records all operations (creating entities, setting
+	/// components, ...) in a command buffer instead of applying them, until merge() is
+	/// called on it. The stage is freed when the returned AsyncStage is dropped.
+	///
+	/// The stage can be moved to another thread, e.g. a background loader, see AsyncStage
+	/// for what the owner thread may do meanwhile. Use one async stage per thread.
+	pub fn async_stage(&self) -> AsyncStage {
+		let stage = unsafe { ecs_async_stage_new(self.world) };
+		AsyncStage {
+			stage: Self {
+				world: stage,
+				owned: true
+			}
+		}
+	}
+
+	/// Applies the operations recorded on an async stage to its world
+	pub fn merge(&self) {
+		unsafe { ecs_merge(self.world) }
+	}
+
+	/// Returns true if operations are deferred, e.g. while a system runs or on an async
+	/// stage, instead of being applied right away
+	pub fn is_deferred(&self) -> bool {
+		unsafe { ecs_is_deferred(self.world) }
+	}
+
+	/// Returns true if this is a stage of a world rather than the world itself
+	pub fn is_stage(&self) -> bool {
+		unsafe { !std::ptr::eq(ecs_get_world(self.world as *const ecs_poly_t), self.world) }
+	}
+
+	/// Creates a new entity. Inside Entity::scope() or Entity::with_rel() the
+	/// entity gets the scope parent or relation pair added.
 	pub fn entity(&self) -> Entity {
-		let entity = unsafe { ecs_new_id(self.world) };
+		let entity = unsafe { ecs_new_w_id(self.world, 0) };
 		Entity::new(self.world, entity)
 	}
 
+	/// Creates a new entity as a child of parent. Deleting the parent also deletes it.
+	pub fn entity_child_of<T: AsEcsId>(&self, parent: T) -> Entity {
+		self.entity().child_of(parent)
+	}
+
+	/// Creates an instance of prefab. The instance shares the prefab components and
+	/// gets its own copy of the prefab children.
+	pub fn instantiate(&self, prefab: Entity) -> Entity {
+		self.entity().is_a(prefab)
+	}
+
+	/// Iterates the direct children of parent
+	pub fn children(&self, parent: Entity) -> impl Iterator<Item = Entity> {
+		parent.children_iter()
+	}
+
 	pub fn prefab(&self, name: &str) -> Entity {
 		unsafe { 
-			let entity = ecs_new_id(self.world);
+			let entity = ecs_new_w_id(self.world, 0);
 			Entity::new(self.world, entity)
 				.named(name)
 				.add_id(EcsPrefab)
 		}
 	}
 
+	/// Creates a pipeline phase which runs after depends_on, usable with SystemBuilder::kind.
+	/// Phases at the same depth in the DependsOn chain (e.g. a phase depending on EcsOnUpdate
+	/// and EcsOnValidate) have their systems ordered by declaration.
+	pub fn create_phase<T: AsEcsId>(&self, name: &str, depends_on: T) -> Entity {
+		self.entity()
+			.named(name)
+			.add_id(unsafe { EcsPhase })
+			.add_relation_ids(unsafe { EcsDependsOn }, depends_on)
+	}
+
     pub fn progress(&self, delta_time: f32) -> bool {
         unsafe { ecs_progress(self.world, delta_time) }
     }	
@@ -69,14 +154,79 @@ impl World {
         unsafe { ecs_should_quit(self.world) }
     }
 
-	pub fn find_entity(&self, entity: EntityId) -> Option<Entity> {
-		let entity = Entity::new(self.world, entity);
+	/// Sets the level of the messages flecs logs, for all worlds. -1 (the default) logs
+	/// warnings and errors, 0 adds tracing and higher levels add debug messages.
+	/// Returns the previous level.
+	pub fn set_log_level(&self, level: i32) -> i32 {
+		unsafe { ecs_log_set_level(level) }
+	}
+
+	/// Sets the number of worker threads for systems built with each_mt or iter_mt
+	pub fn set_threads(&self, threads: i32) {
+		unsafe { ecs_set_threads(self.world, threads) }
+	}
+
+	/// Runs cb once while the world is destroyed (ecs_atfini). flecs runs it after the
+	/// root entities were deleted, before the remaining storage is freed.
+	pub fn on_destroy(&self, cb: impl FnOnce(&World) + 'static) {
+		let ctx = Box::into_raw(Box::new(Box::new(cb) as WorldCallback));
+		unsafe { ecs_atfini(self.world, Some(world_callback_trampoline), ctx as *mut ::std::os::raw::c_void) };
+	}
+
+	/// Runs cb once at the end of the current frame, after the systems have run and
+	/// their commands were merged. Callbacks still pending when the world is dropped
+	/// run right before it is destroyed.
+	pub fn run_post_frame(&self, cb: impl FnOnce(&World) + 'static) {
+		let ctx = Box::into_raw(Box::new(Box::new(cb) as WorldCallback));
+		WorldInfoCache::add_post_frame_action(self.world, ctx as u64);
+		unsafe { ecs_run_post_frame(self.world, Some(post_frame_trampoline), ctx as *mut ::std::os::raw::c_void) };
+	}
+
+	/// Runs cb every frame at the start of progress. The hook is a system without terms in
+	/// the OnLoad phase, so it also runs when ecs_progress is called directly. Hooks run in
+	/// registration order, together with the other OnLoad systems. Destruct the returned
+	/// system to remove the hook.
+	pub fn on_frame_begin(&self, mut cb: impl FnMut(&World) + 'static) -> System {
+		self.system().on_load().iter(move |it| cb(&it.world()))
+	}
+
+	/// Runs cb every frame at the end of progress, as a system in the OnStore phase.
+	/// See on_frame_begin.
+	pub fn on_frame_end(&self, mut cb: impl FnMut(&World) + 'static) -> System {
+		self.system().on_store().iter(move |it| cb(&it.world()))
+	}
+
+	pub fn find_entity(&self, entity: impl AsEcsId) -> Option<Entity> {
+		let entity = Entity::new(self.world, entity.id());
 		if entity.is_valid() {
 			return Some(entity);
 		}
 		None
 	}
 
+	pub fn delete(&self, entity: Entity) {
+		entity.destruct();
+	}
+
+	pub fn delete_by_id(&self, entity: impl AsEcsId) {
+		unsafe { ecs_delete(self.world, entity.id()) };
+	}
+
+	/// Returns true if this exact generation of the id currently exists
+	pub fn is_alive(&self, entity: impl AsEcsId) -> bool {
+		unsafe { ecs_is_alive(self.world, entity.id()) }
+	}
+
+	/// Returns the alive entity for an id, recovering the current generation
+	/// when the id was stripped of it (e.g. received over the network).
+	pub fn get_alive(&self, entity: impl AsEcsId) -> Option<Entity> {
+		let alive = unsafe { ecs_get_alive(self.world, entity.id()) };
+		if alive != 0 {
+			return Some(Entity::new(self.world, alive));
+		}
+		None
+	}
+
 	pub fn lookup(&self, name: &str) -> Option<Entity> {
 		let name_c_str = std::ffi::CString::new(name).unwrap();
 		let sep = NAME_SEP.as_ptr() as *const i8;
@@ -96,6 +246,23 @@ impl World {
 		None
 	}
 
+	/// Looks up an entity by its symbol, which for components is the name they were
+	/// registered with and stays the same across runs. Nothing is created on a miss.
+	pub fn lookup_symbol(&self, symbol: &str) -> Option<Entity> {
+		let symbol_c_str = std::ffi::CString::new(symbol).unwrap();
+		let entity = unsafe { ecs_lookup_symbol(self.world, symbol_c_str.as_ptr(), false) };
+		if entity > 0 {
+			return Some(Entity::new(self.world, entity));
+		}
+		None
+	}
+
+	/// Sets a global alias for the entity, which lookup() resolves like a root name
+	pub fn set_alias(&self, entity: Entity, alias: &str) {
+		let alias_c_str = std::ffi::CString::new(alias).unwrap();
+		unsafe { ecs_set_alias(self.world, entity.raw(), alias_c_str.as_ptr()) };
+	}
+
 	pub fn name(&self, entity: Entity) -> &str {
 		let name_str = unsafe { ecs_get_name(self.world, entity.raw()) };
 		unsafe { flecs_to_rust_str(name_str) }
@@ -154,6 +321,23 @@ impl World {
 		Some(unsafe { (value as *const T).as_ref().unwrap() })
 	}
 
+	/// See Entity::get_mut(), the reference must not be held across structural changes.
+	/// Prefer modify(), which bounds the borrow and runs OnSet observers.
+	pub fn get_mut<T: Component>(&mut self, mut entity: Entity) -> Option<&mut T> {
+		let value = entity.get_mut::<T>()? as *mut T;
+		unsafe { value.as_mut() }
+	}
+
+	/// Mutates the component of entity with f and signals the change, see Entity::update().
+	/// Returns false if the entity doesn't have the component.
+	pub fn modify<T: Component>(&self, entity: Entity, f: impl FnOnce(&mut T)) -> bool {
+		entity.update::<T>(f)
+	}
+
+	pub fn has<T: Component>(&self, entity: Entity) -> bool {
+		entity.has::<T>()
+	}
+
 	pub fn add<T: Component>(&self, entity: Entity) {
         // flecs_static_assert(is_flecs_constructible<T>::value,
         //     "cannot default construct type: add T::T() or use emplace<T>()");
@@ -166,9 +350,23 @@ impl World {
 		let dest = unsafe { ecs_get_mut_id(self.world, entity.raw(), comp_id) } ;
 		let dest = unsafe { (dest as *mut T).as_mut().unwrap() };
 		*dest = value;
+		unsafe { ecs_modified_id(self.world, entity.raw(), comp_id) };
+	}
+
+	/// Returns the id of the pair (rel, target), which can be passed to set_component,
+	/// read_component and write_component when the pair has data
+	pub fn pair(&self, rel: impl AsEcsId, target: impl AsEcsId) -> PairId {
+		PairId::new(rel, target)
+	}
+
+	#[deprecated(note = "use World::pair, which returns a PairId")]
+	pub fn pair_id(&self, rel: EntityId, target: EntityId) -> EntityId {
+		ecs_pair(rel, target)
 	}
 
-	pub fn set_component(&self, entity: EntityId, comp: EntityId, data: &[u8]) {
+	/// comp may also be a pair with data, see pair
+	pub fn set_component(&self, entity: impl AsEcsId, comp: impl AsEcsId, data: &[u8]) {
+		let (entity, comp) = (entity.id(), comp.id());
 		let info = get_component_info(self.world, comp).expect("Component type not registered!");
 		let dest = unsafe { 
 			let ptr = ecs_get_mut_id(self.world, entity, comp) as *mut u8;
@@ -183,7 +381,8 @@ impl World {
 		}
 	}
 
-	pub fn read_component(&self, entity: EntityId, comp: EntityId) -> Option<&[u8]> {
+	pub fn read_component(&self, entity: impl AsEcsId, comp: impl AsEcsId) -> Option<&[u8]> {
+		let (entity, comp) = (entity.id(), comp.id());
 		let info = get_component_info(self.world, comp).expect("Component type not registered!");
 
 		let entity_valid = unsafe { ecs_is_valid(self.world, entity) };
@@ -203,7 +402,8 @@ impl World {
 		Some(src)
 	}
 
-	pub fn write_component<F: FnMut(&mut [u8])>(&self, entity: EntityId, comp: EntityId, mut writer: F) {
+	pub fn write_component<F: FnMut(&mut [u8])>(&self, entity: impl AsEcsId, comp: impl AsEcsId, mut writer: F) {
+		let (entity, comp) = (entity.id(), comp.id());
 		let info = get_component_info(self.world, comp).expect("Component type not registered!");
 		let dest = unsafe { 
 			let ptr = ecs_get_mut_id(self.world, entity, comp) as *mut u8;
@@ -223,6 +423,13 @@ impl World {
 		None
 	}
 
+	/// The id of a registered component, panics if T is not registered
+	pub fn component_raw_id<T: Component>(&self) -> RawId {
+		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
+		RawId::new(comp_id).unwrap()
+	}
+
+	#[deprecated(note = "use World::component_raw_id, which returns a RawId")]
     pub fn component_id<T: Component>(&mut self) -> u64  {
 		let comp_id = WorldInfoCache::get_component_id_for_type::<T>(self.world).expect("Component type not registered!");
 		comp_id
@@ -233,16 +440,85 @@ impl World {
 		Entity::new(self.world, comp_id)
 	}
 
+	/// Registers a component using the name and symbol from its ComponentType impl. Later
+	/// component::<T>() calls, in any world, use them as well. Before that, component::<T>()
+	/// names the type after the Rust type name.
+	pub fn component_type<T: ComponentType>(&mut self) -> Entity {
+		let comp_id = register_component_type::<T>(self.world);
+		Entity::new(self.world, comp_id)
+	}
+
 	pub fn component_named<T: 'static>(&mut self, name: &str) -> EntityId {
 		register_component_typed::<T>(self.world, Some(name))
 	}
 
-	pub fn component_dynamic(&mut self, symbol: &'static str, layout: Layout) -> EntityId {
-		register_component_dynamic(self.world, symbol, None, layout)
+	/// Registers R as an exclusive relation, an entity then has at most one (R, *) pair.
+	/// Adding another pair replaces the previous one, see Entity::set_state.
+	pub fn component_exclusive<R: 'static>(&mut self) -> Entity {
+		let relation = self.component::<R>();
+		unsafe { ecs_add_id(self.world, relation.raw(), EcsExclusive) };
+		relation
+	}
+
+	/// Registers Pair<R, T> for ComponentGroup tuples, e.g. filter::<(Position, Pair<R, T>)>().
+	/// R and T must be registered already. Returns the pair id.
+	pub fn component_pair<R: PairData<T>, T: Component>(&mut self) -> EntityId {
+		let pair = pair_term_id::<R, T>(self.world);
+		WorldInfoCache::register_component_id_for_type_id(self.world, pair, std::any::TypeId::of::<Pair<R, T>>());
+		pair
 	}
 
-	pub fn component_dynamic_named(&mut self, symbol: &'static str, name: &'static str, layout: Layout) -> EntityId {
-		register_component_dynamic(self.world, symbol, Some(name), layout)
+	/// Registers a component from its runtime layout
+	///
+	/// # Panics
+	/// If the alignment of layout is above MAX_DYNAMIC_COMPONENT_ALIGN. Use
+	/// component_dynamic_with_hooks with the default hooks to get the error instead.
+	pub fn component_dynamic(&mut self, symbol: &str, layout: Layout) -> EntityId {
+		register_component_dynamic(self.world, symbol, None, layout, None).unwrap_or_else(|err| panic!("{}", err))
+	}
+
+	/// Same as component_dynamic, with a name that differs from the symbol
+	///
+	/// # Panics
+	/// Same as component_dynamic
+	pub fn component_dynamic_named(&mut self, symbol: &str, name: &str, layout: Layout) -> EntityId {
+		register_component_dynamic(self.world, symbol, Some(name), layout, None).unwrap_or_else(|err| panic!("{}", err))
+	}
+
+	/// Registers a dynamic component whose values own resources that need
+	/// custom construction, copying, or cleanup. Fails for layouts with an alignment above
+	/// MAX_DYNAMIC_COMPONENT_ALIGN, which the table storage can't guarantee.
+	pub fn component_dynamic_with_hooks(&mut self, symbol: &str, name: Option<&str>, layout: Layout, hooks: DynamicComponentHooks) -> Result<EntityId, DynamicComponentError> {
+		register_component_dynamic(self.world, symbol, name, layout, Some(hooks))
+	}
+
+	pub fn pipeline(&self) -> PipelineBuilder<'_> {
+		PipelineBuilder::new(self)
+	}
+
+	/// Sets the pipeline ran by progress()
+	pub fn set_pipeline(&self, pipeline: Entity) {
+		unsafe { ecs_set_pipeline(self.world, pipeline.raw()) };
+	}
+
+	pub fn get_pipeline(&self) -> Entity {
+		Entity::new(self.world, unsafe { ecs_get_pipeline(self.world) })
+	}
+
+	/// Runs the systems of a pipeline once, independent of the one ran by progress().
+	/// Unlike progress() this doesn't advance timers or the world time.
+	pub fn run_pipeline(&self, pipeline: Entity, delta_time: f32) {
+		unsafe { ecs_run_pipeline(self.world, pipeline.raw(), delta_time) };
+	}
+
+	/// Creates a timer entity which can drive one or more systems through SystemBuilder::tick_source
+	pub fn timer(&self) -> Timer {
+		let id = unsafe { ecs_new_w_id(self.world, 0) };
+		Timer::new(self.world, id)
+	}
+
+	pub fn observer(&self) -> ObserverBuilder<'_> {
+		ObserverBuilder::new(self)
 	}
 
 	pub fn system(&self) -> SystemBuilder {
@@ -265,56 +541,236 @@ impl World {
         builder
     }	
 
+	pub fn rule(&self) -> RuleBuilder<'_> {
+		RuleBuilder::new(self)
+	}
+
 	// Iterate through all entities matching 1 component
 	// TODO: can eliminate this in favor of more general each() once I can fix the 
 	// single macro issues
+	/// The filter is created on the first call and reused until the world is destroyed
 	pub fn each1<A: Component>(&self, mut cb: impl FnMut(Entity, &A)) {
-		let filter = Filter::new_1::<A>(self.raw());
+		let filter = Filter::cached_1::<A>(self.raw());
 		filter.each_1(|e: Entity, a: &A| {
 			cb(e, a);
 		});
 	}
 
-	// Rust compiler will not let is use these short forms, perhaps we can solve the errors
-	//
+	/// Iterates all entities with id (a component, tag or pair). Unlike filters, a single
+	/// term iterator has nothing to construct, which makes this the fastest way to visit
+	/// the entities of one id.
+	pub fn each_id<T: AsEcsId>(&self, id: T, mut cb: impl FnMut(Entity)) {
+		let mut term: ecs_term_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		term.id = id.id();
+		unsafe {
+			defer_iter(self.world, || {
+				let mut it = ecs_term_iter(self.world, &mut term);
+				while ecs_term_next(&mut it) {
+					for i in 0..it.count as isize {
+						cb(Entity::new(self.world, *it.entities.offset(i)));
+					}
+				}
+			});
+		}
+	}
+
+	/// Same as each1, iterating with a single term iterator instead of a filter
+	pub fn each_term<A: Component>(&self, mut cb: impl FnMut(Entity, &A)) {
+		let mut term: ecs_term_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		term.id = WorldInfoCache::get_component_id_for_type::<A>(self.world).expect("Component type not registered!");
+		unsafe {
+			defer_iter(self.world, || {
+				let mut it = ecs_term_iter(self.world, &mut term);
+				while ecs_term_next(&mut it) {
+					let a = ecs_field::<A>(&it, 1);
+					// inherited components are shared by all entities of the result
+					let stride = if ecs_field_is_self(&it, 1) { 1 } else { 0 };
+					for i in 0..it.count as isize {
+						let e = Entity::new(self.world, *it.entities.offset(i));
+						cb(e, &*a.offset(i * stride));
+					}
+				}
+			});
+		}
+	}
+
+	/// Returns the first entity with component A for which pred returns true, iteration
+	/// stops there
+	pub fn find_with<A: Component>(&self, pred: impl Fn(&A) -> bool) -> Option<Entity> {
+		let mut term: ecs_term_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		term.id = WorldInfoCache::get_component_id_for_type::<A>(self.world).expect("Component type not registered!");
+		unsafe {
+			let mut it = ecs_term_iter(self.world, &mut term);
+			while ecs_term_next(&mut it) {
+				let a = ecs_field::<A>(&it, 1);
+				let stride = if ecs_field_is_self(&it, 1) { 1 } else { 0 };
+				for i in 0..it.count as isize {
+					if pred(&*a.offset(i * stride)) {
+						let e = Entity::new(self.world, *it.entities.offset(i));
+						ecs_iter_fini(&mut it);
+						return Some(e);
+					}
+				}
+			}
+		}
+		None
+	}
+
 	pub fn each<'a, G: ComponentGroup<'a>>(&'a self, cb: impl FnMut(Entity, G::RefTuple)) {
-		let filter: FilterGroup<'a, G> = FilterGroup::new(self);
+		let filter: FilterGroup<'a, G> = FilterGroup::new_inout(self, ecs_inout_kind_t_EcsIn);
 		filter.each(cb);
-    }	
+	}
+
+	/// Calls cb for every entity with the elements of G, e.g. (&mut Position, &Velocity,
+	/// Option<&Target>). Option elements also match entities without the component.
+	pub fn for_each<'a, G: EachGroup<'a>>(&'a self, mut cb: impl FnMut(Entity, G::Item)) {
+		let mut desc: ecs_filter_desc_t = unsafe { MaybeUninit::zeroed().assume_init() };
+		G::apply_terms(self.world, &mut desc.terms[..G::COUNT]);
+		unsafe {
+			let filter = ecs_filter_init(self.world, &desc);
+			defer_iter(self.world, || {
+				let mut it = ecs_filter_iter(self.world, filter);
+				while ecs_filter_next(&mut it) {
+					each_group::<G>(&it, &mut cb);
+				}
+			});
+			ecs_filter_fini(filter);
+		}
+	}
 
+	#[deprecated(note = "use for_each with &mut elements, e.g. for_each::<(&mut A, &mut B)>")]
 	pub fn each_mut<'a, G: ComponentGroup<'a>>(&'a self, cb: impl FnMut(Entity, G::MutRefTuple)) {
-		let filter: FilterGroup<'a, G> = FilterGroup::new(self);
+		let filter: FilterGroup<'a, G> = FilterGroup::new_inout(self, ecs_inout_kind_t_EcsInOut);
 		filter.each_mut(cb);
     }	
 
 }
 
+/// An async stage of a world, see World::async_stage. Derefs to the World of the stage,
+/// on which operations are recorded.
+///
+/// Unlike World it can be sent to another thread. Flecs only makes creating entity ids
+/// safe between the threads, so while the stage is used on another thread the world must
+/// not be modified or progressed on its owner thread. Send the stage back (e.g. by joining
+/// the thread) and merge it there. Entities created on the stage are bound to it, pass
+/// their ids to the owner thread.
+pub struct AsyncStage {
+	stage: World,
+}
+
+// The stage only touches its own command buffer and the atomic id counter of the world,
+// the rules above keep the owner thread out of the way.
+unsafe impl Send for AsyncStage {}
+
+impl std::ops::Deref for AsyncStage {
+	type Target = World;
+
+	fn deref(&self) -> &World {
+		&self.stage
+	}
+}
+
 impl Drop for World {
 	fn drop(&mut self) {
 		unsafe {
 			if self.owned && ecs_stage_is_async(self.world) {
 				ecs_async_stage_free(self.world);
 			} else if self.owned && !self.world.is_null() {
+				// flecs drops the post frame actions of a destroyed world without running them
+				for ctx in WorldInfoCache::take_post_frame_actions(self.world) {
+					world_callback_trampoline(self.world, ctx as *mut ::std::os::raw::c_void);
+				}
+				WorldInfoCache::free_filters(self.world);
 				ecs_fini(self.world);
 			}
 		}
 	}
 }
 
+type WorldCallback = Box<dyn FnOnce(&World)>;
+
+unsafe extern "C" fn world_callback_trampoline(world: *mut ecs_world_t, ctx: *mut ::std::os::raw::c_void) {
+	let cb = Box::from_raw(ctx as *mut WorldCallback);
+	cb(&World::new_from(world));
+}
+
+unsafe extern "C" fn post_frame_trampoline(world: *mut ecs_world_t, ctx: *mut ::std::os::raw::c_void) {
+	if WorldInfoCache::remove_post_frame_action(world, ctx as u64) {
+		world_callback_trampoline(world, ctx);
+	}
+}
+
 // Additional Add-ons support
 impl World {
+	/// Imports the flecs.units module, which is not imported by default.
+	/// This must be done before using any of the units in the units module.
+	pub fn import_units(&self) {
+		let module_name = std::ffi::CString::new("FlecsUnits").unwrap();
+		unsafe { ecs_import_c(self.raw(), Some(FlecsUnitsImport), module_name.as_ptr()) };
+	}
+
+	/// Starts the REST server for the explorer on the default port. Like flecs, failures
+	/// to bind are logged, see enable_rest_with to detect them.
 	pub fn enable_rest(&self) {
-    let rest_comp_id = unsafe { FLECS__EEcsRest as u64 };
-		let rest_comp_size = std::mem::size_of::<EcsRest>() as size_t;
-		
-		let rest_data: EcsRest = unsafe { MaybeUninit::zeroed().assume_init() };
+		self.start_rest(&RestOptions::default());
+	}
 
-		unsafe { 
-			ecs_set_id(self.raw(), 
-				0, 
-				rest_comp_id, 
-				rest_comp_size, 
-				&rest_data as *const EcsRest as *const ::std::os::raw::c_void) 
+	/// Starts the REST server, replacing a running one. Fails if the address can't be bound.
+	///
+	/// Flecs binds on the server thread and only logs failures, so this checks the address
+	/// up front. A port taken by another process right after the check is only logged.
+	pub fn enable_rest_with(&self, options: RestOptions) -> std::io::Result<()> {
+		let port = if options.port == 0 { ECS_REST_DEFAULT_PORT as u16 } else { options.port };
+		let ipaddr = options.ipaddr.as_deref().unwrap_or("0.0.0.0");
+
+		self.disable_rest();
+		drop(std::net::TcpListener::bind((ipaddr, port))?);
+		self.start_rest(&options);
+		Ok(())
+	}
+
+	fn start_rest(&self, options: &RestOptions) {
+		self.disable_rest();
+		let c_ipaddr = options.ipaddr.as_ref().map(|ipaddr| std::ffi::CString::new(ipaddr.as_str()).unwrap());
+		let rest_data = EcsRest {
+			port: options.port,
+			ipaddr: c_ipaddr.as_ref().map_or(std::ptr::null_mut(), |ipaddr| ipaddr.as_ptr() as *mut _),
+			impl_: std::ptr::null_mut(),
 		};
+
+		// Set as singleton, the copy hook duplicates the address
+		unsafe {
+			ecs_set_id(self.raw(),
+				FLECS__EEcsRest,
+				FLECS__EEcsRest,
+				std::mem::size_of::<EcsRest>() as size_t,
+				&rest_data as *const EcsRest as *const ::std::os::raw::c_void)
+		};
+	}
+
+	/// Stops the REST server, if running
+	pub fn disable_rest(&self) {
+		unsafe { ecs_remove_id(self.raw(), FLECS__EEcsRest, FLECS__EEcsRest) };
 	}
+
+	/// Returns the options of the running REST server
+	pub fn rest_options(&self) -> Option<RestOptions> {
+		unsafe {
+			let rest = ecs_get_id(self.raw(), FLECS__EEcsRest, FLECS__EEcsRest) as *const EcsRest;
+			if rest.is_null() {
+				return None;
+			}
+			let ipaddr = if (*rest).ipaddr.is_null() { None } else { Some(flecs_to_rust_str((*rest).ipaddr).to_owned()) };
+			Some(RestOptions { port: (*rest).port, ipaddr })
+		}
+	}
+}
+
+/// Options for World::enable_rest_with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestOptions {
+	/// 0 uses the default port (27750)
+	pub port: u16,
+	/// None listens on all interfaces
+	pub ipaddr: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+// Counts the allocations of the default flecs os api, which are process wide, so this
+// runs in its own test binary
+use flecs::*;
+
+#[derive(Default)]
+struct A(f32);
+#[derive(Default)]
+struct B(f32);
+#[derive(Default)]
+struct C(f32);
+#[derive(Default)]
+struct D(f32);
+#[derive(Default)]
+struct E(f32);
+struct Group;
+
+fn live_allocations() -> i64 {
+	unsafe { ecs_os_api_malloc_count + ecs_os_api_calloc_count - ecs_os_api_free_count }
+}
+
+#[test]
+fn each_while_finalizes_iterator() {
+	let mut world = World::new();
+	world.component::<A>();
+	world.component::<B>();
+	world.component::<C>();
+	world.component::<D>();
+	world.component::<E>();
+	world.component::<Group>();
+
+	// many tables, each with a few entities
+	for _ in 0..20 {
+		let group = world.entity();
+		for _ in 0..5 {
+			world.entity()
+				.set(A::default()).set(B::default()).set(C::default()).set(D::default()).set(E::default())
+				.add_pair_second::<Group>(group);
+		}
+	}
+
+	// more than 4 terms, so the iterator allocates its field arrays
+	let mut filter = world.filter_builder()
+		.term::<A>().term::<B>().term::<C>().term::<D>().term::<E>()
+		.build();
+	let mut visited = 0;
+	filter.each_while::<(&A, &B, &C, &D, &E)>(|_, (a, b, c, d, e)| {
+		assert_eq!(a.0 + b.0 + c.0 + d.0 + e.0, 0.0);
+		visited += 1;
+		true
+	});
+	assert_eq!(visited, 100);
+
+	let before = live_allocations();
+	for stop_at in 0..100 {
+		let mut visited = 0;
+		filter.each_while::<&A>(|_, _| {
+			visited += 1;
+			visited <= stop_at
+		});
+		assert_eq!(visited, stop_at + 1);
+	}
+	assert_eq!(live_allocations(), before);
+}
@@ -0,0 +1,64 @@
+// The os api is process wide and must be set up before the first world, so this runs
+// in its own test binary
+use flecs::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+		System.alloc(layout)
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout)
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Default, Debug, PartialEq)]
+struct Position {
+	x: f32,
+	y: f32,
+}
+
+#[test]
+fn custom_os_api() {
+	os::use_rust_allocator().unwrap();
+	os::use_std_threads().unwrap();
+	assert_eq!(os::use_rust_allocator(), Err(os::OsApiError::AlreadySet));
+
+	let before = ALLOCATIONS.load(Ordering::SeqCst);
+	let mut world = World::new();
+	// flecs allocates hundreds of blocks while bootstrapping, none through its default malloc
+	assert!(ALLOCATIONS.load(Ordering::SeqCst) - before > 100);
+	assert_eq!(unsafe { ecs_os_api_malloc_count }, 0);
+	assert_eq!(os::use_std_threads(), Err(os::OsApiError::WorldExists));
+
+	// a multithreaded pipeline runs on std threads
+	world.component::<Position>();
+	for _ in 0..1000 {
+		world.entity().set(Position::default());
+	}
+	world.system()
+		.term::<Position>()
+		.iter_mt(|it| {
+			for p in it.field_slice_mut::<Position>(1) {
+				p.x += 1.0;
+			}
+		});
+	world.set_threads(4);
+	for _ in 0..3 {
+		world.progress(0.0);
+	}
+
+	let mut total = 0.0;
+	world.each1::<Position>(|_, p| total += p.x);
+	assert_eq!(total, 3000.0);
+}